@@ -77,14 +77,14 @@ async fn main_async(args: Vec<String>) -> Result<(), Box<dyn std::error::Error>>
                     std::process::exit(1);
                 }
                 let code = &args[2];
-                execute_code(code).await?;
+                execute_code(code, None).await?;
             }
             _ => {
                 // File mode: execute code from file
                 let file_path = &args[1];
                 let code = fs::read_to_string(file_path)
                     .map_err(|e| format!("Failed to read file '{}': {}", file_path, e))?;
-                execute_code(&code).await?;
+                execute_code(&code, Some(file_path)).await?;
             }
         }
     } else {
@@ -93,7 +93,7 @@ async fn main_async(args: Vec<String>) -> Result<(), Box<dyn std::error::Error>>
             // Read from stdin and execute
             let mut code = String::new();
             io::stdin().read_to_string(&mut code)?;
-            execute_code(&code).await?;
+            execute_code(&code, Some("<stdin>")).await?;
         } else {
             // No arguments and stdin is terminal - run REPL
             repl::run_repl().await?;
@@ -104,7 +104,7 @@ async fn main_async(args: Vec<String>) -> Result<(), Box<dyn std::error::Error>>
 }
 
 #[cfg(not(target_os = "none"))]
-async fn execute_code(code: &str) -> Result<(), Box<dyn std::error::Error>> {
+async fn execute_code(code: &str, source_name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
     use uni_core::evaluator::execute_string;
     use uni_core::interpreter::AsyncInterpreter;
 
@@ -118,8 +118,13 @@ async fn execute_code(code: &str) -> Result<(), Box<dyn std::error::Error>> {
     interp.load_prelude().await
         .map_err(|e| format!("Failed to load prelude: {}", e))?;
 
-    execute_string(code, &mut interp).await
-        .map_err(|e| format!("Error: {}", e))?;
+    // Name the source so runtime errors report file:line:column
+    interp.source_name = source_name.map(Into::into);
+
+    if let Err(e) = execute_string(code, &mut interp).await {
+        report_error(&e);
+        std::process::exit(1);
+    }
 
     Ok(())
 }
@@ -139,8 +144,10 @@ async fn execute_and_print(code: &str) -> Result<(), Box<dyn std::error::Error>>
     interp.load_prelude().await
         .map_err(|e| format!("Failed to load prelude: {}", e))?;
 
-    execute_string(code, &mut interp).await
-        .map_err(|e| format!("Error: {}", e))?;
+    if let Err(e) = execute_string(code, &mut interp).await {
+        report_error(&e);
+        std::process::exit(1);
+    }
 
     // Print the top value on the stack
     if let Some(value) = interp.stack.last() {
@@ -150,6 +157,12 @@ async fn execute_and_print(code: &str) -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+// Print a runtime error followed by the Uni call stack that led to it
+#[cfg(not(target_os = "none"))]
+pub(crate) fn report_error(e: &uni_core::RuntimeError) {
+    eprint!("{}", uni_core::repl::format_error(e, "\n"));
+}
+
 // STM32H753ZI USB interrupt bindings
 #[cfg(all(target_os = "none", feature = "target-stm32h753zi"))]
 embassy_stm32::bind_interrupts!(struct Irqs {
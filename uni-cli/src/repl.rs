@@ -14,6 +14,7 @@ pub async fn run_repl() -> Result<(), Box<dyn std::error::Error>> {
     println!(" \\___/|_| |_|_| v{}", env!("CARGO_PKG_VERSION"));
     println!();
     println!("Type `quit` or press Ctrl-D to exit");
+    println!("Press Ctrl-C to interrupt a running line");
     println!("Type `stack` to see the current stack");
     println!("Type `clear` to clear the stack");
     println!("Type `words` to see defined words");
@@ -25,6 +26,15 @@ pub async fn run_repl() -> Result<(), Box<dyn std::error::Error>> {
     let output = Box::new(StdoutOutput::new());
     interp.set_async_output(output);
 
+    // Ctrl-C while a line runs arrives as SIGINT (editline only sees it as a
+    // key while reading); it interrupts the line instead of killing the REPL
+    let cancel = interp.cancel.clone();
+    tokio::spawn(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            cancel.cancel();
+        }
+    });
+
     // Inject Linux time source for date/time operations
     // Load prelude (higher-level words defined in Uni)
     if let Err(e) = interp.load_prelude().await {
@@ -59,7 +69,8 @@ pub async fn run_repl() -> Result<(), Box<dyn std::error::Error>> {
                     continue;
                 }
 
-                // Execute the line
+                // Execute the line, forgetting any Ctrl-C pressed in between
+                interp.cancel.reset();
                 match execute_string(trimmed, &mut interp).await {
                     Ok(()) => {
                         // Success - optionally show stack
@@ -75,7 +86,7 @@ pub async fn run_repl() -> Result<(), Box<dyn std::error::Error>> {
                         }
                     }
                     Err(e) => {
-                        eprintln!("Error: {}", e);
+                        crate::report_error(&e);
                     }
                 }
             }
@@ -0,0 +1,277 @@
+// Benchmark: running defined words as compiled code vs walking their lists
+//
+// Run with: cargo bench -p uni-core --features std --bench definitions
+//
+// Each workload is run three ways on the same interpreter and definitions:
+// - "list walk" is the evaluator's dispatch loop from before bodies were
+//   compiled (see run_list_walker below)
+// - "compiled" is the real evaluator with the default backend
+// - "bytecode" is the real evaluator with Backend::Bytecode
+// All must leave the same result. Alongside the time per run we count heap
+// allocations, which is what matters most on the STM32. What the compiled
+// path still allocates comes from the words themselves (each builtin call
+// boxes its future), not from dispatch.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use uni_core::evaluator::execute;
+use uni_core::interpreter::{AsyncInterpreter, Backend, CallFrame};
+use uni_core::parser::parse;
+use uni_core::value::{RuntimeError, Value};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+struct Workload {
+    name: &'static str,
+    definitions: &'static str,
+    run: &'static str,
+    repeat: usize,
+}
+
+const WORKLOADS: &[Workload] = &[
+    Workload {
+        name: "countdown (tail calls)",
+        definitions: "'countdown [dup 0 <= [drop 0] [1 - countdown] if] def",
+        run: "10000 countdown",
+        repeat: 5,
+    },
+    Workload {
+        name: "fib 20 (nested calls)",
+        definitions: "'fib [dup 2 < [] [dup 1 - fib swap 2 - fib +] if] def",
+        run: "20 fib",
+        repeat: 5,
+    },
+    Workload {
+        name: "sum of squares (stack words)",
+        definitions: "'sumsq [dup 0 <= [drop] [dup dup * rot + swap 1 - sumsq] if] def",
+        run: "0 1000 sumsq",
+        repeat: 50,
+    },
+];
+
+// The evaluator's dispatch loop from before definitions were compiled, cut
+// down to what the workloads use: data, builtins, definitions and if. Every
+// call copies the body out of its cons list, every step clones the remaining
+// items, and every word takes the dictionary lock and hashes its name. Local
+// and call frames are kept as the evaluator keeps them; error locations are
+// left out, which only flatters this side.
+async fn run_list_walker(value: &Value, interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    enum Step {
+        Value(Value),
+        List { items: Vec<Value>, index: usize },
+        PopLocalFrame,
+        PopCallFrame,
+    }
+
+    fn list_to_vec(list: &Value) -> Vec<Value> {
+        let mut current = list.clone();
+        let mut items = Vec::new();
+        loop {
+            match current {
+                Value::Pair(car, cdr) => {
+                    items.push((*car).clone());
+                    current = (*cdr).clone();
+                }
+                Value::Nil => break,
+                other => {
+                    items.push(other);
+                    break;
+                }
+            }
+        }
+        items
+    }
+
+    let mut steps = vec![Step::Value(value.clone())];
+    while let Some(step) = steps.pop() {
+        match step {
+            Step::List { items, index } => {
+                if index >= items.len() {
+                    continue;
+                }
+                let item = items[index].clone();
+                if index + 1 < items.len() {
+                    steps.push(Step::List {
+                        items: items.clone(),
+                        index: index + 1,
+                    });
+                }
+                steps.push(Step::Value(item));
+            }
+            Step::Value(Value::Atom(name, _)) if &*name == "if" => {
+                let false_branch = interp.pop()?;
+                let true_branch = interp.pop()?;
+                let condition = interp.pop()?;
+                let branch = if interp.is_truthy(&condition) {
+                    true_branch
+                } else {
+                    false_branch
+                };
+                steps.push(Step::List {
+                    items: list_to_vec(&branch),
+                    index: 0,
+                });
+            }
+            Step::PopLocalFrame => {
+                interp.local_frames.pop();
+            }
+            Step::PopCallFrame => {
+                interp.call_frames.pop();
+            }
+            Step::Value(Value::Atom(name, _)) => {
+                if let Some(value) = interp
+                    .local_frames
+                    .iter()
+                    .rev()
+                    .find_map(|frame| frame.get(&name).cloned())
+                {
+                    interp.push(value);
+                    continue;
+                }
+                let entry = interp
+                    .dict_get(&name)
+                    .ok_or_else(|| RuntimeError::UndefinedWord(name.to_string()))?;
+                match entry.value {
+                    Value::AsyncBuiltin(func) => func(interp).await?,
+                    body @ (Value::Pair(_, _) | Value::Nil) if entry.is_executable => {
+                        let frame = CallFrame {
+                            word: name,
+                            pos: None,
+                            source: None,
+                            tail_calls: 0,
+                        };
+                        // A tail call takes over the caller's call frame
+                        if matches!(steps.last(), Some(Step::PopCallFrame)) {
+                            steps.pop();
+                            interp.call_frames.pop();
+                        }
+                        interp.call_frames.push(frame);
+                        interp.local_frames.push(HashMap::new());
+                        steps.push(Step::PopLocalFrame);
+                        steps.push(Step::PopCallFrame);
+                        steps.push(Step::List {
+                            items: list_to_vec(&body),
+                            index: 0,
+                        });
+                    }
+                    other => interp.push(other),
+                }
+            }
+            Step::Value(other) => interp.push(other),
+        }
+    }
+    Ok(())
+}
+
+struct Measurement {
+    time: Duration,
+    allocations: usize,
+    result: String,
+}
+
+// None runs the list walker, otherwise the evaluator with that backend
+async fn measure(
+    workload: &Workload,
+    interp: &mut AsyncInterpreter,
+    backend: Option<Backend>,
+) -> Result<Measurement, RuntimeError> {
+    let program = parse(workload.run, interp)?;
+    interp.stack.clear();
+
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..workload.repeat {
+        for value in &program {
+            match backend {
+                Some(backend) => {
+                    interp.backend = backend;
+                    execute(value, interp).await?;
+                }
+                None => run_list_walker(value, interp).await?,
+            }
+        }
+    }
+    let time = start.elapsed() / workload.repeat as u32;
+    let allocations = (ALLOCATIONS.load(Ordering::Relaxed) - allocations_before) / workload.repeat;
+
+    let result = interp.stack.last().map(|v| v.to_string()).unwrap_or_default();
+    interp.stack.clear();
+    Ok(Measurement {
+        time,
+        allocations,
+        result,
+    })
+}
+
+async fn run_benchmarks() -> Result<(), RuntimeError> {
+    println!(
+        "{:<30} {:>12} {:>12} {:>12} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "workload", "list walk", "compiled", "bytecode", "speedup/c", "speedup/b", "allocs/w", "allocs/c", "allocs/b"
+    );
+    for workload in WORKLOADS {
+        let mut interp = AsyncInterpreter::new();
+        interp.load_prelude().await?;
+        uni_core::evaluator::execute_string(workload.definitions, &mut interp).await?;
+
+        // Warm every path up (and fill the compiled code's caches) before timing
+        let paths = [None, Some(Backend::TreeWalker), Some(Backend::Bytecode)];
+        for backend in paths {
+            measure(workload, &mut interp, backend).await?;
+        }
+
+        let mut runs = Vec::new();
+        for backend in paths {
+            runs.push(measure(workload, &mut interp, backend).await?);
+        }
+        let [walk, compiled, bytecode] = &runs[..] else {
+            unreachable!("one run per path")
+        };
+        assert_eq!(walk.result, compiled.result, "{} gave different results", workload.name);
+        assert_eq!(walk.result, bytecode.result, "{} gave different results", workload.name);
+
+        // Speedups are over the list walk
+        let speedup = |m: &Measurement| walk.time.as_secs_f64() / m.time.as_secs_f64();
+        println!(
+            "{:<30} {:>12.2?} {:>12.2?} {:>12.2?} {:>9.2}x {:>9.2}x {:>10} {:>10} {:>10}",
+            workload.name,
+            walk.time,
+            compiled.time,
+            bytecode.time,
+            speedup(compiled),
+            speedup(bytecode),
+            walk.allocations,
+            compiled.allocations,
+            bytecode.allocations
+        );
+    }
+    Ok(())
+}
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("Failed to build runtime");
+    if let Err(error) = runtime.block_on(run_benchmarks()) {
+        eprintln!("benchmark failed: {}", error);
+        std::process::exit(1);
+    }
+}
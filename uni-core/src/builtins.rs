@@ -32,7 +32,9 @@ pub fn register_async_builtins(interp: &mut AsyncInterpreter) {
                 DictEntry {
                     value: Value::AsyncBuiltin(func),
                     is_executable: true,
-                    doc: doc.map(|s| Rc::<str>::from(s)),
+                    is_immediate: false,
+                    doc: doc.map(Rc::<str>::from),
+                    code: None,
                 },
             );
         }
@@ -43,15 +45,20 @@ pub fn register_async_builtins(interp: &mut AsyncInterpreter) {
                 DictEntry {
                     value: Value::AsyncBuiltin(func),
                     is_executable: true,
-                    doc: doc.map(|s| Rc::<str>::from(s)),
+                    is_immediate: false,
+                    doc: doc.map(Rc::<str>::from),
+                    code: None,
                 },
             );
         }
+        interp.dictionary_changed();
     };
 
     // Async I/O primitives
     add_builtin(interp, ".", crate::primitives::print::print_builtin,
         Some("Print the top stack item.\nUsage: value . => (prints value)\nExample: 42 . => 42"));
+    add_builtin(interp, "write", crate::primitives::write::write_builtin,
+        Some("Print a value as source text that read parses back unchanged.\nUsage: value write\nExample: \"hi\" write => \"hi\""));
     add_builtin(interp, "emit", crate::primitives::emit::emit_builtin,
         Some("Output a character from its numeric code.\nUsage: code emit\nExample: 65 emit => A"));
     add_builtin(interp, "words", crate::primitives::words::words_builtin,
@@ -64,7 +71,33 @@ pub fn register_async_builtins(interp: &mut AsyncInterpreter) {
     add_builtin(interp, "delay", crate::primitives::delay::delay,
         Some("Wait for N milliseconds while letting other tasks run.\nUsage: ms delay\nExample: 1000 delay => (waits 1 second)"));
     add_builtin(interp, "spawn", crate::primitives::spawn::spawn,
-        Some("Spawn a quotation as a background task.\nUsage: [code] spawn\nExample: [\"tick\" . cr 5000 delay] spawn"));
+        Some("Spawn a quotation as a background task and push its handle.\nUsage: [code] spawn => task\nExample: [\"tick\" . cr 5000 delay] spawn"));
+    add_builtin(interp, "task-join", crate::primitives::spawn::task_join,
+        Some("Wait for a task to finish and push its final stack as a list. Raises the task's error if it failed.\nUsage: task task-join => list\nExample: [2 3 +] spawn task-join => [5]"));
+    add_builtin(interp, "cancel", sync_builtin!(crate::primitives::spawn::cancel_impl),
+        Some("Interrupt a running task; it finishes as cancelled.\nUsage: task cancel\nExample: [[true] [] while] spawn cancel"));
+    add_builtin(interp, "task-status", sync_builtin!(crate::primitives::spawn::task_status_impl),
+        Some("Push the status of a task: \"running\", \"done\", \"failed\" or \"cancelled\".\nUsage: task task-status => string\nExample: [1] spawn dup task-join drop task-status => \"done\""));
+    add_builtin(interp, "tasks", crate::primitives::spawn::tasks_builtin,
+        Some("List the running tasks and the quotation each one runs.\nUsage: tasks"));
+    add_builtin(interp, "after", crate::primitives::timer::after,
+        Some("Run a quotation once in the background after N milliseconds and push its task handle.\nUsage: ms [code] after => task\nExample: 1000 [\"done\" . cr] after"));
+    add_builtin(interp, "every", crate::primitives::timer::every,
+        Some("Run a quotation in the background every N milliseconds until the timer is cancelled. Ticks keep to the original schedule however long each run takes.\nUsage: ms [code] every => task\nExample: 'ticker 500 [\"tick\" . cr] every val"));
+    add_builtin(interp, "make-channel", sync_builtin!(crate::primitives::channel::make_channel_impl),
+        Some("Create a channel that holds up to capacity values for tasks to pass between them.\nUsage: capacity make-channel => channel\nExample: 'jobs 10 make-channel val"));
+    add_builtin(interp, "send", crate::primitives::channel::send,
+        Some("Put a value on a channel, waiting while it is full.\nUsage: value channel send\nExample: 42 jobs send"));
+    add_builtin(interp, "receive", crate::primitives::channel::receive,
+        Some("Take the next value from a channel, waiting while it is empty.\nUsage: channel receive => value\nExample: jobs receive"));
+    add_builtin(interp, "try-receive", sync_builtin!(crate::primitives::channel::try_receive_impl),
+        Some("Take the next value from a channel if there is one, without waiting.\nUsage: channel try-receive => value true | null false\nExample: jobs try-receive [.] [drop] if"));
+    add_builtin(interp, "select", crate::primitives::select::select,
+        Some("Wait for the first of several events and run its handler. Each clause pairs a source with a handler: a channel (the handler gets the value received), a task (it gets the task's final stack) or a timeout in milliseconds.\nUsage: [[source [handler]] ...] select\nExample: [[inbox [. cr]] [500 [\"timeout\" . cr]]] select"));
+
+    // Exception handling (try itself is a special form in the evaluator)
+    add_builtin(interp, "throw", sync_builtin!(crate::primitives::throw::throw_impl),
+        Some("Raise a value as an exception, unwinding to the nearest try handler.\nUsage: value throw\nExample: [\"oops\" throw] [] try => \"oops\""));
 
     // Utility primitives
     add_builtin(interp, "help", crate::primitives::help::help_builtin,
@@ -77,7 +110,7 @@ pub fn register_async_builtins(interp: &mut AsyncInterpreter) {
     // Sync stack primitives (wrapped in async)
     // Note: swap, dup, over, rot are defined in the prelude using pick and roll
     add_builtin(interp, "drop", sync_builtin!(crate::primitives::stack::drop_impl),
-        Some("Remove the top stack item.\nUsage: value drop\nExample: 42 drop => (empty stack)"));
+        Some("Remove the top stack item. To drop the first n elements of a sequence, use skip.\nUsage: value drop\nExample: 42 drop => (empty stack)"));
     add_builtin(interp, "pick", sync_builtin!(crate::primitives::pick::pick_impl),
         Some("Copy the nth item from the stack to the top.\nUsage: ... n pick\nExample: 1 2 3 1 pick => 1 2 3 2"));
     add_builtin(interp, "roll", sync_builtin!(crate::primitives::roll::roll_impl),
@@ -115,11 +148,11 @@ pub fn register_async_builtins(interp: &mut AsyncInterpreter) {
 
     // Sync comparison primitives (wrapped in async)
     add_builtin(interp, "=", sync_builtin!(crate::primitives::equals::equals_impl),
-        Some("Test equality.\nUsage: a b = => bool\nExample: 5 5 = => true"));
+        Some("Test equality; lists, vectors and records compare by contents.\nUsage: a b = => bool\nExample: [1 2] [1 2] = => true"));
     add_builtin(interp, "!=", sync_builtin!(crate::primitives::not_equal::not_equal_impl),
         Some("Test inequality.\nUsage: a b != => bool\nExample: 5 3 != => true"));
     add_builtin(interp, "<", sync_builtin!(crate::primitives::less_than::less_than_impl),
-        Some("Test less than.\nUsage: a b < => bool\nExample: 3 7 < => true"));
+        Some("Test less than; works on any two values, e.g. strings and lists.\nUsage: a b < => bool\nExample: 3 7 < => true"));
     add_builtin(interp, ">", sync_builtin!(crate::primitives::greater_than::greater_than_impl),
         Some("Test greater than.\nUsage: a b > => bool\nExample: 7 3 > => true"));
     add_builtin(interp, "<=", sync_builtin!(crate::primitives::less_equal::less_equal_impl),
@@ -134,6 +167,10 @@ pub fn register_async_builtins(interp: &mut AsyncInterpreter) {
         Some("Define a constant value.\nUsage: 'name value val\nExample: 'pi 3.14159 val"));
     add_builtin(interp, "doc", sync_builtin!(crate::primitives::doc::doc_impl),
         Some("Add documentation to the last defined word.\nUsage: \"documentation\" doc"));
+    add_builtin(interp, "immediate", sync_builtin!(crate::primitives::immediate::immediate_impl),
+        Some("Make a defined word a macro: it runs on the code after it, as a list, and leaves the code to run instead.\nUsage: 'name immediate\nExample: 'when [dup cdr swap car [] 'if 3 list swap append] def 'when immediate"));
+    add_builtin(interp, "quasiquote", crate::primitives::quasiquote::quasiquote,
+        Some("Fill in a code template, usually written `[...]. ,x is replaced by the value x leaves, ,@x by the items of the list it leaves.\nUsage: `[... ,x ... ,@xs ...]\nExample: 2 3 `[,[+] *] => [5 *]"));
 
     // Variable primitives
     add_builtin(interp, "var", sync_builtin!(crate::primitives::var::var_impl),
@@ -142,7 +179,7 @@ pub fn register_async_builtins(interp: &mut AsyncInterpreter) {
         Some("Fetch value from a variable.\nUsage: var @ => value\nExample: counter @ => 0"));
     add_builtin(interp, "!", sync_builtin!(crate::primitives::store::store_impl),
         Some("Store value in a variable.\nUsage: value var !\nExample: 5 counter !"));
-    add_builtin(interp, "lval", sync_builtin!(crate::primitives::lval::lval_impl),
+    add_builtin(interp, "lval", crate::primitives::lval::lval_builtin,
         Some("Define a local constant value.\nUsage: 'name value lval"));
     add_builtin(interp, "lvar", sync_builtin!(crate::primitives::lvar::lvar_impl),
         Some("Create a local mutable variable.\nUsage: initial-value 'name lvar"));
@@ -214,6 +251,56 @@ pub fn register_async_builtins(interp: &mut AsyncInterpreter) {
     add_builtin(interp, "list->vector", sync_builtin!(crate::primitives::vector::list_to_vector_impl),
         Some("Convert a list to a vector.\nUsage: list list->vector => vector\nExample: (1 2 3) list->vector => #(1 2 3)"));
 
+    // Sequence primitives (lists and vectors; results keep the input's shape)
+    add_builtin(interp, "map", crate::primitives::sequence::map_builtin,
+        Some("Apply a quotation to each element.\nUsage: seq [code] map => seq'\nExample: [1 2 3] [dup *] map => [1 4 9]"));
+    add_builtin(interp, "filter", crate::primitives::sequence::filter_builtin,
+        Some("Keep elements for which the quotation is truthy.\nUsage: seq [pred] filter => seq'\nExample: [1 2 3 4] [2 mod 0 =] filter => [2 4]"));
+    add_builtin(interp, "fold", crate::primitives::sequence::fold_builtin,
+        Some("Combine elements left to right starting from an initial value.\nUsage: seq init [code] fold => acc\nExample: [1 2 3] 0 [+] fold => 6"));
+    add_builtin(interp, "reduce", crate::primitives::sequence::reduce_builtin,
+        Some("Fold a non-empty sequence, seeded with its first element.\nUsage: seq [code] reduce => acc\nExample: [3 1 2] [max] reduce => 3"));
+    add_builtin(interp, "find", crate::primitives::sequence::find_builtin,
+        Some("First element for which the quotation is truthy, or null.\nUsage: seq [pred] find => x\nExample: [1 2 3] [1 >] find => 2"));
+    add_builtin(interp, "any?", crate::primitives::sequence::any_builtin,
+        Some("True if the quotation is truthy for some element.\nUsage: seq [pred] any? => bool\nExample: [1 2 3] [2 =] any? => true"));
+    add_builtin(interp, "all?", crate::primitives::sequence::all_builtin,
+        Some("True if the quotation is truthy for every element.\nUsage: seq [pred] all? => bool\nExample: [1 2 3] [0 >] all? => true"));
+    add_builtin(interp, "sort", sync_builtin!(crate::primitives::sequence::sort_impl),
+        Some("Stable ascending sort using the same order as <.\nUsage: seq sort => seq'\nExample: [3 1 2] sort => [1 2 3]"));
+    add_builtin(interp, "sort-by", crate::primitives::sequence::sort_by_builtin,
+        Some("Stable sort on a key computed by the quotation.\nUsage: seq [key] sort-by => seq'\nExample: [3 -1 2] [abs] sort-by => [-1 2 3]"));
+    add_builtin(interp, "zip", sync_builtin!(crate::primitives::sequence::zip_impl),
+        Some("Pair up elements of two sequences, stopping at the shorter.\nUsage: seq1 seq2 zip => seq\nExample: [1 2] [a b] zip => [[1 a] [2 b]]"));
+    add_builtin(interp, "range", sync_builtin!(crate::primitives::sequence::range_impl),
+        Some("List of integers from start up to but not including end.\nUsage: start end range => list\nExample: 0 4 range => [0 1 2 3]"));
+    add_builtin(interp, "reverse", sync_builtin!(crate::primitives::sequence::reverse_impl),
+        Some("Reverse a sequence.\nUsage: seq reverse => seq'\nExample: [1 2 3] reverse => [3 2 1]"));
+    add_builtin(interp, "take", sync_builtin!(crate::primitives::sequence::take_impl),
+        Some("The first n elements of a sequence.\nUsage: seq n take => seq'\nExample: [1 2 3] 2 take => [1 2]"));
+    add_builtin(interp, "skip", sync_builtin!(crate::primitives::sequence::skip_impl),
+        Some("Everything after the first n elements of a sequence; what other languages call drop, which here is the stack word.\nUsage: seq n skip => seq'\nExample: [1 2 3] 2 skip => [3]"));
+
+    // Map primitives (maps are values; updates return a new map)
+    add_builtin(interp, "map-get", sync_builtin!(crate::primitives::map::map_get_impl),
+        Some("Look up a key in a map, or null if missing.\nUsage: map key map-get => value\nExample: { a 1 } 'a map-get => 1"));
+    add_builtin(interp, "map-put", sync_builtin!(crate::primitives::map::map_put_impl),
+        Some("Return a map with key set to value.\nUsage: map key value map-put => map'\nExample: { } 'a 1 map-put => {a 1}"));
+    add_builtin(interp, "map-remove", sync_builtin!(crate::primitives::map::map_remove_impl),
+        Some("Return a map without the given key.\nUsage: map key map-remove => map'\nExample: { a 1 b 2 } 'a map-remove => {b 2}"));
+    add_builtin(interp, "map-contains?", sync_builtin!(crate::primitives::map::map_contains_impl),
+        Some("Check whether a map has a key.\nUsage: map key map-contains? => bool\nExample: { a 1 } 'a map-contains? => true"));
+    add_builtin(interp, "map-size", sync_builtin!(crate::primitives::map::map_size_impl),
+        Some("Number of entries in a map.\nUsage: map map-size => n\nExample: { a 1 b 2 } map-size => 2"));
+    add_builtin(interp, "map-keys", sync_builtin!(crate::primitives::map::map_keys_impl),
+        Some("List of a map's keys in order.\nUsage: map map-keys => list\nExample: { b 2 a 1 } map-keys => [a b]"));
+    add_builtin(interp, "map-values", sync_builtin!(crate::primitives::map::map_values_impl),
+        Some("List of a map's values in key order.\nUsage: map map-values => list\nExample: { b 2 a 1 } map-values => [1 2]"));
+    add_builtin(interp, "map-merge", sync_builtin!(crate::primitives::map::map_merge_impl),
+        Some("Combine two maps; entries from the second win.\nUsage: map1 map2 map-merge => map\nExample: { a 1 } { a 2 b 3 } map-merge => {a 2 b 3}"));
+    add_builtin(interp, "map-each", crate::primitives::map::map_each_builtin,
+        Some("Run a quotation with each key and value, in key order.\nUsage: map [code] map-each\nExample: { a 1 } [. . cr] map-each"));
+
     // Bitwise primitives
     add_builtin(interp, "&", sync_builtin!(crate::primitives::bit_and::bit_and_impl),
         Some("Bitwise AND.\nUsage: a b & => result\nExample: 12 10 & => 8"));
@@ -231,11 +318,47 @@ pub fn register_async_builtins(interp: &mut AsyncInterpreter) {
     // String/type primitives
     add_builtin(interp, "->string", sync_builtin!(crate::primitives::to_string::to_string_impl),
         Some("Convert value to string.\nUsage: value ->string => string\nExample: 42 ->string => \"42\""));
+    add_builtin(interp, "string->number", sync_builtin!(crate::primitives::read::string_to_number_impl),
+        Some("Parse a number literal, or null if the string isn't one.\nUsage: string string->number => n\nExample: \"3/4\" string->number => 3/4"));
+    add_builtin(interp, "read", sync_builtin!(crate::primitives::read::read_impl),
+        Some("Parse a string into a single value without executing it.\nUsage: string read => value\nExample: \"[1 2 +]\" read => [1 2 +]"));
+    add_builtin(interp, "->source", sync_builtin!(crate::primitives::write::to_source_impl),
+        Some("Convert a value to source text that read parses back unchanged.\nUsage: value ->source => string\nExample: 1.0 ->source => \"1.0\""));
     add_builtin(interp, "truthy?", sync_builtin!(crate::primitives::truthy::truthy_impl),
         Some("Test if value is truthy (not false or null).\nUsage: value truthy? => bool\nExample: 0 truthy? => true"));
     add_builtin(interp, "type-of", sync_builtin!(crate::primitives::type_of::type_of_impl),
         Some("Get the type of a value.\nUsage: value type-of => type-string\nExample: 42 type-of => \"i64\""));
 
+    // String primitives (indices count characters, not bytes)
+    add_builtin(interp, "string-length", sync_builtin!(crate::primitives::string::string_length_impl),
+        Some("Number of characters in a string.\nUsage: s string-length => n\nExample: \"héllo\" string-length => 5"));
+    add_builtin(interp, "substring", sync_builtin!(crate::primitives::string::substring_impl),
+        Some("Characters from start up to but not including end.\nUsage: s start end substring => s'\nExample: \"hello\" 1 3 substring => \"el\""));
+    add_builtin(interp, "char-at", sync_builtin!(crate::primitives::string::char_at_impl),
+        Some("The character at an index, as a one-character string.\nUsage: s index char-at => char\nExample: \"hello\" 1 char-at => \"e\""));
+    add_builtin(interp, "index-of", sync_builtin!(crate::primitives::string::index_of_impl),
+        Some("Position of the first occurrence of needle, or null.\nUsage: s needle index-of => n\nExample: \"hello\" \"ll\" index-of => 2"));
+    add_builtin(interp, "split", sync_builtin!(crate::primitives::string::split_impl),
+        Some("Split a string on a separator; an empty separator splits into characters.\nUsage: s sep split => list\nExample: \"a,b\" \",\" split => [\"a\" \"b\"]"));
+    add_builtin(interp, "join", sync_builtin!(crate::primitives::string::join_impl),
+        Some("Join the elements of a list or vector with a separator.\nUsage: seq sep join => s\nExample: [\"a\" \"b\"] \",\" join => \"a,b\""));
+    add_builtin(interp, "trim", sync_builtin!(crate::primitives::string::trim_impl),
+        Some("Remove leading and trailing whitespace.\nUsage: s trim => s'\nExample: \"  hi \" trim => \"hi\""));
+    add_builtin(interp, "upper", sync_builtin!(crate::primitives::string::upper_impl),
+        Some("Convert a string to upper case.\nUsage: s upper => s'\nExample: \"hi\" upper => \"HI\""));
+    add_builtin(interp, "lower", sync_builtin!(crate::primitives::string::lower_impl),
+        Some("Convert a string to lower case.\nUsage: s lower => s'\nExample: \"HI\" lower => \"hi\""));
+    add_builtin(interp, "starts-with?", sync_builtin!(crate::primitives::string::starts_with_impl),
+        Some("Check whether a string starts with a prefix.\nUsage: s prefix starts-with? => bool\nExample: \"hello\" \"he\" starts-with? => true"));
+    add_builtin(interp, "ends-with?", sync_builtin!(crate::primitives::string::ends_with_impl),
+        Some("Check whether a string ends with a suffix.\nUsage: s suffix ends-with? => bool\nExample: \"hello\" \"lo\" ends-with? => true"));
+    add_builtin(interp, "replace", sync_builtin!(crate::primitives::string::replace_impl),
+        Some("Replace every occurrence of a substring.\nUsage: s from to replace => s'\nExample: \"a-b-c\" \"-\" \"+\" replace => \"a+b+c\""));
+    add_builtin(interp, "string->list", sync_builtin!(crate::primitives::string::string_to_list_impl),
+        Some("List of a string's characters.\nUsage: s string->list => list\nExample: \"ab\" string->list => [\"a\" \"b\"]"));
+    add_builtin(interp, "chars->string", sync_builtin!(crate::primitives::string::chars_to_string_impl),
+        Some("Build a string from characters or integer code points.\nUsage: seq chars->string => s\nExample: [\"a\" 98] chars->string => \"ab\""));
+
     // I32 buffer primitives (for integer data and DSP)
     add_builtin(interp, "i32-buffer", sync_builtin!(crate::primitives::i32_buffer::i32_buffer_impl),
         Some("Create an i32 buffer.\nUsage: capacity i32-buffer => buffer\nExample: 100 i32-buffer"));
@@ -312,8 +435,9 @@ pub async fn ensure_datetime_record_type(interp: &mut AsyncInterpreter) -> Resul
         // Push type name
         interp.push(Value::String(Rc::<str>::from("datetime")));
 
-        // Create record type
+        // Create record type (drop the descriptor it leaves on the stack)
         make_record_type_impl(interp)?;
+        interp.pop()?;
     }
 
     Ok(())
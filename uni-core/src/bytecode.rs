@@ -0,0 +1,224 @@
+// Bytecode: the instruction set of the alternative execution backend
+//
+// The default backend runs compiled Code one op per continuation. Interpreters
+// set to Backend::Bytecode run the same lists as flat instruction arrays
+// instead, through the VM loop in evaluator.rs, which keeps executing
+// instructions in place until one of them has to schedule work (calling a
+// definition, running a special form). The compiler does what the op-per-
+// continuation form can't:
+//
+// - `[a] [b] if` with literal branches is inlined as a conditional jump, so
+//   the branch lists are never pushed, popped or looked up. Branches with a
+//   parameter header need their own frame, so those ifs are left as they are.
+// - Calls in tail position (including the ends of inlined branches) are
+//   marked as tail calls, so they never schedule the rest of the chunk.
+// - `'name lval` becomes a local-set, and later uses of name in the same
+//   chunk read the innermost frame directly.
+//
+// Lookups stay late-bound exactly as in compile.rs, so both backends give
+// every program the same meaning.
+
+use crate::compat::{Rc, Vec};
+use crate::compile::{takes_parameters, Special, SpecialForm, Word};
+use crate::value::{AsyncPrimitiveFn, Value};
+
+#[derive(Clone)]
+pub(crate) struct Chunk(Rc<[Instr]>);
+
+impl Chunk {
+    pub(crate) fn get(&self, pc: usize) -> Option<&Instr> {
+        self.0.get(pc)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+// Implement Debug manually since instructions may hold builtins
+impl core::fmt::Debug for Chunk {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Chunk({} instructions)", self.0.len())
+    }
+}
+
+pub(crate) enum Instr {
+    // Push a data value (quoted atoms push the plain atom)
+    PushLiteral(Value),
+    // Call a builtin that appears in the code itself
+    CallBuiltin(AsyncPrimitiveFn),
+    // Look a word up (locals first) and run it, returning here afterwards
+    CallWord(Word),
+    // The same, but nothing in this chunk runs after it
+    TailCall(Word),
+    // Pop a condition and jump to the target if it is falsy
+    BranchIfFalse { target: usize, word: Word },
+    Jump(usize),
+    // ( value -- ) binds name in the innermost local frame, as `'name lval` does
+    LocalSet { name: Rc<str>, lval: Word },
+    // A word bound by a local-set earlier in this chunk
+    LocalGet(Word),
+    // exec, try, quit, the loop words, and if with computed branches
+    Special { special: Special, tail: bool },
+}
+
+// Compiles a list (or a single value, like compile::compile) to bytecode
+pub(crate) fn compile_chunk(body: &Value) -> Chunk {
+    let mut compiler = ChunkCompiler {
+        instrs: Vec::new(),
+        locals: Vec::new(),
+    };
+    compiler.compile_list(body, true);
+    Chunk(compiler.instrs.into())
+}
+
+struct ChunkCompiler {
+    instrs: Vec<Instr>,
+    locals: Vec<Rc<str>>, // Names set by a local-set so far
+}
+
+impl ChunkCompiler {
+    fn compile_list(&mut self, body: &Value, tail: bool) {
+        let items = list_items(body);
+        let mut index = 0;
+        while index < items.len() {
+            let remaining = &items[index..];
+            let is_last = |used: usize| tail && remaining.len() == used;
+
+            match remaining {
+                [then_branch, else_branch, Value::Atom(name, span), ..]
+                    if &**name == "if" && inlinable(then_branch) && inlinable(else_branch) =>
+                {
+                    self.compile_if(then_branch, else_branch, Word::new(name, span), is_last(3));
+                    index += 3;
+                }
+                [Value::QuotedAtom(local), Value::Atom(name, span), ..] if &**name == "lval" => {
+                    self.instrs.push(Instr::LocalSet {
+                        name: local.clone(),
+                        lval: Word::new(name, span),
+                    });
+                    self.locals.push(local.clone());
+                    index += 2;
+                }
+                [item, ..] => {
+                    let instr = self.compile_item(item, is_last(1));
+                    self.instrs.push(instr);
+                    index += 1;
+                }
+                [] => unreachable!("index is within items"),
+            }
+        }
+    }
+
+    fn compile_if(&mut self, then_branch: &Value, else_branch: &Value, word: Word, tail: bool) {
+        let branch_at = self.instrs.len();
+        self.instrs.push(Instr::BranchIfFalse { target: 0, word });
+        self.compile_list(then_branch, tail);
+
+        let jump_at = self.instrs.len();
+        self.instrs.push(Instr::Jump(0));
+        let else_at = self.instrs.len();
+        self.compile_list(else_branch, tail);
+        let end = self.instrs.len();
+
+        if let Instr::BranchIfFalse { target, .. } = &mut self.instrs[branch_at] {
+            *target = else_at;
+        }
+        self.instrs[jump_at] = Instr::Jump(end);
+    }
+
+    fn compile_item(&self, item: &Value, tail: bool) -> Instr {
+        match item {
+            Value::Atom(name, span) => match SpecialForm::from_name(name) {
+                Some(form) => Instr::Special {
+                    special: Special::new(name, span, form),
+                    tail,
+                },
+                None if self.locals.contains(name) => Instr::LocalGet(Word::new(name, span)),
+                None if tail => Instr::TailCall(Word::new(name, span)),
+                None => Instr::CallWord(Word::new(name, span)),
+            },
+            Value::QuotedAtom(name) => Instr::PushLiteral(Value::Atom(name.clone(), None)),
+            Value::AsyncBuiltin(func) => Instr::CallBuiltin(*func),
+            other => Instr::PushLiteral(other.clone()),
+        }
+    }
+}
+
+fn is_list(value: &Value) -> bool {
+    matches!(value, Value::Pair(_, _) | Value::Nil)
+}
+
+// A literal if branch that can run inline in the enclosing chunk's frame
+fn inlinable(branch: &Value) -> bool {
+    is_list(branch) && !takes_parameters(branch)
+}
+
+// The items a list runs, in order; an improper tail runs last, and a value
+// that isn't a list runs on its own
+fn list_items(body: &Value) -> Vec<Value> {
+    let mut items = Vec::new();
+    let mut current = body;
+    loop {
+        match current {
+            Value::Pair(car, cdr) => {
+                items.push((**car).clone());
+                current = cdr;
+            }
+            Value::Nil => break,
+            tail => {
+                items.push(tail.clone());
+                break;
+            }
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::AsyncInterpreter;
+    use crate::parser::parse;
+
+    fn chunk_for(code: &str) -> Chunk {
+        let mut interp = AsyncInterpreter::new();
+        let values = parse(code, &mut interp).unwrap();
+        compile_chunk(&values[0])
+    }
+
+    #[test]
+    fn test_literal_if_becomes_jumps() {
+        let chunk = chunk_for("[dup 0 < [neg] [] if double]");
+        assert_eq!(chunk.len(), 7);
+        assert!(matches!(chunk.get(0), Some(Instr::CallWord(w)) if &*w.name == "dup"));
+        assert!(matches!(chunk.get(1), Some(Instr::PushLiteral(Value::Int32(0)))));
+        assert!(matches!(chunk.get(2), Some(Instr::CallWord(w)) if &*w.name == "<"));
+        assert!(matches!(chunk.get(3), Some(Instr::BranchIfFalse { target: 6, .. })));
+        assert!(matches!(chunk.get(4), Some(Instr::CallWord(w)) if &*w.name == "neg"));
+        assert!(matches!(chunk.get(5), Some(Instr::Jump(6))));
+        // The empty else branch compiles to nothing, so both paths meet at double
+        assert!(matches!(chunk.get(6), Some(Instr::TailCall(w)) if &*w.name == "double"));
+    }
+
+    #[test]
+    fn test_tail_calls_inside_inlined_branches() {
+        let chunk = chunk_for("[dup 0 <= [drop] [1 - countdown] if]");
+        assert!(matches!(chunk.get(3), Some(Instr::BranchIfFalse { target: 6, .. })));
+        assert!(matches!(chunk.get(4), Some(Instr::TailCall(w)) if &*w.name == "drop"));
+        assert!(matches!(chunk.get(6), Some(Instr::PushLiteral(Value::Int32(1)))));
+        assert!(matches!(chunk.get(8), Some(Instr::TailCall(w)) if &*w.name == "countdown"));
+
+        // A computed branch can't be inlined and stays a special form
+        let chunk = chunk_for("[then-part [x] if]");
+        assert!(matches!(chunk.get(2), Some(Instr::Special { tail: true, .. })));
+    }
+
+    #[test]
+    fn test_locals_compile_to_local_instructions() {
+        let chunk = chunk_for("[5 'x lval x y]");
+        assert!(matches!(chunk.get(1), Some(Instr::LocalSet { name, .. }) if &**name == "x"));
+        assert!(matches!(chunk.get(2), Some(Instr::LocalGet(w)) if &*w.name == "x"));
+        assert!(matches!(chunk.get(3), Some(Instr::TailCall(w)) if &*w.name == "y"));
+    }
+}
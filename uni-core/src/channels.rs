@@ -0,0 +1,196 @@
+// Channels: bounded queues of values for tasks to talk through
+//
+// A channel is shared by handing its Value::Channel to spawned tasks (on the
+// stack, in a var, or captured in the quotation). send waits while the
+// channel is full and receive waits while it is empty; both suspend only the
+// calling task, and both give up with Interrupted when that task is cancelled.
+//
+// On Linux a channel is a tokio mpsc channel. Any number of tasks may
+// receive, so the receiving end sits behind tokio's async mutex and waiting
+// receivers take turns. On the STM32 it is an embassy-sync channel, like
+// platform_output::WRITE_CHANNEL, with a semaphore holding one permit per
+// free slot so a smaller capacity than the channel's fixed size still makes
+// send wait for room.
+
+use crate::interpreter::CancelToken;
+use crate::primitives::delay::cancellable;
+use crate::value::{RuntimeError, Value};
+#[cfg(feature = "target-stm32h753zi")]
+use embassy_sync::semaphore::Semaphore;
+
+// Largest capacity a channel can have on the STM32, where every channel
+// reserves this many slots up front
+#[cfg(feature = "target-stm32h753zi")]
+pub const MAX_CHANNEL_CAPACITY: usize = 16;
+
+// Largest capacity tokio's channels accept
+#[cfg(not(feature = "target-stm32h753zi"))]
+pub const MAX_CHANNEL_CAPACITY: usize = tokio::sync::Semaphore::MAX_PERMITS;
+
+pub struct Channel {
+    capacity: usize,
+    #[cfg(not(feature = "target-stm32h753zi"))]
+    sender: tokio::sync::mpsc::Sender<Value>,
+    #[cfg(not(feature = "target-stm32h753zi"))]
+    receiver: tokio::sync::Mutex<tokio::sync::mpsc::Receiver<Value>>,
+    #[cfg(feature = "target-stm32h753zi")]
+    slots: embassy_sync::channel::Channel<
+        embassy_sync::blocking_mutex::raw::NoopRawMutex,
+        Value,
+        MAX_CHANNEL_CAPACITY,
+    >,
+    #[cfg(feature = "target-stm32h753zi")]
+    room: embassy_sync::semaphore::GreedySemaphore<embassy_sync::blocking_mutex::raw::NoopRawMutex>,
+}
+
+impl Channel {
+    pub fn new(capacity: usize) -> Result<Self, RuntimeError> {
+        if capacity == 0 {
+            return Err(RuntimeError::DomainError(
+                "channel capacity must be at least 1".into(),
+            ));
+        }
+        if capacity > MAX_CHANNEL_CAPACITY {
+            return Err(RuntimeError::DomainError(crate::compat::format!(
+                "channel capacity must be at most {}",
+                MAX_CHANNEL_CAPACITY
+            )));
+        }
+
+        #[cfg(not(feature = "target-stm32h753zi"))]
+        {
+            let (sender, receiver) = tokio::sync::mpsc::channel(capacity);
+            Ok(Channel {
+                capacity,
+                sender,
+                receiver: tokio::sync::Mutex::new(receiver),
+            })
+        }
+
+        #[cfg(feature = "target-stm32h753zi")]
+        {
+            Ok(Channel {
+                capacity,
+                slots: embassy_sync::channel::Channel::new(),
+                room: embassy_sync::semaphore::GreedySemaphore::new(capacity),
+            })
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // Values waiting to be received
+    pub fn len(&self) -> usize {
+        #[cfg(not(feature = "target-stm32h753zi"))]
+        {
+            self.sender.max_capacity() - self.sender.capacity()
+        }
+        #[cfg(feature = "target-stm32h753zi")]
+        {
+            self.slots.len()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub async fn send(&self, value: Value, cancel: &CancelToken) -> Result<(), RuntimeError> {
+        #[cfg(not(feature = "target-stm32h753zi"))]
+        {
+            // The channel owns a receiver for as long as it exists, so this can't fail
+            let permit = cancellable(self.sender.reserve(), cancel)
+                .await?
+                .map_err(|_| RuntimeError::DomainError("channel closed".into()))?;
+            permit.send(value);
+            Ok(())
+        }
+
+        #[cfg(feature = "target-stm32h753zi")]
+        {
+            // A permit is a free slot within capacity, so the value always fits
+            let Ok(permit) = cancellable(self.room.acquire(1), cancel).await?;
+            permit.disarm();
+            self.slots
+                .try_send(value)
+                .map_err(|_| RuntimeError::DomainError("channel full".into()))
+        }
+    }
+
+    pub async fn receive(&self, cancel: &CancelToken) -> Result<Value, RuntimeError> {
+        cancellable(self.next_value(), cancel).await?
+    }
+
+    // Waits for the next value, leaving cancelling to the caller (select
+    // races this against other events and cancels them all together)
+    pub(crate) async fn next_value(&self) -> Result<Value, RuntimeError> {
+        #[cfg(not(feature = "target-stm32h753zi"))]
+        {
+            let next = self.receiver.lock().await.recv().await;
+            next.ok_or_else(|| RuntimeError::DomainError("channel closed".into()))
+        }
+
+        #[cfg(feature = "target-stm32h753zi")]
+        {
+            let value = self.slots.receive().await;
+            self.room.release(1);
+            Ok(value)
+        }
+    }
+
+    // The next value if one is waiting, without waiting for one
+    pub fn try_receive(&self) -> Option<Value> {
+        #[cfg(not(feature = "target-stm32h753zi"))]
+        {
+            // A receiver holding the lock is waiting, so nothing is queued
+            let mut receiver = self.receiver.try_lock().ok()?;
+            receiver.try_recv().ok()
+        }
+
+        #[cfg(feature = "target-stm32h753zi")]
+        {
+            let value = self.slots.try_receive().ok()?;
+            self.room.release(1);
+            Some(value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_channel_is_first_in_first_out() {
+        let channel = Channel::new(2).unwrap();
+        let cancel = CancelToken::default();
+        channel.send(Value::Int32(1), &cancel).await.unwrap();
+        channel.send(Value::Int32(2), &cancel).await.unwrap();
+        assert_eq!(channel.len(), 2);
+
+        assert!(matches!(channel.receive(&cancel).await, Ok(Value::Int32(1))));
+        assert!(matches!(channel.try_receive(), Some(Value::Int32(2))));
+        assert!(channel.try_receive().is_none());
+        assert!(channel.is_empty());
+
+        assert!(matches!(Channel::new(0), Err(RuntimeError::DomainError(_))));
+        assert!(matches!(
+            Channel::new(MAX_CHANNEL_CAPACITY + 1),
+            Err(RuntimeError::DomainError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_full_channel_send_can_be_cancelled() {
+        let channel = Channel::new(1).unwrap();
+        let cancel = CancelToken::default();
+        channel.send(Value::Int32(1), &cancel).await.unwrap();
+
+        cancel.cancel();
+        let result = channel.send(Value::Int32(2), &cancel).await;
+        assert!(matches!(result, Err(RuntimeError::Interrupted)));
+        assert_eq!(channel.len(), 1);
+    }
+}
@@ -0,0 +1,101 @@
+// Closures: quotations that keep the locals they were written next to
+//
+// Local frames come and go with the code that runs: lval and lvar bind names
+// in the innermost frame, and the frame is popped when its quotation or
+// definition finishes. A quotation that outlives that frame (returned from a
+// definition, stored in a var, handed to spawn or every) would lose its
+// locals. So when a quotation is pushed while locals are bound and it refers
+// to some of them, the evaluator pushes a Value::Closure instead, holding the
+// quotation and those bindings. Running the closure runs the quotation in a
+// frame that starts with the captured bindings.
+//
+// Captured bindings are copies of what the locals held at that moment: an
+// lval stays the value it was, while an lvar is a shared variable, so @ and !
+// through a closure see and change the same variable as the code that made it.
+// Quotations that mention no bound locals stay plain lists.
+//
+// Code is data, so [x 1 2] may be meant as a list even where x is bound. A
+// closure reads as its quotation to everything that treats it as a list (list
+// and sequence words, =, the writer - see Value::as_list); only running it
+// uses the captured locals.
+
+use crate::compat::{Rc, Vec};
+use crate::value::Value;
+
+#[cfg(not(target_os = "none"))]
+use std::collections::HashMap;
+#[cfg(target_os = "none")]
+use alloc::collections::BTreeMap as HashMap;
+
+#[derive(Debug)]
+pub struct Closure {
+    pub body: Value,                       // The quotation
+    pub captured: HashMap<Rc<str>, Value>, // The locals it refers to
+}
+
+// The closure for quotation, if it refers to any local bound in frames
+// (innermost first, like lookups). Nested quotations count, since they run
+// inside this one.
+pub(crate) fn capture(quotation: &Value, frames: &[HashMap<Rc<str>, Value>]) -> Option<Closure> {
+    if frames.iter().all(|frame| frame.is_empty()) {
+        return None;
+    }
+
+    let mut captured = HashMap::new();
+    let mut pending: Vec<&Value> = Vec::new();
+    pending.push(quotation);
+    while let Some(value) = pending.pop() {
+        match value {
+            Value::Pair(car, cdr) => {
+                pending.push(cdr);
+                pending.push(car);
+            }
+            Value::Atom(name, _) if !captured.contains_key(name) => {
+                if let Some(bound) = frames.iter().rev().find_map(|frame| frame.get(name)) {
+                    captured.insert(name.clone(), bound.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if captured.is_empty() {
+        return None;
+    }
+    Some(Closure {
+        body: quotation.clone(),
+        captured,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::AsyncInterpreter;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_capture_keeps_only_referenced_locals() {
+        let mut interp = AsyncInterpreter::new();
+        let quotation = parse("[x [y +] exec]", &mut interp).unwrap().remove(0);
+
+        let mut outer = HashMap::new();
+        outer.insert(interp.intern_atom("x"), Value::Int32(1));
+        outer.insert(interp.intern_atom("y"), Value::Int32(2));
+        outer.insert(interp.intern_atom("unused"), Value::Int32(3));
+        let mut inner = HashMap::new();
+        inner.insert(interp.intern_atom("x"), Value::Int32(10));
+
+        let closure = capture(&quotation, &[outer, inner]).unwrap();
+        assert_eq!(closure.captured.len(), 2);
+        assert!(matches!(closure.captured.get("x"), Some(Value::Int32(10))));
+        assert!(matches!(closure.captured.get("y"), Some(Value::Int32(2))));
+
+        // No locals bound, or none referenced: the quotation stays a list
+        assert!(capture(&quotation, &[HashMap::new()]).is_none());
+        let other = parse("[1 2 +]", &mut interp).unwrap().remove(0);
+        let mut frame = HashMap::new();
+        frame.insert(interp.intern_atom("x"), Value::Int32(1));
+        assert!(capture(&other, &[frame]).is_none());
+    }
+}
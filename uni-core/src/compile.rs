@@ -0,0 +1,342 @@
+// Compiled code: the form the evaluator runs lists in
+//
+// Code is data in Uni, so a definition body is an ordinary cons list. Walking
+// that list directly means copying its elements out on every call and looking
+// every word up in the dictionary (a lock plus a string hash) every time it
+// runs. `compile` flattens a list once into a shared, immutable slice of ops:
+//
+// - Data and builtins become Op::Value and run exactly as before.
+// - Words become Op::Word, which remembers what the name resolved to and the
+//   dictionary version it was resolved under. Every change to the dictionary
+//   gets a new version, so a redefined word is picked up on its next call
+//   (late binding) without anyone having to find and patch the callers.
+// - Special forms (exec, if, try, cond, times, ...) are recognised once and keep
+//   the compiled code of the last quotation they were handed, so an `if`
+//   inside a loop doesn't recompile its branches every time round.
+//
+// `def` compiles bodies when a word is defined and stores the result in its
+// dictionary entry; the evaluator compiles any other list when it first runs.
+// Interpreters using the bytecode backend run the same Code through its
+// bytecode (see bytecode.rs), built from the list the first time it's needed.
+
+use crate::compat::{format, Box, Rc, Vec};
+use crate::interpreter::{AsyncInterpreter, DictEntry};
+use crate::tokenizer::SourceSpan;
+use crate::value::{AsyncPrimitiveFn, RuntimeError, Value};
+use crate::bytecode::{compile_chunk, Chunk};
+use core::cell::{OnceCell, RefCell};
+
+#[derive(Clone)]
+pub struct Code(Rc<CodeInner>);
+
+struct CodeInner {
+    ops: Box<[Op]>,
+    source: Value,          // The list this was compiled from
+    chunk: OnceCell<Chunk>, // Bytecode for the same list, compiled on first use
+}
+
+// Implement Debug manually since the cached bindings may hold builtins
+impl core::fmt::Debug for Code {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Code({} ops)", self.0.ops.len())
+    }
+}
+
+impl Code {
+    pub fn len(&self) -> usize {
+        self.0.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.ops.is_empty()
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&Op> {
+        self.0.ops.get(index)
+    }
+
+    pub(crate) fn chunk(&self) -> Chunk {
+        self.0.chunk.get_or_init(|| compile_chunk(&self.0.source)).clone()
+    }
+}
+
+pub(crate) enum Op {
+    // Anything that isn't an atom: data pushes itself, builtins run
+    Value(Value),
+    // A dictionary word (or local variable) looked up by name
+    Word(Word),
+    // exec, if, try, quit, cond, case, match and the loop words
+    Special(Special),
+}
+
+pub(crate) struct Word {
+    pub(crate) name: Rc<str>,
+    pub(crate) span: Option<Rc<SourceSpan>>,
+    binding: RefCell<Option<(usize, Binding)>>, // Last lookup and the dictionary version it saw
+}
+
+pub(crate) struct Special {
+    pub(crate) name: Rc<str>,
+    pub(crate) span: Option<Rc<SourceSpan>>,
+    pub(crate) form: SpecialForm,
+    pub(crate) quotes: [QuoteCache; 2], // if uses one per branch, the others just the first
+}
+
+impl Special {
+    pub(crate) fn new(name: &Rc<str>, span: &Option<Rc<SourceSpan>>, form: SpecialForm) -> Self {
+        Special {
+            name: name.clone(),
+            span: span.clone(),
+            form,
+            quotes: Default::default(),
+        }
+    }
+}
+
+// Words the evaluator handles itself instead of looking them up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SpecialForm {
+    Exec,
+    If,
+    Try,
+    Quit,
+    Times,
+    Do,
+    Loop,
+    RangeEach,
+    Leave,
+    Cond,
+    Case,
+    Match,
+}
+
+impl SpecialForm {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "exec" => Some(Self::Exec),
+            "if" => Some(Self::If),
+            "try" => Some(Self::Try),
+            "quit" => Some(Self::Quit),
+            "times" => Some(Self::Times),
+            "do" => Some(Self::Do),
+            "loop" => Some(Self::Loop),
+            "range-each" => Some(Self::RangeEach),
+            "leave" => Some(Self::Leave),
+            "cond" => Some(Self::Cond),
+            "case" => Some(Self::Case),
+            "match" => Some(Self::Match),
+            _ => None,
+        }
+    }
+}
+
+// Whether a list starts by popping its arguments into locals, as a
+// [| a b | ...] header does; a branch like that needs a frame of its own
+// rather than running in its caller's
+pub(crate) fn takes_parameters(list: &Value) -> bool {
+    let Value::Pair(first, rest) = list else {
+        return false;
+    };
+    matches!(
+        (&**first, &**rest),
+        (Value::QuotedAtom(_), Value::Pair(word, _)) if matches!(&**word, Value::Atom(name, _) if &**name == "lval")
+    )
+}
+
+// The evaluator sees these names before any local or dictionary entry, so a
+// definition under one of them would be silently ignored; refuse it instead
+pub(crate) fn check_definable(name: &str, word: &str) -> Result<(), RuntimeError> {
+    match SpecialForm::from_name(name) {
+        Some(_) => Err(RuntimeError::DomainError(format!(
+            "{}: {} is built into the evaluator and can't be redefined",
+            word, name
+        ))),
+        None => Ok(()),
+    }
+}
+
+// What a word means in the dictionary, ready to run
+#[derive(Clone)]
+pub(crate) enum Binding {
+    Constant(Value),           // Non-executable entries (val) are pushed as they are
+    Builtin(AsyncPrimitiveFn), // Primitives are called directly
+    Definition(Code),          // Executable lists run in their own frames
+    Execute(Value),            // Any other executable value runs once, like a single op
+}
+
+impl Binding {
+    pub(crate) fn from_entry(entry: DictEntry) -> Self {
+        if !entry.is_executable {
+            return Binding::Constant(entry.value);
+        }
+        match entry.value {
+            Value::AsyncBuiltin(func) => Binding::Builtin(func),
+            Value::Pair(_, _) | Value::Nil => {
+                Binding::Definition(entry.code.unwrap_or_else(|| compile(&entry.value)))
+            }
+            other => Binding::Execute(other),
+        }
+    }
+
+    pub(crate) fn lookup(name: &Rc<str>, interp: &AsyncInterpreter) -> Result<Self, RuntimeError> {
+        interp
+            .dict_get(name)
+            .map(Binding::from_entry)
+            .ok_or_else(|| RuntimeError::UndefinedWord(name.as_ref().into()))
+    }
+}
+
+impl Word {
+    pub(crate) fn new(name: &Rc<str>, span: &Option<Rc<SourceSpan>>) -> Self {
+        Word {
+            name: name.clone(),
+            span: span.clone(),
+            binding: RefCell::new(None),
+        }
+    }
+
+    // Reuses the cached lookup while the dictionary is unchanged
+    pub(crate) fn binding(&self, interp: &AsyncInterpreter) -> Result<Binding, RuntimeError> {
+        let version = interp.dictionary_version();
+        if let Some((seen, binding)) = self.binding.borrow().as_ref()
+            && *seen == version
+        {
+            return Ok(binding.clone());
+        }
+
+        let binding = Binding::lookup(&self.name, interp)?;
+        *self.binding.borrow_mut() = Some((version, binding.clone()));
+        Ok(binding)
+    }
+}
+
+// The compiled code of the last quotation a special form ran
+// Lists are immutable, so the same first pair means the same list.
+#[derive(Default)]
+pub(crate) struct QuoteCache(RefCell<Option<(Value, Code)>>);
+
+impl QuoteCache {
+    pub(crate) fn code_for(&self, quotation: &Value) -> Code {
+        if let Some((seen, code)) = self.0.borrow().as_ref()
+            && same_list(seen, quotation)
+        {
+            return code.clone();
+        }
+
+        let code = compile(quotation);
+        *self.0.borrow_mut() = Some((quotation.clone(), code.clone()));
+        code
+    }
+}
+
+fn same_list(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Pair(car_a, cdr_a), Value::Pair(car_b, cdr_b)) => {
+            Rc::ptr_eq(car_a, car_b) && Rc::ptr_eq(cdr_a, cdr_b)
+        }
+        (Value::Nil, Value::Nil) => true,
+        _ => false,
+    }
+}
+
+// Flattens a list into code. An improper tail runs as the last op, and a value
+// that isn't a list at all compiles to a single op that runs it.
+pub fn compile(body: &Value) -> Code {
+    let mut ops = Vec::new();
+    let mut current = body;
+    loop {
+        match current {
+            Value::Pair(car, cdr) => {
+                ops.push(compile_op(car));
+                current = cdr;
+            }
+            Value::Nil => break,
+            tail => {
+                ops.push(compile_op(tail));
+                break;
+            }
+        }
+    }
+    Code(Rc::new(CodeInner {
+        ops: ops.into(),
+        source: body.clone(),
+        chunk: OnceCell::new(),
+    }))
+}
+
+fn compile_op(value: &Value) -> Op {
+    match value {
+        Value::Atom(name, span) => match SpecialForm::from_name(name) {
+            Some(form) => Op::Special(Special::new(name, span, form)),
+            None => Op::Word(Word::new(name, span)),
+        },
+        other => Op::Value(other.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn compile_source(code: &str, interp: &mut AsyncInterpreter) -> Code {
+        let values = parse(code, interp).unwrap();
+        compile(&values[0])
+    }
+
+    #[test]
+    fn test_compile_flattens_lists() {
+        let mut interp = AsyncInterpreter::new();
+        let code = compile_source("[1 dup [2 3] if | exec]", &mut interp);
+        assert_eq!(code.len(), 5);
+        assert!(matches!(code.get(0), Some(Op::Value(Value::Int32(1)))));
+        assert!(matches!(code.get(1), Some(Op::Word(w)) if &*w.name == "dup"));
+        assert!(matches!(code.get(2), Some(Op::Value(Value::Pair(_, _)))));
+        assert!(matches!(code.get(3), Some(Op::Special(s)) if s.form == SpecialForm::If));
+        assert!(matches!(code.get(4), Some(Op::Special(s)) if s.form == SpecialForm::Exec));
+
+        assert!(compile(&Value::Nil).is_empty());
+        assert_eq!(compile(&Value::Int32(7)).len(), 1);
+    }
+
+    #[test]
+    fn test_word_binding_follows_redefinition() {
+        let mut interp = AsyncInterpreter::new();
+        let code = compile_source("[answer]", &mut interp);
+        let Some(Op::Word(word)) = code.get(0) else {
+            panic!("expected a word");
+        };
+
+        assert!(matches!(word.binding(&interp), Err(RuntimeError::UndefinedWord(_))));
+
+        let answer = interp.intern_atom("answer");
+        let constant = |n| DictEntry {
+            value: Value::Int32(n),
+            is_executable: false,
+            is_immediate: false,
+            doc: None,
+            code: None,
+        };
+        interp.dict_insert(answer.clone(), constant(1));
+        assert!(matches!(word.binding(&interp), Ok(Binding::Constant(Value::Int32(1)))));
+
+        interp.dict_insert(answer, constant(2));
+        assert!(matches!(word.binding(&interp), Ok(Binding::Constant(Value::Int32(2)))));
+    }
+
+    #[test]
+    fn test_quote_cache_reuses_same_list() {
+        let mut interp = AsyncInterpreter::new();
+        let values = parse("[1 2] [1 2]", &mut interp).unwrap();
+        let cache = QuoteCache::default();
+
+        let first = cache.code_for(&values[0]);
+        let again = cache.code_for(&values[0].clone());
+        assert!(Rc::ptr_eq(&first.0, &again.0));
+
+        // An equal but distinct list is compiled afresh
+        let other = cache.code_for(&values[1]);
+        assert!(!Rc::ptr_eq(&first.0, &other.0));
+        assert_eq!(other.len(), 2);
+    }
+}
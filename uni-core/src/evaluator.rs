@@ -5,6 +5,11 @@
 // 2. Atoms: Look up in dictionary and execute the definition
 // 3. Quoted atoms: Already parsed as (quote atom), quote builtin handles them
 // 4. Lists are data by default, use 'exec' builtin to execute them
+//    (a list that mentions bound locals is pushed as a closure over them -
+//    see closures.rs - and runs wherever a list would)
+// 5. Lists that do run (definitions, exec, if, loop bodies) run as compiled
+//    code - see compile.rs - or as bytecode when the interpreter's backend is
+//    Backend::Bytecode (see bytecode.rs and run_bytecode below)
 //
 // ASYNC CONCEPT: This evaluator is fully async to support async I/O primitives
 // The continuation-based architecture remains identical to the sync version
@@ -16,9 +21,19 @@
 // - Mutable references (&mut) allow us to modify the interpreter state
 // - The ? operator propagates errors up the call stack automatically
 
-use crate::interpreter::AsyncInterpreter;
-use crate::value::{RuntimeError, Value};
-use crate::compat::{Rc, Vec, ToString};
+use crate::bytecode::{Chunk, Instr};
+use crate::closures::{capture, Closure};
+use crate::compile::{compile, takes_parameters, Binding, Code, Op, QuoteCache, SpecialForm};
+use crate::interpreter::{AsyncInterpreter, Backend, CallFrame};
+use crate::tokenizer::SourceSpan;
+use crate::macros::{expand, is_macro, run_macro};
+use crate::patterns::match_pattern;
+use crate::primitives::lval::{lval_builtin, lval_impl};
+use crate::value::{AsyncPrimitiveFn, RuntimeError, Value};
+use crate::compat::{Box, Rc, Vec, ToString, format};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
 
 #[cfg(not(target_os = "none"))]
 use std::collections::HashMap;
@@ -34,29 +49,68 @@ enum Continuation {
     // Execute a single value (push data or execute atom)
     Value(Value),
 
-    // Execute a list of values sequentially with index tracking
-    // When index == items.len()-1, we can do tail-call optimization
-    List {
-        items: Vec<Value>,
+    // Run compiled code one op at a time with index tracking
+    // The code is shared, so scheduling the next op copies nothing.
+    // When index == code.len()-1, we can do tail-call optimization
+    Code {
+        code: Code,
         index: usize,
     },
 
-    // Execute an if statement (condition already evaluated)
-    If {
-        condition_result: bool,
-        true_branch: Value,
-        false_branch: Value,
+    // Run bytecode from pc until it finishes or has to schedule something
+    // (bytecode backend; see run_bytecode)
+    Bytecode {
+        chunk: Chunk,
+        pc: usize,
     },
 
     // Execute an exec'd expression
     Exec(Value),
 
-    // Execute a defined word's body
-    Definition(Value),
-
     // Pop a local frame when this continuation is reached
     // Used to clean up local variables after quotation/definition execution
     PopLocalFrame,
+
+    // Pop the call frame of a defined word when its body finishes
+    // When this is on top as a word is entered, that word is a tail call
+    PopCallFrame,
+
+    // Exception handler installed by `try`
+    // Reaching it normally means the protected body finished without error.
+    // When an error unwinds to it, the interpreter is restored to the saved
+    // depths and the handler runs with the error value on the stack.
+    Handler {
+        handler: Value,
+        stack_depth: usize,
+        return_depth: usize,
+        frame_depth: usize,
+        call_depth: usize,
+        loop_depth: usize,
+    },
+
+    // The rest of a cond, waiting on the result of the test before it
+    // A truthy result runs body in place of the cond; anything else moves
+    // on to the clauses in rest.
+    Cond {
+        body: Value,
+        rest: Value,
+        captured: Option<Rc<Closure>>, // Set when the clause list was a closure
+    },
+
+    // Counted loop started by times, do, loop, or range-each
+    // Each time it is reached it schedules one run of the body followed by
+    // itself with the next index. `leave` unwinds to it and restores the
+    // saved local/call frame depths.
+    Loop {
+        body: Code, // Compiled once when the loop starts
+        captured: Option<Rc<Closure>>, // Set when the body is a closure
+        index: i64,
+        limit: Option<i64>, // Exclusive upper bound; None runs until `leave`
+        push_index: bool,   // range-each hands the index to the body on the stack
+        frame_depth: usize,
+        call_depth: usize,
+        loop_depth: usize, // Depth of interp.loop_indices before this loop started
+    },
 }
 
 // ASYNC CONCEPT: Continuation-based execution loop (now async!)
@@ -65,99 +119,51 @@ enum Continuation {
 pub async fn execute_with_continuations(
     initial_value: &Value,
     interp: &mut AsyncInterpreter,
+) -> Result<(), RuntimeError> {
+    run_continuations(Continuation::Value(initial_value.clone()), interp).await
+}
+
+async fn run_continuations(
+    initial: Continuation,
+    interp: &mut AsyncInterpreter,
 ) -> Result<(), RuntimeError> {
     let mut continuation_stack: Vec<Continuation> = Vec::new();
-    continuation_stack.push(Continuation::Value(initial_value.clone()));
+    continuation_stack.push(initial);
 
     while let Some(continuation) = continuation_stack.pop() {
-        match continuation {
+        if let Err(error) = count_step(interp).await {
+            return Err(locate_error(error, interp));
+        }
+
+        let result = match continuation {
             Continuation::Value(value) => {
                 // ASYNC CONCEPT: Await the async execution
-                execute_value_direct(&value, interp, &mut continuation_stack).await?;
+                execute_value_direct(&value, interp, &mut continuation_stack).await
             }
 
-            Continuation::List { items, index } => {
-                if index >= items.len() {
-                    continue; // Empty list or finished
-                }
-
-                let item = &items[index];
-                let is_tail_call = index == items.len() - 1;
+            Continuation::Code { code, index } => {
+                let Some(op) = code.get(index) else {
+                    continue; // Empty code or finished
+                };
 
-                if is_tail_call {
-                    // TAIL-CALL OPTIMIZATION: Don't push continuation for last item
-                    // This reuses the current "stack frame" enabling proper TCO
-                    continuation_stack.push(Continuation::Value(item.clone()));
-                } else {
-                    // Push continuation for next item, then execute current
-                    continuation_stack.push(Continuation::List {
-                        items: items.clone(),
+                // TAIL-CALL OPTIMIZATION: Nothing is scheduled after the last op,
+                // so a word called from there reuses the current "stack frame"
+                if index + 1 < code.len() {
+                    continuation_stack.push(Continuation::Code {
+                        code: code.clone(),
                         index: index + 1,
                     });
-                    continuation_stack.push(Continuation::Value(item.clone()));
                 }
+                execute_op(op, interp, &mut continuation_stack).await
             }
 
-            Continuation::If {
-                condition_result,
-                true_branch,
-                false_branch,
-            } => {
-                let branch = if condition_result {
-                    true_branch
-                } else {
-                    false_branch
-                };
-                // TAIL-CALL OPTIMIZATION: Execute branch directly without adding continuation
-                match &branch {
-                    Value::Pair(_, _) | Value::Nil => {
-                        let items = list_to_vec(&branch)?;
-                        continuation_stack.push(Continuation::List { items, index: 0 });
-                    }
-                    _ => {
-                        continuation_stack.push(Continuation::Value(branch));
-                    }
-                }
+            Continuation::Bytecode { chunk, pc } => {
+                run_bytecode(chunk, pc, interp, &mut continuation_stack).await
             }
 
             Continuation::Exec(value) => {
-                // Convert list to continuation or execute single value directly
-                match &value {
-                    Value::Pair(_, _) => {
-                        // Push local frame for quotation execution
-                        interp.local_frames.push(HashMap::new());
-                        // Schedule frame cleanup after execution
-                        continuation_stack.push(Continuation::PopLocalFrame);
-                        // Execute the list
-                        let items = list_to_vec(&value)?;
-                        continuation_stack.push(Continuation::List { items, index: 0 });
-                    }
-                    Value::Nil => {
-                        // Empty list - do nothing (no frame needed)
-                    }
-                    _ => {
-                        // Single value - execute directly (tail-call optimized, no frame needed)
-                        continuation_stack.push(Continuation::Value(value));
-                    }
-                }
-            }
-
-            Continuation::Definition(definition) => {
-                match &definition {
-                    Value::Pair(_, _) | Value::Nil => {
-                        // Push local frame for definition execution
-                        interp.local_frames.push(HashMap::new());
-                        // Schedule frame cleanup after execution
-                        continuation_stack.push(Continuation::PopLocalFrame);
-                        // Execute list as code (tail-call optimized)
-                        let items = list_to_vec(&definition)?;
-                        continuation_stack.push(Continuation::List { items, index: 0 });
-                    }
-                    _ => {
-                        // Execute single value directly (tail-call optimized, no frame needed for single values)
-                        continuation_stack.push(Continuation::Value(definition));
-                    }
-                }
+                schedule_exec(value, None, interp, &mut continuation_stack);
+                Ok(())
             }
 
             Continuation::PopLocalFrame => {
@@ -168,13 +174,259 @@ pub async fn execute_with_continuations(
                     ));
                 }
                 interp.local_frames.pop();
+                Ok(())
+            }
+
+            Continuation::PopCallFrame => {
+                interp.call_frames.pop();
+                Ok(())
+            }
+
+            Continuation::Loop {
+                body,
+                captured,
+                index,
+                limit,
+                push_index,
+                frame_depth,
+                call_depth,
+                loop_depth,
+            } => {
+                if limit.is_some_and(|limit| index >= limit) {
+                    // Finished - retire this loop's index
+                    interp.loop_indices.truncate(loop_depth);
+                    Ok(())
+                } else {
+                    if let Some(current) = interp.loop_indices.last_mut() {
+                        *current = index;
+                    }
+                    continuation_stack.push(Continuation::Loop {
+                        body: body.clone(),
+                        captured: captured.clone(),
+                        index: index.saturating_add(1),
+                        limit,
+                        push_index,
+                        frame_depth,
+                        call_depth,
+                        loop_depth,
+                    });
+                    if push_index {
+                        interp.push(index_value(index));
+                    }
+
+                    // Each iteration runs in its own local frame holding i (and j,
+                    // the enclosing loop's index), so they shadow any outer binding
+                    // only while the loop body runs. A closure body starts from
+                    // its captured locals.
+                    let mut frame = match &captured {
+                        Some(closure) => closure.captured.clone(),
+                        None => HashMap::new(),
+                    };
+                    frame.insert(interp.intern_atom("i"), index_value(index));
+                    if let Some(outer) = loop_depth.checked_sub(1) {
+                        let outer_index = interp.loop_indices[outer];
+                        frame.insert(interp.intern_atom("j"), index_value(outer_index));
+                    }
+                    interp.local_frames.push(frame);
+                    continuation_stack.push(Continuation::PopLocalFrame);
+                    schedule_code(body, interp, &mut continuation_stack);
+                    Ok(())
+                }
+            }
+
+            Continuation::Cond { body, rest, captured } => {
+                let condition = interp.pop()?;
+                if interp.is_truthy(&condition) {
+                    schedule_branch(with_captured(body, &captured), None, interp, &mut continuation_stack);
+                    Ok(())
+                } else {
+                    start_cond(rest, captured, interp, &mut continuation_stack)
+                }
+            }
+
+            // Protected body completed normally - the handler is simply discarded
+            Continuation::Handler { .. } => Ok(()),
+        };
+
+        if let Err(error) = result {
+            // A leave from a nested evaluation goes to the loop, not to try
+            if matches!(error, RuntimeError::Leave) {
+                leave_loop(interp, &mut continuation_stack)?;
+                continue;
             }
+            let error = locate_error(error, interp);
+            unwind_to_handler(error, interp, &mut continuation_stack)?;
         }
     }
 
     Ok(())
 }
 
+// ASYNC CONCEPT: Cooperative scheduling
+// Builtins that never wait on anything (arithmetic, stack words) complete on
+// their first poll, so a busy loop like `[true] [] while` would never give the
+// executor back and would starve the REPL and every other task. Every step the
+// evaluator takes is counted; every yield_interval steps it yields once, and
+// past the fuel limit the call fails. Every step also checks for a cancel.
+async fn count_step(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    if interp.cancel.take() {
+        return Err(RuntimeError::Interrupted);
+    }
+    interp.steps += 1;
+    if interp.fuel.is_some_and(|fuel| interp.steps > fuel) {
+        return Err(RuntimeError::FuelExhausted {
+            steps: interp.steps - 1,
+        });
+    }
+    if interp
+        .yield_interval
+        .is_some_and(|interval| interp.steps.is_multiple_of(interval))
+    {
+        #[cfg(feature = "std")]
+        crate::virtual_time::note_activity();
+        YieldNow(false).await;
+    }
+    Ok(())
+}
+
+// Returns Pending exactly once, asking to be polled again straight away
+// Works the same under tokio and Embassy, since it only uses the waker.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+// Fuel is per outermost execute call: nested calls (a builtin running a
+// quotation) spend from the same budget rather than getting a new one
+fn enter_execution(interp: &mut AsyncInterpreter) {
+    if interp.execution_depth == 0 {
+        interp.steps = 0;
+    }
+    interp.execution_depth += 1;
+}
+
+fn leave_execution(interp: &mut AsyncInterpreter) {
+    interp.execution_depth -= 1;
+}
+
+// RUST CONCEPT: Call frames that respect tail calls
+// If the caller's PopCallFrame is the very next continuation, nothing of the
+// caller remains to run, so the callee takes over the caller's frame (and its
+// PopCallFrame) and the frame stack stays flat. Otherwise a new frame is pushed.
+// Either way the caller then schedules exactly one PopCallFrame for this word.
+// Returns whether this was a tail call.
+fn enter_call_frame(
+    word: Rc<str>,
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) -> bool {
+    let mut frame = CallFrame {
+        word,
+        pos: interp.current_pos.clone(),
+        source: interp.current_source.clone(),
+        tail_calls: 0,
+    };
+
+    if matches!(continuation_stack.last(), Some(Continuation::PopCallFrame))
+        && let Some(caller) = interp.call_frames.last_mut()
+    {
+        continuation_stack.pop();
+        frame.tail_calls = caller.tail_calls + 1;
+        *caller = frame;
+        true
+    } else {
+        interp.call_frames.push(frame);
+        false
+    }
+}
+
+// TAIL-CALL OPTIMIZATION: Whether all that is left of the innermost local
+// frame is popping it (and the call frame of the definition it belongs to).
+// Code run from here can use that frame instead of pushing one of its own.
+fn local_frame_finished(continuation_stack: &[Continuation]) -> bool {
+    matches!(
+        continuation_stack,
+        [.., Continuation::PopLocalFrame] | [.., Continuation::PopLocalFrame, Continuation::PopCallFrame]
+    )
+}
+
+// RUST CONCEPT: Attaching source location to errors
+// Wraps an error with the most recent source word the evaluator executed.
+// Errors that are already located (e.g. from a nested execute) keep their
+// innermost location, and quit is left alone so callers can match on it.
+fn locate_error(error: RuntimeError, interp: &AsyncInterpreter) -> RuntimeError {
+    if matches!(
+        error,
+        RuntimeError::Located { .. } | RuntimeError::QuitRequested | RuntimeError::Leave
+    ) {
+        return error;
+    }
+
+    match (&interp.current_word, &interp.current_pos) {
+        (Some(word), Some(pos)) => RuntimeError::Located {
+            error: Box::new(error),
+            word: word.clone(),
+            pos: pos.clone(),
+            source: interp.current_source.clone(),
+            backtrace: interp.call_frames.clone(),
+        },
+        _ => error,
+    }
+}
+
+// RUST CONCEPT: Error recovery by unwinding the continuation stack
+// Discards pending continuations until the innermost `try` handler is found.
+// The data stack, return stack, and local frames are cut back to their depths
+// when `try` was entered, the error is pushed as a value, and the handler runs.
+// Without a handler the error propagates to the caller unchanged.
+fn unwind_to_handler(
+    error: RuntimeError,
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) -> Result<(), RuntimeError> {
+    // quit is a request to leave, not a failure, and running out of fuel or
+    // being interrupted must stop the whole call - never catch any of them
+    if matches!(
+        error.root(),
+        RuntimeError::QuitRequested | RuntimeError::FuelExhausted { .. } | RuntimeError::Interrupted
+    ) {
+        return Err(error);
+    }
+
+    while let Some(continuation) = continuation_stack.pop() {
+        if let Continuation::Handler {
+            handler,
+            stack_depth,
+            return_depth,
+            frame_depth,
+            call_depth,
+            loop_depth,
+        } = continuation
+        {
+            interp.stack.truncate(stack_depth);
+            interp.return_stack.truncate(return_depth);
+            interp.local_frames.truncate(frame_depth);
+            interp.call_frames.truncate(call_depth);
+            interp.loop_indices.truncate(loop_depth);
+            interp.push(error.into_value());
+            continuation_stack.push(Continuation::Exec(handler));
+            return Ok(());
+        }
+    }
+
+    Err(error)
+}
+
 // ASYNC CONCEPT: Helper function to execute a value directly and manage continuations
 // This is where atoms are looked up and special forms are handled
 // Now async to support async builtins!
@@ -228,22 +480,28 @@ async fn execute_value_direct(
             interp.push(Value::Array(array.clone()));
             Ok(())
         }
+        Value::Map(map) => {
+            interp.push(Value::Map(map.clone()));
+            Ok(())
+        }
         Value::Variable(var) => {
             interp.push(Value::Variable(var.clone()));
             Ok(())
         }
         Value::Pair(_, _) | Value::Nil => {
-            interp.push(value.clone());
+            let quotation = quotation_value(value, interp);
+            interp.push(quotation);
             Ok(())
         }
         Value::QuotedAtom(atom_name) => {
-            interp.push(Value::Atom(atom_name.clone()));
+            interp.push(Value::Atom(atom_name.clone(), None));
             Ok(())
         }
         // ASYNC CONCEPT: Async builtins must be awaited!
         Value::AsyncBuiltin(func) => func(interp).await,
         // ASYNC CONCEPT: Atoms need async lookup since they might execute async builtins
-        Value::Atom(atom_name) => {
+        Value::Atom(atom_name, span) => {
+            note_position(atom_name, span, interp);
             execute_atom_with_continuations(atom_name, interp, continuation_stack).await
         }
         // RUST CONCEPT: Records and record types push themselves
@@ -261,100 +519,638 @@ async fn execute_value_direct(
             interp.push(value.clone());
             Ok(())
         }
+        // ASYNC CONCEPT: Task handles, channels and closures push themselves
+        Value::Task(_) | Value::Channel(_) | Value::Closure(_) => {
+            interp.push(value.clone());
+            Ok(())
+        }
     }
 }
 
-// RUST CONCEPT: Convert list structure to vector for sequential processing
-fn list_to_vec(list: &Value) -> Result<Vec<Value>, RuntimeError> {
-    let mut current = list.clone();
-    let mut items = Vec::new();
+// Remember where a word was written for error reporting
+// Atoms without a span (generated code) leave the caller's location in place
+fn note_position(name: &Rc<str>, span: &Option<Rc<SourceSpan>>, interp: &mut AsyncInterpreter) {
+    if let Some(span) = span {
+        interp.current_pos = Some(span.start.clone());
+        interp.current_word = Some(name.clone());
+        interp.current_source = span.source.clone();
+    }
+}
 
-    loop {
-        match current {
-            Value::Pair(car, cdr) => {
-                items.push((*car).clone());
-                current = (*cdr).clone();
+// ASYNC CONCEPT: Running one op of compiled code
+// Words and special forms behave exactly like the atoms they were compiled
+// from; they just skip the work the compiler already did.
+async fn execute_op(
+    op: &Op,
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) -> Result<(), RuntimeError> {
+    match op {
+        Op::Value(value) => execute_value_direct(value, interp, continuation_stack).await,
+        Op::Word(word) => {
+            note_position(&word.name, &word.span, interp);
+            if let Some(value) = lookup_local(&word.name, interp) {
+                interp.push(value);
+                return Ok(());
             }
-            Value::Nil => break,
-            _ => {
-                // Single value (improper list) - just return it as single item
-                items.push(current);
-                break;
+            let binding = word.binding(interp)?;
+            run_binding(&word.name, binding, interp, continuation_stack).await
+        }
+        Op::Special(special) => {
+            note_position(&special.name, &special.span, interp);
+            execute_special_form(special.form, Some(&special.quotes), interp, continuation_stack)
+        }
+    }
+}
+
+// ASYNC CONCEPT: The bytecode VM loop
+// Runs instructions in place - pushing literals, awaiting builtins, following
+// jumps - for as long as they only touch the data stack. An instruction that
+// has to schedule work (calling a definition, a special form) first schedules
+// the rest of this chunk, unless it is in tail position, and then hands
+// control back to the continuation loop. Errors propagate to that loop too,
+// which unwinds to try handlers exactly as for the tree walker.
+async fn run_bytecode(
+    chunk: Chunk,
+    mut pc: usize,
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) -> Result<(), RuntimeError> {
+    while let Some(instr) = chunk.get(pc) {
+        pc += 1;
+        count_step(interp).await?;
+        let (word, tail) = match instr {
+            Instr::PushLiteral(value) => {
+                let value = quotation_value(value, interp);
+                interp.push(value);
+                continue;
+            }
+            Instr::CallBuiltin(func) => {
+                func(interp).await?;
+                continue;
+            }
+            Instr::Jump(target) => {
+                pc = *target;
+                continue;
+            }
+            Instr::BranchIfFalse { target, word } => {
+                note_position(&word.name, &word.span, interp);
+                let condition = interp.pop()?;
+                if !interp.is_truthy(&condition) {
+                    pc = *target;
+                }
+                continue;
+            }
+            Instr::LocalSet { name, lval } => {
+                note_position(&lval.name, &lval.span, interp);
+                interp.push(Value::Atom(name.clone(), None));
+                // Only a shortcut while lval still means the primitive
+                if lookup_local(&lval.name, interp).is_none()
+                    && let Ok(Binding::Builtin(func)) = lval.binding(interp)
+                    && core::ptr::fn_addr_eq(func, lval_builtin as AsyncPrimitiveFn)
+                {
+                    lval_impl(interp)?;
+                    continue;
+                }
+                (lval, false)
+            }
+            Instr::LocalGet(word) => {
+                let local = interp
+                    .local_frames
+                    .last()
+                    .and_then(|frame| frame.get(&word.name).cloned());
+                if let Some(value) = local {
+                    note_position(&word.name, &word.span, interp);
+                    interp.push(value);
+                    continue;
+                }
+                // The local-set didn't run (it was in a branch not taken)
+                (word, false)
+            }
+            Instr::CallWord(word) => (word, false),
+            Instr::TailCall(word) => (word, true),
+            Instr::Special { special, tail } => {
+                note_position(&special.name, &special.span, interp);
+                if !tail {
+                    schedule_rest(&chunk, pc, continuation_stack);
+                }
+                return execute_special_form(special.form, Some(&special.quotes), interp, continuation_stack);
+            }
+        };
+
+        // Calling a word: locals, constants and builtins are handled in place
+        note_position(&word.name, &word.span, interp);
+        if let Some(value) = lookup_local(&word.name, interp) {
+            interp.push(value);
+            continue;
+        }
+        match word.binding(interp)? {
+            Binding::Constant(value) => interp.push(value),
+            Binding::Builtin(func) => func(interp).await?,
+            binding => {
+                if !tail {
+                    schedule_rest(&chunk, pc, continuation_stack);
+                }
+                return run_binding(&word.name, binding, interp, continuation_stack).await;
             }
         }
     }
+    Ok(())
+}
 
-    Ok(items)
+fn schedule_rest(chunk: &Chunk, pc: usize, continuation_stack: &mut Vec<Continuation>) {
+    if pc < chunk.len() {
+        continuation_stack.push(Continuation::Bytecode {
+            chunk: chunk.clone(),
+            pc,
+        });
+    }
 }
 
 // ASYNC CONCEPT: Atom execution with continuation support (now async!)
+// Used for atoms that run outside compiled code, e.g. at the top level
 async fn execute_atom_with_continuations(
     atom_name: &Rc<str>,
     interp: &mut AsyncInterpreter,
     continuation_stack: &mut Vec<Continuation>,
 ) -> Result<(), RuntimeError> {
-    // RUST CONCEPT: Special handling for exec, if, and quit
-    if &**atom_name == "exec" {
-        let value = interp.pop()?;
-        continuation_stack.push(Continuation::Exec(value));
+    if let Some(form) = SpecialForm::from_name(atom_name) {
+        return execute_special_form(form, None, interp, continuation_stack);
+    }
+
+    if let Some(value) = lookup_local(atom_name, interp) {
+        interp.push(value);
         return Ok(());
     }
 
-    if &**atom_name == "if" {
-        let false_branch = interp.pop()?;
-        let true_branch = interp.pop()?;
-        let condition = interp.pop()?;
+    let binding = Binding::lookup(atom_name, interp)?;
+    run_binding(atom_name, binding, interp, continuation_stack).await
+}
 
-        let condition_result = interp.is_truthy(&condition);
-        continuation_stack.push(Continuation::If {
-            condition_result,
-            true_branch,
-            false_branch,
-        });
-        return Ok(());
+// RUST CONCEPT: Local frame lookup comes before the dictionary
+// Check local frames from top (most recent) to bottom (oldest)
+// Anything found there is a constant and is pushed directly
+fn lookup_local(atom_name: &Rc<str>, interp: &AsyncInterpreter) -> Option<Value> {
+    interp
+        .local_frames
+        .iter()
+        .rev()
+        .find_map(|frame| frame.get(atom_name).cloned())
+}
+
+async fn run_binding(
+    word: &Rc<str>,
+    binding: Binding,
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) -> Result<(), RuntimeError> {
+    match binding {
+        Binding::Constant(value) => {
+            // Non-executable entry - just push as constant
+            interp.push(value);
+            Ok(())
+        }
+        Binding::Builtin(func) => func(interp).await,
+        Binding::Definition(code) => {
+            // Record the call for backtraces (collapsing tail calls)
+            let tail_call = enter_call_frame(word.clone(), interp, continuation_stack);
+            if tail_call
+                && local_frame_finished(continuation_stack)
+                && let Some(frame) = interp.local_frames.last_mut()
+            {
+                // The caller's locals are finished with; start the callee afresh in their frame
+                frame.clear();
+            } else {
+                // Push local frame for definition execution
+                interp.local_frames.push(HashMap::new());
+                // Schedule frame cleanup after execution
+                continuation_stack.push(Continuation::PopLocalFrame);
+            }
+            continuation_stack.push(Continuation::PopCallFrame);
+            schedule_code(code, interp, continuation_stack);
+            Ok(())
+        }
+        Binding::Execute(value) => {
+            // Execute single value directly (tail-call optimized, no frame needed for
+            // single values); Exec so a closure defined as a word runs rather than pushes
+            continuation_stack.push(Continuation::Exec(value));
+            Ok(())
+        }
     }
+}
 
-    if &**atom_name == "quit" {
-        // Return special error to signal clean exit
-        return Err(RuntimeError::QuitRequested);
+// Runs compiled code with whichever backend the interpreter uses
+fn schedule_code(code: Code, interp: &AsyncInterpreter, continuation_stack: &mut Vec<Continuation>) {
+    match interp.backend {
+        Backend::TreeWalker => continuation_stack.push(Continuation::Code { code, index: 0 }),
+        Backend::Bytecode => continuation_stack.push(Continuation::Bytecode {
+            chunk: code.chunk(),
+            pc: 0,
+        }),
     }
+}
 
-    // RUST CONCEPT: Local frame lookup first, then dictionary lookup
-    // Check local frames from top (most recent) to bottom (oldest)
-    for frame in interp.local_frames.iter().rev() {
-        if let Some(value) = frame.get(atom_name) {
-            // Found in local frame - push the value directly (it's a constant)
-            interp.push(value.clone());
-            return Ok(());
+// Compiled code for a quotation, reusing the caller's cache when it has one
+fn quotation_code(quotation: &Value, cache: Option<&QuoteCache>) -> Code {
+    match cache {
+        Some(cache) => cache.code_for(quotation),
+        None => compile(quotation),
+    }
+}
+
+// Quotations that refer to bound locals are pushed as closures over them
+fn quotation_value(quotation: &Value, interp: &AsyncInterpreter) -> Value {
+    if let Value::Pair(_, _) = quotation
+        && let Some(closure) = capture(quotation, &interp.local_frames)
+    {
+        return Value::Closure(Rc::new(closure));
+    }
+    quotation.clone()
+}
+
+// A closure runs like its quotation, in a frame that starts with its captured locals
+// In tail position the captured locals go into the finished frame instead, so
+// recursion through a branch that refers to locals doesn't pile up frames.
+fn schedule_closure(
+    closure: &Closure,
+    cache: Option<&QuoteCache>,
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) {
+    if local_frame_finished(continuation_stack)
+        && let Some(frame) = interp.local_frames.last_mut()
+    {
+        frame.extend(closure.captured.iter().map(|(name, value)| (name.clone(), value.clone())));
+    } else {
+        interp.local_frames.push(closure.captured.clone());
+        continuation_stack.push(Continuation::PopLocalFrame);
+    }
+    let code = quotation_code(&closure.body, cache);
+    schedule_code(code, interp, continuation_stack);
+}
+
+// Convert a list to a code continuation or execute a single value directly
+fn schedule_exec(
+    value: Value,
+    cache: Option<&QuoteCache>,
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) {
+    match &value {
+        Value::Pair(_, _) => {
+            // Push local frame for quotation execution
+            interp.local_frames.push(HashMap::new());
+            // Schedule frame cleanup after execution
+            continuation_stack.push(Continuation::PopLocalFrame);
+            let code = quotation_code(&value, cache);
+            schedule_code(code, interp, continuation_stack);
+        }
+        Value::Nil => {
+            // Empty list - do nothing (no frame needed)
+        }
+        Value::Closure(closure) => schedule_closure(closure, cache, interp, continuation_stack),
+        _ => {
+            // Single value - execute directly (tail-call optimized, no frame needed)
+            continuation_stack.push(Continuation::Value(value));
         }
     }
+}
 
-    // Not in local frames - try dictionary lookup
-    let entry_copy = {
-        #[cfg(not(target_os = "none"))]
-        {
-            let dict = interp.dictionary.lock().unwrap();
-            dict.get(atom_name).cloned()
+// RUST CONCEPT: Special forms
+// exec, if, try, and quit work on the continuation stack directly, as do the
+// multi-way conditionals:
+//   [[test] [body] ... [default]] cond  - run the body of the first test left truthy
+//   value [key [body] ... [default]] case - run the body of the first key = value
+//   value [pattern [body] ...] match      - run the body of the first pattern that fits
+// and the counted loops:
+//   n [body] times               - run body n times
+//   limit start [body] do        - run body for i = start .. limit-1
+//   [body] loop                  - run body until leave
+//   start end [body] range-each  - like do, but also pushes i before each run
+//   leave                        - exit the innermost loop immediately
+// Inside a loop body, i is the current index and j the enclosing loop's index.
+// Compiled code passes its quotation caches so a form that keeps being handed
+// the same quotation only compiles it once.
+fn execute_special_form(
+    form: SpecialForm,
+    quotes: Option<&[QuoteCache; 2]>,
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) -> Result<(), RuntimeError> {
+    let quote = |slot: usize| quotes.map(|quotes| &quotes[slot]);
+
+    match form {
+        SpecialForm::Exec => {
+            let value = interp.pop()?;
+            schedule_exec(value, quote(0), interp, continuation_stack);
         }
-        #[cfg(target_os = "none")]
-        {
-            let dict = interp.dictionary.borrow();
-            dict.get(atom_name).cloned()
+        SpecialForm::If => {
+            let false_branch = interp.pop()?;
+            let true_branch = interp.pop()?;
+            let condition = interp.pop()?;
+
+            let (branch, slot) = if interp.is_truthy(&condition) {
+                (true_branch, 0)
+            } else {
+                (false_branch, 1)
+            };
+            schedule_branch(branch, quote(slot), interp, continuation_stack);
+        }
+        SpecialForm::Cond => {
+            // Stack: [[test] [body] ... [default]] cond
+            let (clauses, captured) = clause_list(interp.pop()?);
+            start_cond(clauses, captured, interp, continuation_stack)?;
+        }
+        SpecialForm::Case => {
+            // Stack: value [key [body] ... [default]] case
+            let (clauses, captured) = clause_list(interp.pop()?);
+            let value = interp.pop()?;
+            if let Some(body) = case_branch(&value, clauses)? {
+                schedule_branch(with_captured(body, &captured), None, interp, continuation_stack);
+            }
+        }
+        SpecialForm::Match => {
+            // Stack: value [pattern [body] ...] match
+            let (clauses, captured) = clause_list(interp.pop()?);
+            let value = interp.pop()?;
+            let (body, bindings) = match_branch(&value, clauses)?;
+            if bindings.is_empty() {
+                schedule_branch(with_captured(body, &captured), None, interp, continuation_stack);
+            } else {
+                // The body runs in a fresh frame holding what the pattern bound,
+                // on top of any locals the clause list captured
+                let mut frame = captured.map(|closure| closure.captured.clone()).unwrap_or_default();
+                frame.extend(bindings);
+                let closure = Closure { body, captured: frame };
+                schedule_closure(&closure, None, interp, continuation_stack);
+            }
+        }
+        SpecialForm::Try => {
+            // Stack: [body] [handler] try
+            let handler = interp.pop()?;
+            let body = interp.pop()?;
+
+            // Record depths after popping the operands so unwinding drops
+            // anything the body left behind before the handler sees the error
+            continuation_stack.push(Continuation::Handler {
+                handler,
+                stack_depth: interp.stack.len(),
+                return_depth: interp.return_stack.len(),
+                frame_depth: interp.local_frames.len(),
+                call_depth: interp.call_frames.len(),
+                loop_depth: interp.loop_indices.len(),
+            });
+            schedule_exec(body, quote(0), interp, continuation_stack);
+        }
+        SpecialForm::Quit => {
+            // Return special error to signal clean exit
+            return Err(RuntimeError::QuitRequested);
+        }
+        SpecialForm::Times => {
+            let body = loop_body(interp.pop()?, quote(0));
+            let count = pop_loop_bound(interp, "times")?;
+            start_loop(body, 0, Some(count), false, interp, continuation_stack);
+        }
+        SpecialForm::Do => {
+            let body = loop_body(interp.pop()?, quote(0));
+            let start = pop_loop_bound(interp, "do")?;
+            let limit = pop_loop_bound(interp, "do")?;
+            start_loop(body, start, Some(limit), false, interp, continuation_stack);
+        }
+        SpecialForm::Loop => {
+            let body = loop_body(interp.pop()?, quote(0));
+            start_loop(body, 0, None, false, interp, continuation_stack);
+        }
+        SpecialForm::RangeEach => {
+            let body = loop_body(interp.pop()?, quote(0));
+            let end = pop_loop_bound(interp, "range-each")?;
+            let start = pop_loop_bound(interp, "range-each")?;
+            start_loop(body, start, Some(end), true, interp, continuation_stack);
+        }
+        SpecialForm::Leave => leave_loop(interp, continuation_stack)?,
+    }
+    Ok(())
+}
+
+// TAIL-CALL OPTIMIZATION: Runs the branch if, cond or case picked directly in
+// place of the form, without a frame or anything scheduled after it. A branch
+// with a parameter header runs like exec instead, so its locals stay its own.
+fn schedule_branch(
+    branch: Value,
+    cache: Option<&QuoteCache>,
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) {
+    match &branch {
+        Value::Pair(_, _) if takes_parameters(&branch) => {
+            schedule_exec(branch, cache, interp, continuation_stack)
         }
+        Value::Pair(_, _) => {
+            let code = quotation_code(&branch, cache);
+            schedule_code(code, interp, continuation_stack);
+        }
+        Value::Nil => {}
+        Value::Closure(closure) => schedule_closure(closure, cache, interp, continuation_stack),
+        _ => continuation_stack.push(Continuation::Value(branch)),
+    }
+}
+
+// A cond or case clause list, and the closure it came from if it was one
+fn clause_list(clauses: Value) -> (Value, Option<Rc<Closure>>) {
+    match clauses {
+        Value::Closure(closure) => (closure.body.clone(), Some(closure)),
+        other => (other, None),
+    }
+}
+
+// A test or body from a clause list that captured locals keeps them
+fn with_captured(quotation: Value, captured: &Option<Rc<Closure>>) -> Value {
+    match captured {
+        Some(closure) if matches!(quotation, Value::Pair(_, _)) => Value::Closure(Rc::new(Closure {
+            body: quotation,
+            captured: closure.captured.clone(),
+        })),
+        _ => quotation,
+    }
+}
+
+// Runs the next cond test, with a Cond continuation waiting on its result,
+// or the default when only that is left
+fn start_cond(
+    clauses: Value,
+    captured: Option<Rc<Closure>>,
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) -> Result<(), RuntimeError> {
+    match clauses {
+        Value::Nil => Ok(()),
+        Value::Pair(test, rest) => match &*rest {
+            Value::Pair(body, rest) => {
+                let test = with_captured((*test).clone(), &captured);
+                continuation_stack.push(Continuation::Cond {
+                    body: (**body).clone(),
+                    rest: (**rest).clone(),
+                    captured,
+                });
+                schedule_branch(test, None, interp, continuation_stack);
+                Ok(())
+            }
+            Value::Nil => {
+                schedule_branch(with_captured((*test).clone(), &captured), None, interp, continuation_stack);
+                Ok(())
+            }
+            _ => Err(cond_error("cond")),
+        },
+        _ => Err(cond_error("cond")),
+    }
+}
+
+// The body of the first case clause whose key is value, or the default if
+// none is; keys are compared with =, lists included, and a quoted atom key
+// matches the atom
+fn case_branch(value: &Value, clauses: Value) -> Result<Option<Value>, RuntimeError> {
+    let matches = |key: &Value| match key {
+        Value::QuotedAtom(name) => matches!(value, Value::Atom(atom, _) if atom == name),
+        key => key == value,
     };
 
-    match entry_copy {
-        Some(entry) => {
-            if entry.is_executable {
-                // Push definition execution continuation
-                continuation_stack.push(Continuation::Definition(entry.value));
-            } else {
-                // Non-executable entry - just push as constant
-                interp.push(entry.value);
+    let mut current = clauses;
+    loop {
+        let (key, rest) = match current {
+            Value::Nil => return Ok(None),
+            Value::Pair(key, rest) => (key, rest),
+            _ => return Err(cond_error("case")),
+        };
+        match &*rest {
+            Value::Pair(body, rest) => {
+                if matches(&key) {
+                    return Ok(Some((**body).clone()));
+                }
+                current = (**rest).clone();
             }
-            Ok(())
+            Value::Nil => return Ok(Some((*key).clone())),
+            _ => return Err(cond_error("case")),
+        }
+    }
+}
+
+// The body of the first match clause whose pattern value matches (see
+// patterns.rs), with the names the pattern bound
+fn match_branch(value: &Value, clauses: Value) -> Result<(Value, HashMap<Rc<str>, Value>), RuntimeError> {
+    let malformed = || RuntimeError::TypeError("match expects a list of pattern [body] pairs".to_string());
+
+    let mut current = &clauses;
+    loop {
+        match current {
+            Value::Pair(pattern, rest) => {
+                let Value::Pair(body, rest) = &**rest else {
+                    return Err(malformed());
+                };
+                let mut bindings = HashMap::new();
+                if match_pattern(pattern, value, &mut bindings)? {
+                    return Ok(((**body).clone(), bindings));
+                }
+                current = rest;
+            }
+            Value::Nil => {
+                return Err(RuntimeError::DomainError(format!("match: no pattern matches {}", value)))
+            }
+            _ => return Err(malformed()),
         }
-        None => Err(RuntimeError::UndefinedWord((**atom_name).to_string())),
+    }
+}
+
+fn cond_error(word: &str) -> RuntimeError {
+    let clause = if word == "cond" { "[test] [body]" } else { "key [body]" };
+    RuntimeError::TypeError(format!(
+        "{} expects a list of {} pairs, optionally followed by a default [body]",
+        word, clause
+    ))
+}
+
+// A loop body's code, and the closure it came from if it was one
+fn loop_body(body: Value, cache: Option<&QuoteCache>) -> (Code, Option<Rc<Closure>>) {
+    match body {
+        Value::Closure(closure) => (quotation_code(&closure.body, cache), Some(closure)),
+        other => (quotation_code(&other, cache), None),
+    }
+}
+
+fn start_loop(
+    (body, captured): (Code, Option<Rc<Closure>>),
+    start: i64,
+    limit: Option<i64>,
+    push_index: bool,
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) {
+    let loop_depth = interp.loop_indices.len();
+    interp.loop_indices.push(start);
+    continuation_stack.push(Continuation::Loop {
+        body,
+        captured,
+        index: start,
+        limit,
+        push_index,
+        frame_depth: interp.local_frames.len(),
+        call_depth: interp.call_frames.len(),
+        loop_depth,
+    });
+}
+
+// Discard everything scheduled inside the innermost loop, including the loop itself
+// When the loop is outside this evaluation (leave inside a quotation run by a
+// builtin like map), RuntimeError::Leave carries the request out to it instead.
+fn leave_loop(
+    interp: &mut AsyncInterpreter,
+    continuation_stack: &mut Vec<Continuation>,
+) -> Result<(), RuntimeError> {
+    let Some(at) = continuation_stack
+        .iter()
+        .rposition(|continuation| matches!(continuation, Continuation::Loop { .. }))
+    else {
+        return Err(if interp.loop_indices.is_empty() {
+            RuntimeError::DomainError("leave used outside of a loop".to_string())
+        } else {
+            RuntimeError::Leave
+        });
+    };
+
+    if let Some(Continuation::Loop {
+        frame_depth,
+        call_depth,
+        loop_depth,
+        ..
+    }) = continuation_stack.drain(at..).next()
+    {
+        interp.local_frames.truncate(frame_depth);
+        interp.call_frames.truncate(call_depth);
+        interp.loop_indices.truncate(loop_depth);
+    }
+    Ok(())
+}
+
+fn pop_loop_bound(interp: &mut AsyncInterpreter, word: &str) -> Result<i64, RuntimeError> {
+    use num_traits::ToPrimitive;
+    match interp.pop()? {
+        Value::Int32(i) => Ok(i as i64),
+        Value::Integer(i) => i.to_i64().ok_or_else(|| {
+            RuntimeError::TypeError(format!("{}: loop bound out of range", word))
+        }),
+        Value::Number(n) if n.fract() == 0.0 && n.is_finite() => Ok(n as i64),
+        other => Err(RuntimeError::TypeError(format!(
+            "{}: loop bound must be an integer, got {}",
+            word,
+            other.type_name()
+        ))),
+    }
+}
+
+// Loop indices are exposed to Uni code as Int32 when they fit
+pub(crate) fn index_value(index: i64) -> Value {
+    match i32::try_from(index) {
+        Ok(i) => Value::Int32(i),
+        Err(_) => Value::Integer(num_bigint::BigInt::from(index)),
     }
 }
 
@@ -362,7 +1158,46 @@ async fn execute_atom_with_continuations(
 // This is the main entry point for executing Uni values
 // Now uses async continuation-based execution for tail-call optimization
 pub async fn execute(value: &Value, interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
-    execute_with_continuations(value, interp).await
+    execute_from(Continuation::Value(value.clone()), interp).await
+}
+
+// ASYNC CONCEPT: Calling a quotation from Rust
+// Runs the value exactly as `exec` would (lists run as code with their own local
+// frame). Builtins such as map and filter use this to call user quotations, so
+// async words inside the quotation work and errors unwind to outer try handlers.
+pub async fn execute_quotation(
+    quotation: &Value,
+    interp: &mut AsyncInterpreter,
+) -> Result<(), RuntimeError> {
+    execute_from(Continuation::Exec(quotation.clone()), interp).await
+}
+
+async fn execute_from(
+    initial: Continuation,
+    interp: &mut AsyncInterpreter,
+) -> Result<(), RuntimeError> {
+    // Start without a location so errors never point at stale code, then put the
+    // caller's back so a builtin running nested code still reports its own call site
+    let saved_pos = interp.current_pos.take();
+    let saved_word = interp.current_word.take();
+    let saved_source = interp.current_source.take();
+
+    let frame_depth = interp.local_frames.len();
+    let call_depth = interp.call_frames.len();
+    let loop_depth = interp.loop_indices.len();
+
+    enter_execution(interp);
+    let result = run_continuations(initial, interp).await;
+    leave_execution(interp);
+
+    // An uncaught error abandons its frames; the backtrace already has a copy
+    interp.local_frames.truncate(frame_depth);
+    interp.call_frames.truncate(call_depth);
+    interp.loop_indices.truncate(loop_depth);
+    interp.current_pos = saved_pos;
+    interp.current_word = saved_word;
+    interp.current_source = saved_source;
+    result
 }
 
 // ASYNC CONCEPT: Top-level async execution function
@@ -378,12 +1213,29 @@ pub async fn execute_string(code: &str, interp: &mut AsyncInterpreter) -> Result
     let values = parse(code, interp)?;
 
     // ASYNC CONCEPT: Execute each value asynchronously
-    // We execute each top-level value in sequence
-    // If any execution fails, the ? will return that error immediately
-    for value in values {
+    // We execute each top-level value in sequence, all on one fuel budget
+    // If any execution fails, we stop and return that error immediately
+    enter_execution(interp);
+    let result = execute_values(values, interp).await;
+    leave_execution(interp);
+    result
+}
+
+// Runs top-level values in order, expanding macros (see macros.rs) just
+// before each one so a macro applies as soon as it is defined
+async fn execute_values(values: Vec<Value>, interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let mut pending = values;
+    pending.reverse(); // Next value last
+    while let Some(value) = pending.pop() {
+        if is_macro(&value, interp) {
+            let rest = interp.make_list(pending.drain(..).rev().collect());
+            pending = run_macro(&value, rest, interp).await?;
+            pending.reverse();
+            continue;
+        }
+        let value = expand(value, interp).await?;
         execute(&value, interp).await?;
     }
-
     Ok(())
 }
 
@@ -440,7 +1292,7 @@ mod tests {
         let list = interp.make_list(vec![
             Value::Number(1.0),
             Value::Number(2.0),
-            Value::Atom(plus_atom),
+            Value::Atom(plus_atom, None),
         ]);
 
         execute(&list, &mut interp).await.unwrap();
@@ -465,7 +1317,7 @@ mod tests {
 
         // Get the + builtin from dictionary and execute it
         let plus_atom = interp.intern_atom("+");
-        execute(&Value::Atom(plus_atom), &mut interp).await.unwrap();
+        execute(&Value::Atom(plus_atom, None), &mut interp).await.unwrap();
 
         // Should have popped 3 and 5, pushed 8
         let result = interp.pop().unwrap();
@@ -479,7 +1331,7 @@ mod tests {
 
         // RUST CONCEPT: Testing error cases
         let undefined_atom = interp.intern_atom("nonexistent");
-        let result = execute(&Value::Atom(undefined_atom), &mut interp).await;
+        let result = execute(&Value::Atom(undefined_atom, None), &mut interp).await;
 
         assert!(result.is_err());
         assert!(
@@ -598,6 +1450,271 @@ mod tests {
         assert!(matches!(result, Value::Int32(99))); // Small integers use Int32
     }
 
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_try_catches_error_in_deep_tail_recursion() {
+        let mut interp = setup_interpreter().await;
+
+        // The error is raised 1000 tail calls deep; unwinding must still find the handler
+        execute_string(
+            "'sink [dup 0 <= [1 swap /] [1 - sink] if] def",
+            &mut interp,
+        )
+        .await
+        .unwrap();
+
+        execute_string("[1000 sink] [error-kind] try", &mut interp).await.unwrap();
+        let result = interp.pop().unwrap();
+        assert!(matches!(result, Value::String(s) if s.as_ref() == "division-by-zero"));
+        assert!(interp.stack.is_empty());
+        assert!(interp.local_frames.is_empty());
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_error_in_handler_propagates() {
+        let mut interp = setup_interpreter().await;
+
+        // A handler that fails itself is not caught by its own try
+        let error = execute_string("[1 throw] [drop 2 throw] try", &mut interp)
+            .await
+            .unwrap_err();
+        assert!(matches!(error.root(), RuntimeError::Thrown(Value::Int32(2))));
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_execute_string_tracks_current_pos() {
+        let mut interp = setup_interpreter().await;
+
+        // pop_with_context reads current_pos while the failing word runs
+        let error = execute_string("1\n  *", &mut interp).await.unwrap_err();
+        match error.root() {
+            RuntimeError::StackUnderflowAt { pos, .. } => {
+                assert_eq!((pos.line, pos.column), (2, 3));
+            }
+            other => panic!("Expected StackUnderflowAt, got {:?}", other),
+        }
+
+        // Locations don't leak out of a run
+        assert!(interp.current_pos.is_none());
+        assert!(interp.current_word.is_none());
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_prelude_errors_name_the_prelude() {
+        let mut interp = setup_interpreter().await;
+
+        // while is a prelude word; the failing word inside it is located there
+        let error = execute_string("[1 0 /] [] while", &mut interp).await.unwrap_err();
+        assert!(matches!(error, RuntimeError::Located { .. }));
+        assert!(matches!(error.root(), RuntimeError::DivisionByZero));
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_tail_calls_collapse_into_one_frame() {
+        let mut interp = setup_interpreter().await;
+
+        execute_string(
+            "'spin [dup 0 <= [1 swap /] [1 - spin] if] def",
+            &mut interp,
+        )
+        .await
+        .unwrap();
+
+        let error = execute_string("1000 spin", &mut interp).await.unwrap_err();
+        let backtrace = error.backtrace();
+        assert_eq!(backtrace.len(), 1);
+        assert_eq!(backtrace[0].word.as_ref(), "spin");
+        assert_eq!(backtrace[0].tail_calls, 1000);
+
+        // Frames are released once the error leaves execute
+        assert!(interp.call_frames.is_empty());
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_non_tail_calls_push_frames() {
+        let mut interp = setup_interpreter().await;
+
+        execute_string("'down [dup 0 <= [1 swap /] [1 - down 1 +] if] def", &mut interp)
+            .await
+            .unwrap();
+
+        let error = execute_string("3 down", &mut interp).await.unwrap_err();
+        let words: Vec<&str> = error.backtrace().iter().map(|f| f.word.as_ref()).collect();
+        assert_eq!(words, ["down", "down", "down", "down"]);
+        assert!(error.backtrace().iter().all(|f| f.tail_calls == 0));
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_call_frames_balanced_after_success_and_try() {
+        let mut interp = setup_interpreter().await;
+
+        execute_string("'boom [1 0 /] def 'wrap [boom 1] def", &mut interp)
+            .await
+            .unwrap();
+        execute_string("[wrap] [drop 7] try 5 3 +", &mut interp).await.unwrap();
+        assert!(interp.call_frames.is_empty());
+        assert!(matches!(interp.pop().unwrap(), Value::Int32(8)));
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_many_loop_iterations_keep_state_flat() {
+        let mut interp = setup_interpreter().await;
+
+        execute_string("0 10000 [1 +] times", &mut interp).await.unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Int32(10000)));
+        assert!(interp.local_frames.is_empty());
+        assert!(interp.loop_indices.is_empty());
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_failed_loop_leaves_no_local_frame() {
+        let mut interp = setup_interpreter().await;
+
+        assert!(execute_string("5 [i 0 /] times", &mut interp).await.is_err());
+        assert!(interp.local_frames.is_empty());
+        assert!(interp.loop_indices.is_empty());
+
+        // The loop index went with its frame, so i is the imaginary unit again
+        interp.stack.clear();
+        execute_string("i", &mut interp).await.unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "i");
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_fuel_stops_busy_loop_and_try_cannot_catch_it() {
+        let mut interp = setup_interpreter().await;
+        interp.fuel = Some(10_000);
+
+        let error = execute_string("[[true] [] while] [drop 0] try", &mut interp)
+            .await
+            .unwrap_err();
+        assert!(matches!(error.root(), RuntimeError::FuelExhausted { steps: 10_000 }));
+        assert_eq!(error.kind(), "fuel-exhausted");
+        assert!(interp.call_frames.is_empty());
+
+        // The next call gets a fresh budget
+        execute_string("1 2 +", &mut interp).await.unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Int32(3)));
+        assert!(interp.steps <= 3);
+
+        // The bytecode VM counts the steps it runs in place as well
+        interp.backend = Backend::Bytecode;
+        let error = execute_string("[[true] [] while] exec", &mut interp)
+            .await
+            .unwrap_err();
+        assert!(matches!(error.root(), RuntimeError::FuelExhausted { .. }));
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_long_running_code_yields_to_other_tasks() {
+        use core::cell::Cell;
+
+        for yield_interval in [Some(100), None] {
+            let mut interp = setup_interpreter().await;
+            interp.yield_interval = yield_interval;
+            let other_task_ran = Cell::new(false);
+
+            // join polls the second future only when the first returns Pending
+            let (ran_during_loop, ()) = tokio::join!(
+                async {
+                    execute_string("0 1000 [1 +] times", &mut interp).await.unwrap();
+                    other_task_ran.get()
+                },
+                async { other_task_ran.set(true) }
+            );
+            assert_eq!(ran_during_loop, yield_interval.is_some());
+        }
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_cancel_interrupts_busy_loop() {
+        let mut interp = setup_interpreter().await;
+        let cancel = interp.cancel.clone();
+
+        let (result, ()) = tokio::join!(
+            execute_string("[[true] [] while] [drop 0] try", &mut interp),
+            async {
+                tokio::task::yield_now().await;
+                cancel.cancel();
+            }
+        );
+        let error = result.unwrap_err();
+        assert!(matches!(error.root(), RuntimeError::Interrupted));
+        assert_eq!(error.kind(), "interrupted");
+
+        // The cancel is used up, so the interpreter carries on as normal
+        assert!(!interp.cancel.is_cancelled());
+        interp.stack.clear();
+        execute_string("2 3 *", &mut interp).await.unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Int32(6)));
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_cancel_interrupts_delay() {
+        let mut interp = setup_interpreter().await;
+        let cancel = interp.cancel.clone();
+
+        let started = std::time::Instant::now();
+        let (result, ()) = tokio::join!(execute_string("60000 delay", &mut interp), async {
+            tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+            cancel.cancel();
+        });
+        assert!(matches!(result.unwrap_err().root(), RuntimeError::Interrupted));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_bytecode_tail_calls_collapse_into_one_frame() {
+        let mut interp = setup_interpreter().await;
+        interp.backend = Backend::Bytecode;
+
+        // The tail call sits at the end of an inlined else branch
+        execute_string(
+            "'spin [dup 0 <= [1 swap /] [1 - spin] if] def",
+            &mut interp,
+        )
+        .await
+        .unwrap();
+
+        let error = execute_string("1000 spin", &mut interp).await.unwrap_err();
+        let backtrace = error.backtrace();
+        assert_eq!(backtrace.len(), 1);
+        assert_eq!(backtrace[0].tail_calls, 1000);
+        assert!(interp.call_frames.is_empty());
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[tokio::test]
+    async fn test_bytecode_locals_and_redefinition() {
+        let mut interp = setup_interpreter().await;
+        interp.backend = Backend::Bytecode;
+
+        execute_string(
+            "'step [1 +] def 'twice ['x lval x step x step +] def 5 twice",
+            &mut interp,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Int32(12)));
+
+        // Bytecode stays late-bound like the tree walker
+        execute_string("'step [10 *] def 5 twice", &mut interp).await.unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Int32(100)));
+    }
+
     #[cfg(not(target_os = "none"))]
     #[tokio::test]
     async fn test_execute_string_with_list() {
@@ -629,9 +1746,9 @@ mod tests {
         let quote_atom = interp.intern_atom("quote");
 
         let quoted_hello = Value::Pair(
-            Rc::new(Value::Atom(quote_atom)),
+            Rc::new(Value::Atom(quote_atom, None)),
             Rc::new(Value::Pair(
-                Rc::new(Value::Atom(hello_atom)),
+                Rc::new(Value::Atom(hello_atom, None)),
                 Rc::new(Value::Nil),
             )),
         );
@@ -15,7 +15,9 @@ pub fn register_linux_primitives(interp: &mut AsyncInterpreter) {
     interp.dict_insert(now_atom.clone(), DictEntry {
         value: Value::AsyncBuiltin(now_impl),
         is_executable: true,
+        is_immediate: false,
         doc: Some(Rc::<str>::from("( -- record ) Get current date/time as a record with fields: year month day hour minute second offset-minutes")),
+        code: None,
     });
 
     // Register set-time primitive
@@ -23,7 +25,9 @@ pub fn register_linux_primitives(interp: &mut AsyncInterpreter) {
     interp.dict_insert(set_time_atom.clone(), DictEntry {
         value: Value::AsyncBuiltin(set_time_impl),
         is_executable: true,
+        is_immediate: false,
         doc: Some(Rc::<str>::from("( year month day hour minute second -- ) Set system time (requires elevated privileges)")),
+        code: None,
     });
 }
 
@@ -78,10 +82,10 @@ pub fn set_time_impl(interp: &mut AsyncInterpreter) -> core::pin::Pin<crate::com
     let year = interp.pop_integer()? as i32;
 
     // Validate ranges
-    if month < 1 || month > 12 {
+    if !(1..=12).contains(&month) {
         return Err(RuntimeError::DomainError(format!("Invalid month: {}", month)));
     }
-    if day < 1 || day > 31 {
+    if !(1..=31).contains(&day) {
         return Err(RuntimeError::DomainError(format!("Invalid day: {}", day)));
     }
     if hour > 23 {
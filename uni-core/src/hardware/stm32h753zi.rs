@@ -14,7 +14,9 @@ pub fn register_stm32_primitives(interp: &mut AsyncInterpreter) {
     interp.dict_insert(now_atom.clone(), DictEntry {
         value: Value::AsyncBuiltin(now_impl),
         is_executable: true,
+        is_immediate: false,
         doc: Some(Rc::<str>::from("( -- record ) Get current date/time from RTC as a record with fields: year month day hour minute second offset-minutes")),
+        code: None,
     });
 
     // Register set-time primitive
@@ -22,7 +24,9 @@ pub fn register_stm32_primitives(interp: &mut AsyncInterpreter) {
     interp.dict_insert(set_time_atom.clone(), DictEntry {
         value: Value::AsyncBuiltin(set_time_impl),
         is_executable: true,
+        is_immediate: false,
         doc: Some(Rc::<str>::from("( year month day hour minute second -- ) Set RTC time")),
+        code: None,
     });
 }
 
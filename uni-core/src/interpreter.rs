@@ -1,8 +1,9 @@
-use crate::compat::{Rc, Arc, String, Vec, Box, ToString};
+use crate::compat::{format, Rc, Arc, String, Vec, Box, ToString};
 use crate::tokenizer::SourcePos;
 use crate::value::{RuntimeError, Value};
 use crate::output::AsyncOutput;
 use num_traits::Zero;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 #[cfg(target_os = "none")]
 use num_traits::Float;
@@ -26,7 +27,9 @@ use core::cell::RefCell;
 pub struct DictEntry {
     pub value: Value,
     pub is_executable: bool, // true = execute lists (def), false = push as data (val)
+    pub is_immediate: bool,  // true = a macro, run on the code after it before that runs (see macros.rs)
     pub doc: Option<Rc<str>>, // Optional documentation string for help
+    pub code: Option<crate::compile::Code>, // Compiled body of an executable list (set by def)
 }
 
 // Implement Debug manually since Value doesn't auto-derive Debug
@@ -35,11 +38,170 @@ impl core::fmt::Debug for DictEntry {
         f.debug_struct("DictEntry")
             .field("value", &self.value)
             .field("is_executable", &self.is_executable)
+            .field("is_immediate", &self.is_immediate)
             .field("doc", &self.doc)
+            .field("compiled", &self.code.is_some())
             .finish()
     }
 }
 
+// RUST CONCEPT: Versioning the dictionary for cached lookups
+// Compiled code remembers what each word resolved to along with the dictionary
+// version at the time. Every change takes a version no dictionary has had
+// before, so a cached lookup is only reused by the dictionary that made it and
+// only until that dictionary changes.
+static NEXT_DICTIONARY_VERSION: AtomicUsize = AtomicUsize::new(1);
+
+fn new_dictionary_version() -> usize {
+    NEXT_DICTIONARY_VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
+// RUST CONCEPT: Choosing how compiled code runs
+// Both backends run the same compiled lists with the same meaning; the bytecode
+// VM (bytecode.rs) trades a compile step for fewer continuations per call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    #[default]
+    TreeWalker, // One continuation per op of compiled Code
+    Bytecode,   // Flat instructions run in place by the VM loop
+}
+
+// How many evaluator steps run between yields to the executor by default
+// Small enough to keep the REPL and other tasks responsive, large enough that
+// the yield itself doesn't show up in profiles.
+pub const DEFAULT_YIELD_INTERVAL: u64 = 1024;
+
+// ASYNC CONCEPT: Interrupting a running evaluation
+// A clonable handle to one flag. Anything holding a clone - a SIGINT handler,
+// a task watching the terminal for Ctrl-C, another thread - can cancel; the
+// evaluator notices on its next step, clears the flag and fails with
+// RuntimeError::Interrupted. A word that is waiting (delay, task-join, a channel)
+// registers its waker here, so cancelling wakes it at once.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<CancelState>);
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    waker: CancelWaker,
+}
+
+// The waker of the one wait in progress; each token belongs to one interpreter,
+// which only ever waits on one thing at a time
+#[cfg(not(target_os = "none"))]
+#[derive(Default)]
+struct CancelWaker(Mutex<Option<core::task::Waker>>);
+
+#[cfg(not(target_os = "none"))]
+impl CancelWaker {
+    fn register(&self, waker: &core::task::Waker) {
+        let mut slot = self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match slot.as_mut() {
+            Some(current) if current.will_wake(waker) => {}
+            _ => *slot = Some(waker.clone()),
+        }
+    }
+
+    fn wake(&self) {
+        let waker = self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(target_os = "none")]
+struct CancelWaker(embassy_sync::waitqueue::AtomicWaker);
+
+#[cfg(target_os = "none")]
+impl Default for CancelWaker {
+    fn default() -> Self {
+        CancelWaker(embassy_sync::waitqueue::AtomicWaker::new())
+    }
+}
+
+#[cfg(target_os = "none")]
+impl CancelWaker {
+    fn register(&self, waker: &core::task::Waker) {
+        self.0.register(waker);
+    }
+
+    fn wake(&self) {
+        self.0.wake();
+    }
+}
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Release);
+        self.0.waker.wake();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Acquire)
+    }
+
+    // Forgets a cancel nobody has acted on (e.g. Ctrl-C pressed while idle)
+    pub fn reset(&self) {
+        self.0.cancelled.store(false, Ordering::Release);
+    }
+
+    // Clears the flag, reporting whether it was set
+    pub fn take(&self) -> bool {
+        self.0.cancelled.swap(false, Ordering::AcqRel)
+    }
+
+    // Completes once the token is cancelled, leaving the flag for take()
+    pub(crate) fn cancelled(&self) -> impl core::future::Future<Output = ()> + '_ {
+        core::future::poll_fn(|cx| {
+            // Register before looking, so a cancel in between still wakes us
+            self.0.waker.register(cx.waker());
+            if self.is_cancelled() {
+                core::task::Poll::Ready(())
+            } else {
+                core::task::Poll::Pending
+            }
+        })
+    }
+}
+
+impl core::fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("CancelToken").field(&self.is_cancelled()).finish()
+    }
+}
+
+// RUST CONCEPT: Uni-level call record for backtraces
+// One frame per active defined word. A call in tail position replaces the
+// caller's frame instead of adding one, so deep tail recursion stays flat.
+#[derive(Debug, Clone)]
+pub struct CallFrame {
+    pub word: Rc<str>,
+    pub pos: Option<SourcePos>, // Call site, if the calling atom came from source text
+    pub source: Option<Rc<str>>, // Source name of the call site, if known
+    pub tail_calls: usize, // Number of tail calls collapsed into this frame
+}
+
+impl core::fmt::Display for CallFrame {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.word)?;
+        match (&self.pos, &self.source) {
+            (Some(pos), Some(source)) => {
+                write!(f, " (called at {}:{}:{})", source, pos.line, pos.column)?
+            }
+            (Some(pos), None) => {
+                write!(f, " (called at line {}, column {})", pos.line, pos.column)?
+            }
+            _ => {}
+        }
+        match self.tail_calls {
+            0 => Ok(()),
+            1 => write!(f, " [1 tail call collapsed]"),
+            n => write!(f, " [{} tail calls collapsed]", n),
+        }
+    }
+}
+
 pub struct AsyncInterpreter {
     pub stack: Vec<Value>,
     pub return_stack: Vec<Value>, // Return stack for Forth-like operations
@@ -49,10 +211,24 @@ pub struct AsyncInterpreter {
     pub dictionary: Arc<Mutex<HashMap<Rc<str>, DictEntry>>>,
     #[cfg(target_os = "none")]
     pub dictionary: Arc<RefCell<HashMap<Rc<str>, DictEntry>>>,
+    pub dictionary_version: Arc<AtomicUsize>, // Shared along with the dictionary; see new_dictionary_version
     pub atoms: HashMap<String, Rc<str>>,
     pub local_frames: Vec<HashMap<Rc<str>, Value>>, // Stack of local variable frames for lexical scoping
+    pub call_frames: Vec<CallFrame>, // Active defined words, innermost last (for backtraces)
+    pub loop_indices: Vec<i64>, // Current index of each active counted loop, innermost last
+    pub backend: Backend, // Which evaluator runs definitions and quotations
+    pub yield_interval: Option<u64>, // Yield to the executor every this many steps (None never yields)
+    pub fuel: Option<u64>, // Most steps one execute call may take before failing with FuelExhausted
+    pub steps: u64, // Steps taken by the current (or last) outermost execute call
+    pub(crate) execution_depth: usize, // Nested execute calls in progress; the outermost resets steps
+    pub cancel: CancelToken, // Cancelling this interrupts whatever the interpreter is running
+    pub tasks: Rc<crate::tasks::TaskRegistry>, // Shared with every task spawned from here
     pub current_pos: Option<SourcePos>, // Track current execution position for error messages
+    pub current_word: Option<Rc<str>>, // Most recent source word executed (paired with current_pos)
+    pub current_source: Option<Rc<str>>, // Source name of current_word, if known
+    pub source_name: Option<Rc<str>>, // Name attached to code parsed from now on (e.g. a file path)
     pending_doc_target: Option<Rc<str>>, // Remember most recent definition for doc
+    pub(crate) macros_defined: bool, // Set once any word is made immediate, so code is only expanded after that
 
     // ASYNC CONCEPT: AsyncOutput instead of Output
     async_output: Option<Box<dyn AsyncOutput>>, // Optional async output for print/display (REPL mode)
@@ -65,7 +241,16 @@ pub struct AsyncInterpreter {
     pub spawner: Option<embassy_executor::Spawner>,
 }
 
+impl Default for AsyncInterpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AsyncInterpreter {
+    // The dictionary holds Rc<str> keys, so the Arc is never actually shared across threads;
+    // it is kept for parity with the no_std build where spawned tasks share the same handle
+    #[allow(clippy::arc_with_non_send_sync)]
     pub fn new() -> Self {
         let mut interpreter = Self {
             stack: Vec::new(),
@@ -74,10 +259,24 @@ impl AsyncInterpreter {
             dictionary: Arc::new(Mutex::new(HashMap::new())),
             #[cfg(target_os = "none")]
             dictionary: Arc::new(RefCell::new(HashMap::new())),
+            dictionary_version: Arc::new(AtomicUsize::new(new_dictionary_version())),
             atoms: HashMap::new(),
             local_frames: Vec::new(),
+            call_frames: Vec::new(),
+            loop_indices: Vec::new(),
+            backend: Backend::default(),
+            yield_interval: Some(DEFAULT_YIELD_INTERVAL),
+            fuel: None,
+            steps: 0,
+            execution_depth: 0,
+            cancel: CancelToken::default(),
+            tasks: Rc::new(crate::tasks::TaskRegistry::default()),
             current_pos: None,
+            current_word: None,
+            current_source: None,
+            source_name: None,
             pending_doc_target: None,
+            macros_defined: false,
             async_output: None,
             platform: crate::platform::Platform::default(), // Platform-specific hardware state
             #[cfg(feature = "target-stm32h753zi")]
@@ -94,7 +293,11 @@ impl AsyncInterpreter {
     // ASYNC CONCEPT: Async prelude loading
     // Must be called after new() to load prelude definitions
     pub async fn load_prelude(&mut self) -> Result<(), crate::value::RuntimeError> {
-        crate::prelude::load_prelude(self).await
+        // Errors inside prelude words should say so, not blame the user's file
+        let saved_source = self.source_name.replace("<prelude>".into());
+        let result = crate::prelude::load_prelude(self).await;
+        self.source_name = saved_source;
+        result
     }
 
     pub fn intern_atom(&mut self, text: &str) -> Rc<str> {
@@ -178,6 +381,18 @@ impl AsyncInterpreter {
         }
     }
 
+    // Pop a string, naming the word that wanted it if something else is there
+    pub fn pop_string(&mut self, word: &str) -> Result<Rc<str>, RuntimeError> {
+        match self.pop()? {
+            Value::String(s) => Ok(s),
+            other => Err(RuntimeError::TypeError(format!(
+                "{} expects a string, got {}",
+                word,
+                other.type_name()
+            ))),
+        }
+    }
+
     pub fn make_list(&self, items: Vec<Value>) -> Value {
         items.into_iter().rev().fold(Value::Nil, |acc, item| {
             Value::Pair(Rc::new(item), Rc::new(acc))
@@ -316,11 +531,23 @@ impl AsyncInterpreter {
     #[cfg(not(target_os = "none"))]
     pub fn dict_insert(&mut self, key: crate::compat::Rc<str>, entry: DictEntry) {
         self.dictionary.lock().unwrap().insert(key, entry);
+        self.dictionary_changed();
     }
 
     #[cfg(target_os = "none")]
     pub fn dict_insert(&mut self, key: crate::compat::Rc<str>, entry: DictEntry) {
         self.dictionary.borrow_mut().insert(key, entry);
+        self.dictionary_changed();
+    }
+
+    pub fn dictionary_version(&self) -> usize {
+        self.dictionary_version.load(Ordering::Relaxed)
+    }
+
+    // Call after changing the dictionary other than through dict_insert,
+    // so compiled code looks its words up again
+    pub fn dictionary_changed(&self) {
+        self.dictionary_version.store(new_dictionary_version(), Ordering::Relaxed);
     }
 }
 
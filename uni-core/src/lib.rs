@@ -32,6 +32,8 @@
 //! ```
 
 #![cfg_attr(target_os = "none", no_std)]
+// Tests use 3.14 and friends as ordinary sample literals, not as approximations of PI
+#![cfg_attr(test, allow(clippy::approx_constant))]
 
 #[cfg(target_os = "none")]
 extern crate alloc;
@@ -43,8 +45,18 @@ pub mod value;
 pub mod interpreter;
 pub mod tokenizer;
 pub mod parser;
+pub mod writer;
 pub mod builtins;
+pub mod bytecode;
+pub mod compile;
 pub mod evaluator;
+pub mod closures;
+pub mod macros;
+pub mod patterns;
+pub mod tasks;
+pub mod channels;
+#[cfg(feature = "std")]
+pub mod virtual_time;
 pub mod primitives;
 pub mod hardware;
 pub mod platform;
@@ -59,9 +71,11 @@ pub mod repl;
 mod compat;
 
 // Re-exports for convenience
-pub use interpreter::{AsyncInterpreter, DictEntry};
+pub use interpreter::{AsyncInterpreter, CallFrame, DictEntry};
 pub use value::{Value, RuntimeError};
 pub use output::AsyncOutput;
 #[cfg(feature = "std")]
 pub use stdout_output::StdoutOutput;
-pub use evaluator::{execute, execute_string};
+pub use evaluator::{execute, execute_quotation, execute_string};
+pub use parser::read_value;
+pub use writer::write_value;
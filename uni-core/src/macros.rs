@@ -0,0 +1,120 @@
+// Macros: immediate words that rewrite code before it runs
+//
+// `'name immediate` marks a defined word as a macro. Instead of running when
+// execution reaches it, a macro runs when parsed code is expanded: it gets
+// the code after it (up to the end of the enclosing list, or of the source at
+// top level) as a list on the stack, and leaves the list of code to use in
+// place of itself and that code. The replacement is expanded in turn, so
+// macros can expand into other macros.
+//
+// execute_string expands each top-level value just before running it, so a
+// macro defined earlier in a source applies to everything after it, and
+// nested lists (definition bodies, branches) are expanded all the way down.
+// A list written as data is expanded like any other. Until some word is made
+// immediate nothing is looked at, so sources without macros parse and run
+// exactly as before.
+
+use crate::compat::{format, Box, Rc, Vec};
+use crate::evaluator::execute;
+use crate::interpreter::AsyncInterpreter;
+use crate::value::{RuntimeError, Value};
+use core::future::Future;
+use core::pin::Pin;
+
+// Whether value is an atom naming an immediate word
+pub(crate) fn is_macro(value: &Value, interp: &AsyncInterpreter) -> bool {
+    match value {
+        Value::Atom(name, _) if interp.macros_defined => {
+            interp.dict_get(name).is_some_and(|entry| entry.is_immediate)
+        }
+        _ => false,
+    }
+}
+
+// Runs a macro on the code after it, giving the code to use instead
+pub(crate) async fn run_macro(
+    word: &Value,
+    rest: Value,
+    interp: &mut AsyncInterpreter,
+) -> Result<Vec<Value>, RuntimeError> {
+    interp.push(rest);
+    execute(word, interp).await?;
+    let replacement = interp.pop()?;
+    list_items(replacement.as_list()).ok_or_else(|| {
+        RuntimeError::TypeError(format!(
+            "macro {} must leave a list of code, got {}",
+            word,
+            replacement.type_name()
+        ))
+    })
+}
+
+// Expands the macros inside a list and every list nested in it
+pub(crate) fn expand<'a>(
+    value: Value,
+    interp: &'a mut AsyncInterpreter,
+) -> Pin<Box<dyn Future<Output = Result<Value, RuntimeError>> + 'a>> {
+    Box::pin(async move {
+        if !interp.macros_defined || !matches!(value, Value::Pair(_, _)) {
+            return Ok(value);
+        }
+
+        // Items still to look at, next one last
+        let mut pending = Vec::new();
+        let mut current = &value;
+        while let Value::Pair(car, cdr) = current {
+            pending.push((**car).clone());
+            current = cdr;
+        }
+        let mut tail = current.clone();
+        pending.reverse();
+
+        let mut expanded = Vec::with_capacity(pending.len());
+        let mut changed = false;
+        while let Some(item) = pending.pop() {
+            if is_macro(&item, interp) {
+                let rest = rebuild(pending.drain(..).rev(), tail.clone());
+                pending = run_macro(&item, rest, interp).await?;
+                pending.reverse();
+                // The replacement is a proper list, and took the old tail with it
+                tail = Value::Nil;
+                changed = true;
+                continue;
+            }
+
+            if let Value::Pair(car, cdr) = &item {
+                let nested = expand(item.clone(), interp).await?;
+                changed |= !matches!(&nested, Value::Pair(same_car, same_cdr)
+                    if Rc::ptr_eq(car, same_car) && Rc::ptr_eq(cdr, same_cdr));
+                expanded.push(nested);
+            } else {
+                expanded.push(item);
+            }
+        }
+
+        if !changed {
+            return Ok(value);
+        }
+        Ok(rebuild(expanded.into_iter(), tail))
+    })
+}
+
+fn rebuild(items: impl DoubleEndedIterator<Item = Value>, tail: Value) -> Value {
+    items.rev().fold(tail, |acc, item| Value::Pair(Rc::new(item), Rc::new(acc)))
+}
+
+// The items of a proper list
+fn list_items(list: &Value) -> Option<Vec<Value>> {
+    let mut items = Vec::new();
+    let mut current = list;
+    loop {
+        match current {
+            Value::Pair(car, cdr) => {
+                items.push((**car).clone());
+                current = cdr;
+            }
+            Value::Nil => return Some(items),
+            _ => return None,
+        }
+    }
+}
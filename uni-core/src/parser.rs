@@ -14,15 +14,22 @@
 // - 'Result<T, E>' is Rust's way of handling errors without exceptions
 // - Pattern matching with 'match' is Rust's equivalent to switch statements but much more powerful
 
-use crate::compat::{Rc, String, Vec, format, ToString};
+use crate::compat::{BTreeMap, Rc, String, Vec, format, ToString};
 use crate::interpreter::AsyncInterpreter;
-use crate::tokenizer::{Token, TokenKind, tokenize};
-use crate::value::{RuntimeError, Value};
+use crate::tokenizer::{SourceSpan, Token, TokenKind, tokenize};
+use crate::value::{MapKey, RuntimeError, Value};
 use num_bigint::BigInt;
 #[cfg(feature = "complex_numbers")]
 use num_complex::Complex64;
+#[cfg(feature = "complex_numbers")]
+use crate::tokenizer::parse_float_part;
 use num_rational::BigRational;
 
+#[cfg(not(target_os = "none"))]
+use std::cell::RefCell;
+#[cfg(target_os = "none")]
+use core::cell::RefCell;
+
 // RUST CONCEPT: Error types
 // We create our own error type for parser-specific errors
 // #[derive(Debug)] automatically implements Debug trait so we can print errors
@@ -59,10 +66,66 @@ impl From<ParseError> for RuntimeError {
     }
 }
 
+// RUST CONCEPT: Where the text being parsed came from
+// Source text gets a span on every atom so runtime errors can point back at it.
+// Generated code (e.g. from make-record-type) gets none, so errors inside it
+// are reported at the user's call site instead of inside the generated text.
+enum Origin {
+    Source(Option<Rc<str>>),
+    Generated,
+}
+
+impl Origin {
+    // Where an atom read from token came from, if anywhere
+    fn span(&self, token: &Token) -> Option<Rc<SourceSpan>> {
+        match self {
+            Origin::Source(source) => Some(Rc::new(SourceSpan {
+                start: token.pos.clone(),
+                end: token.end_pos.clone(),
+                source: source.clone(),
+            })),
+            Origin::Generated => None,
+        }
+    }
+}
+
 // RUST CONCEPT: Public functions
 // 'pub fn' makes this function available to other modules
 // This is our main entry point - takes a string, returns parsed Values
+// Atom spans are tagged with interp.source_name (e.g. the file being run)
 pub fn parse(input: &str, interp: &mut AsyncInterpreter) -> Result<Vec<Value>, ParseError> {
+    let origin = Origin::Source(interp.source_name.clone());
+    parse_with_origin(input, interp, &origin)
+}
+
+// Parse code built at runtime by primitives - atoms carry no source span
+pub fn parse_generated(
+    input: &str,
+    interp: &mut AsyncInterpreter,
+) -> Result<Vec<Value>, ParseError> {
+    parse_with_origin(input, interp, &Origin::Generated)
+}
+
+// RUST CONCEPT: Reading data, not code
+// Parses text that must hold exactly one value (e.g. user input or a line read
+// from a serial port). Nothing is executed and atoms carry no source span.
+pub fn read_value(input: &str, interp: &mut AsyncInterpreter) -> Result<Value, ParseError> {
+    let mut values = parse_with_origin(input, interp, &Origin::Generated)?;
+    match values.len() {
+        0 => Err(ParseError::UnexpectedEndOfInput),
+        1 => Ok(values.pop().unwrap()),
+        n => Err(ParseError::UnexpectedToken(format!(
+            "Expected a single value, found {}",
+            n
+        ))),
+    }
+}
+
+fn parse_with_origin(
+    input: &str,
+    interp: &mut AsyncInterpreter,
+    origin: &Origin,
+) -> Result<Vec<Value>, ParseError> {
     // RUST CONCEPT: Error propagation
     // The ? operator here means "if tokenize fails, return that error immediately"
     // Otherwise, unwrap the Ok value and continue
@@ -86,8 +149,7 @@ pub fn parse(input: &str, interp: &mut AsyncInterpreter) -> Result<Vec<Value>, P
         // RUST CONCEPT: Mutable references
         // We pass &mut index so parse_value can modify our index variable
         // This is how the parser keeps track of where it is in the token stream
-        let value = parse_value(&tokens, &mut index, interp)?;
-        results.push(value);
+        parse_code_value(&tokens, &mut index, interp, origin, &mut results)?;
     }
 
     // RUST CONCEPT: Return values
@@ -105,6 +167,7 @@ fn parse_value(
     tokens: &[Token],
     index: &mut usize,
     interp: &mut AsyncInterpreter,
+    origin: &Origin,
 ) -> Result<Value, ParseError> {
     // RUST CONCEPT: Bounds checking
     // .get() returns Option<T> - Some(value) if index exists, None if out of bounds
@@ -155,9 +218,9 @@ fn parse_value(
         Some(token) if matches!(token.kind, TokenKind::Rational(_, _)) => {
             if let TokenKind::Rational(numer, denom) = &token.kind {
                 *index += 1;
-                match (numer.parse::<i64>(), denom.parse::<i64>()) {
-                    (Ok(n), Ok(d)) if d != 0 => {
-                        let rational = Value::Rational(BigRational::new(BigInt::from(n), BigInt::from(d)));
+                match (numer.parse::<BigInt>(), denom.parse::<BigInt>()) {
+                    (Ok(n), Ok(d)) if d != BigInt::from(0) => {
+                        let rational = Value::Rational(BigRational::new(n, d));
                         Ok(rational.demote())
                     }
                     _ => Err(ParseError::InvalidNumber(format!("Invalid rational: {}/{}", numer, denom))),
@@ -171,8 +234,8 @@ fn parse_value(
         Some(token) if matches!(token.kind, TokenKind::GaussianInt(_, _)) => {
             if let TokenKind::GaussianInt(re, im) = &token.kind {
                 *index += 1;
-                match (re.parse::<i64>(), im.parse::<i64>()) {
-                    (Ok(r), Ok(i)) => Ok(Value::GaussianInt(BigInt::from(r), BigInt::from(i))),
+                match (re.parse::<BigInt>(), im.parse::<BigInt>()) {
+                    (Ok(r), Ok(i)) => Ok(Value::GaussianInt(r, i)),
                     _ => Err(ParseError::InvalidNumber(format!("Invalid Gaussian integer: {}+{}i", re, im))),
                 }
             } else {
@@ -184,8 +247,8 @@ fn parse_value(
         Some(token) if matches!(token.kind, TokenKind::Complex(_, _)) => {
             if let TokenKind::Complex(re, im) = &token.kind {
                 *index += 1;
-                match (re.parse::<f64>(), im.parse::<f64>()) {
-                    (Ok(r), Ok(i)) => Ok(Value::Complex(Complex64::new(r, i))),
+                match (parse_float_part(re), parse_float_part(im)) {
+                    (Some(r), Some(i)) => Ok(Value::Complex(Complex64::new(r, i))),
                     _ => Err(ParseError::InvalidNumber(format!("Invalid complex: {}+{}i", re, im))),
                 }
             } else {
@@ -197,12 +260,20 @@ fn parse_value(
             if let TokenKind::Atom(atom_text) = &token.kind {
                 *index += 1;
 
+                // Tagged literals like #record[point 3 4] rebuild values that have
+                // no syntax of their own (see writer.rs)
+                if is_literal_tag(atom_text)
+                    && matches!(tokens.get(*index), Some(next) if matches!(next.kind, TokenKind::LeftBracket))
+                {
+                    return parse_tagged(atom_text, tokens, index, interp, origin);
+                }
+
                 // RUST CONCEPT: Atom interning
                 // Atoms are symbols that get interned (deduplicated) for memory efficiency
                 // The tokenizer has already identified all numeric literals, so anything
                 // here is a true atom (identifier/symbol)
                 let interned_atom = interp.intern_atom(atom_text);
-                Ok(Value::Atom(interned_atom))
+                Ok(Value::Atom(interned_atom, origin.span(token)))
             } else {
                 unreachable!()
             }
@@ -242,11 +313,15 @@ fn parse_value(
         Some(token) if matches!(token.kind, TokenKind::LeftBracket) => {
             // RUST CONCEPT: Recursive parsing
             // Lists can contain other lists, so we call parse_list which may call parse_value again
-            parse_list(tokens, index, interp)
+            parse_list(tokens, index, interp, origin)
         }
 
         Some(token) if matches!(token.kind, TokenKind::ArrayLeftBracket) => {
-            parse_array(tokens, index, interp)
+            parse_array(tokens, index, interp, origin)
+        }
+
+        Some(token) if matches!(token.kind, TokenKind::LeftBrace) => {
+            parse_map(tokens, index, interp, origin)
         }
 
         Some(token) if matches!(token.kind, TokenKind::Quote) => {
@@ -334,13 +409,33 @@ fn parse_value(
             }
         }
 
+        Some(token) if matches!(token.kind, TokenKind::Unquote | TokenKind::UnquoteSplicing) => {
+            // ,x reads as [unquote x] and ,@x as [unquote-splicing x]; only a
+            // quasiquote filling in its template gives them a meaning
+            let name = match token.kind {
+                TokenKind::Unquote => "unquote",
+                _ => "unquote-splicing",
+            };
+            let marker = Value::Atom(interp.intern_atom(name), origin.span(token));
+            *index += 1;
+            let item = parse_value(tokens, index, interp, origin)?;
+            Ok(Value::Pair(
+                Rc::new(marker),
+                Rc::new(Value::Pair(Rc::new(item), Rc::new(Value::Nil))),
+            ))
+        }
+
+        Some(token) if matches!(token.kind, TokenKind::Quasiquote) => Err(ParseError::UnexpectedToken(
+            "Quasiquote (`) builds code, so it can't be used inside data".to_string(),
+        )),
+
         Some(token) if matches!(token.kind, TokenKind::Pipe) => {
             // RUST CONCEPT: Error handling
             // A pipe by itself is invalid - it should only appear in [a | b] notation
             Err(ParseError::InvalidPipeNotation)
         }
 
-        Some(token) if matches!(token.kind, TokenKind::RightBracket) => {
+        Some(token) if matches!(token.kind, TokenKind::RightBracket | TokenKind::RightBrace) => {
             // RUST CONCEPT: Error types
             // A closing bracket without a matching opening bracket is an error
             Err(ParseError::MismatchedBrackets)
@@ -360,10 +455,12 @@ fn parse_value(
 // - [1 2 3] (proper list)
 // - [a . b] (cons pair)
 // - [] (empty list)
+// - [| a b | body] (a body with named parameters, see parse_parameters)
 fn parse_list(
     tokens: &[Token],
     index: &mut usize,
     interp: &mut AsyncInterpreter,
+    origin: &Origin,
 ) -> Result<Value, ParseError> {
     // RUST CONCEPT: Assertions and debugging
     // debug_assert! is removed in release builds but helps catch bugs during development
@@ -376,7 +473,12 @@ fn parse_list(
 
     // RUST CONCEPT: Vec for collecting elements
     // We'll collect list elements here, then convert to cons cells at the end
-    let mut elements = Vec::new();
+    let mut elements = match tokens.get(*index) {
+        Some(token) if matches!(token.kind, TokenKind::Pipe) => {
+            parse_parameters(tokens, index, interp, origin)?
+        }
+        _ => Vec::new(),
+    };
 
     // RUST CONCEPT: Loop with pattern matching
     // We loop until we find the closing bracket or run out of tokens
@@ -397,7 +499,7 @@ fn parse_list(
                 *index += 1; // Skip the pipe
 
                 // Parse the element after the pipe (the "tail" of the cons cell)
-                let tail = parse_value(tokens, index, interp)?;
+                let tail = parse_value(tokens, index, interp, origin)?;
 
                 // RUST CONCEPT: Expecting specific tokens
                 // After [a | b], we MUST see a closing bracket
@@ -433,8 +535,7 @@ fn parse_list(
             _ => {
                 // RUST CONCEPT: Recursive parsing continues
                 // Parse the next element and add it to our list
-                let element = parse_value(tokens, index, interp)?;
-                elements.push(element);
+                parse_code_value(tokens, index, interp, origin, &mut elements)?;
             }
         }
     }
@@ -455,10 +556,96 @@ fn parse_list(
     Ok(list)
 }
 
+// Parses one value where code is expected, adding it to values
+// A quasiquoted template `[a ,b] is read as the template followed by the
+// word that fills it in: [a [unquote b]] quasiquote
+fn parse_code_value(
+    tokens: &[Token],
+    index: &mut usize,
+    interp: &mut AsyncInterpreter,
+    origin: &Origin,
+    values: &mut Vec<Value>,
+) -> Result<(), ParseError> {
+    match tokens.get(*index) {
+        Some(token) if matches!(token.kind, TokenKind::Quasiquote) => {
+            let word = Value::Atom(interp.intern_atom("quasiquote"), origin.span(token));
+            *index += 1;
+            match tokens.get(*index) {
+                Some(next) if matches!(next.kind, TokenKind::LeftBracket) => {
+                    values.push(parse_list(tokens, index, interp, origin)?);
+                    values.push(word);
+                    Ok(())
+                }
+                Some(_) => Err(ParseError::UnexpectedToken(
+                    "Quasiquote (`) must be followed by a list".to_string(),
+                )),
+                None => Err(ParseError::UnexpectedEndOfInput),
+            }
+        }
+        _ => {
+            values.push(parse_value(tokens, index, interp, origin)?);
+            Ok(())
+        }
+    }
+}
+
+// Named parameters: [| a b | a a * b b * +]
+// The header becomes ordinary code that pops the arguments into the local
+// frame the body runs in, last parameter first since it is on top:
+// ['b lval 'a lval a a * b b * +]. Nothing changes for lists without a
+// header, and the bytecode backend turns each pair into a local-set.
+fn parse_parameters(
+    tokens: &[Token],
+    index: &mut usize,
+    interp: &mut AsyncInterpreter,
+    origin: &Origin,
+) -> Result<Vec<Value>, ParseError> {
+    *index += 1; // Skip the opening pipe
+
+    let mut names: Vec<(Rc<str>, Option<Rc<SourceSpan>>)> = Vec::new();
+    loop {
+        match tokens.get(*index) {
+            Some(token) if matches!(token.kind, TokenKind::Pipe) => {
+                *index += 1; // Skip the closing pipe
+                break;
+            }
+            Some(token @ Token {
+                kind: TokenKind::Atom(name),
+                ..
+            }) => {
+                *index += 1;
+                let name = interp.intern_atom(name);
+                if names.iter().any(|(seen, _)| *seen == name) {
+                    return Err(ParseError::UnexpectedToken(format!(
+                        "Parameter {} is named twice",
+                        name
+                    )));
+                }
+                names.push((name, origin.span(token)));
+            }
+            None => return Err(ParseError::UnexpectedEndOfInput),
+            // [|] and [| 1 ...] are still malformed pipe notation
+            Some(_) => return Err(ParseError::InvalidPipeNotation),
+        }
+    }
+    if names.is_empty() {
+        return Err(ParseError::InvalidPipeNotation);
+    }
+
+    let lval = interp.intern_atom("lval");
+    let mut code = Vec::with_capacity(names.len() * 2);
+    for (name, span) in names.into_iter().rev() {
+        code.push(Value::QuotedAtom(name));
+        code.push(Value::Atom(lval.clone(), span));
+    }
+    Ok(code)
+}
+
 fn parse_array(
     tokens: &[Token],
     index: &mut usize,
     interp: &mut AsyncInterpreter,
+    origin: &Origin,
 ) -> Result<Value, ParseError> {
     debug_assert!(
         matches!(tokens.get(*index), Some(token) if matches!(token.kind, TokenKind::ArrayLeftBracket))
@@ -483,7 +670,7 @@ fn parse_array(
                 return Err(ParseError::UnexpectedEndOfInput);
             }
             _ => {
-                let element = parse_value(tokens, index, interp)?;
+                let element = parse_value(tokens, index, interp, origin)?;
                 elements.push(element);
             }
         }
@@ -492,6 +679,132 @@ fn parse_array(
     Ok(interp.make_array(elements))
 }
 
+// Tags understood by parse_tagged - each is followed directly by a list
+fn is_literal_tag(text: &str) -> bool {
+    matches!(text, "#record" | "#record-type" | "#i32" | "#f32")
+}
+
+// RUST CONCEPT: Tagged literals
+// #record[type field ...], #record-type[type name ...], #i32[n ...], #f32[x ...]
+// The list after the tag is parsed as ordinary data, then converted
+fn parse_tagged(
+    tag: &str,
+    tokens: &[Token],
+    index: &mut usize,
+    interp: &mut AsyncInterpreter,
+    origin: &Origin,
+) -> Result<Value, ParseError> {
+    let mut items = Vec::new();
+    let mut current = parse_list(tokens, index, interp, origin)?;
+    while let Value::Pair(car, cdr) = current {
+        items.push((*car).clone());
+        current = (*cdr).clone();
+    }
+    if !matches!(current, Value::Nil) {
+        return Err(ParseError::InvalidPipeNotation);
+    }
+
+    let invalid = |what: &str| ParseError::UnexpectedToken(format!("Invalid {} literal: {}", tag, what));
+
+    match tag {
+        "#record" | "#record-type" => {
+            let mut items = items.into_iter();
+            let type_name = match items.next() {
+                // The writer uses a string for a name that isn't a plain atom
+                Some(Value::Atom(name, _) | Value::String(name)) => name,
+                _ => return Err(invalid("expected a type name first")),
+            };
+            if tag == "#record" {
+                Ok(Value::Record {
+                    type_name,
+                    fields: Rc::new(RefCell::new(items.collect())),
+                })
+            } else {
+                let field_names = items
+                    .map(|item| match item {
+                        Value::Atom(name, _) | Value::String(name) => Ok(name),
+                        _ => Err(invalid("field names must be atoms or strings")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Value::RecordType {
+                    type_name,
+                    field_names: Rc::new(field_names),
+                })
+            }
+        }
+        "#i32" => {
+            let samples = items
+                .iter()
+                .map(|item| match item {
+                    Value::Int32(i) => Ok(*i),
+                    _ => Err(invalid("elements must be 32-bit integers")),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Value::I32Buffer(Rc::new(RefCell::new(samples))))
+        }
+        _ => {
+            let samples = items
+                .iter()
+                .map(|item| match item {
+                    Value::Number(n) => Ok(*n as f32),
+                    Value::Int32(i) => Ok(*i as f32),
+                    _ => Err(invalid("elements must be numbers")),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Value::F32Buffer(Rc::new(RefCell::new(samples))))
+        }
+    }
+}
+
+// RUST CONCEPT: Map literals
+// { key value ... } - entries alternate key, value; later duplicates win
+// Like list elements, keys and values are data and are not evaluated
+fn parse_map(
+    tokens: &[Token],
+    index: &mut usize,
+    interp: &mut AsyncInterpreter,
+    origin: &Origin,
+) -> Result<Value, ParseError> {
+    debug_assert!(
+        matches!(tokens.get(*index), Some(token) if matches!(token.kind, TokenKind::LeftBrace))
+    );
+
+    *index += 1; // Skip the { token
+
+    let mut entries = BTreeMap::new();
+
+    loop {
+        match tokens.get(*index) {
+            Some(token) if matches!(token.kind, TokenKind::RightBrace) => {
+                *index += 1;
+                break;
+            }
+            Some(token) if matches!(token.kind, TokenKind::RightBracket | TokenKind::Pipe) => {
+                return Err(ParseError::MismatchedBrackets);
+            }
+            None => {
+                return Err(ParseError::UnexpectedEndOfInput);
+            }
+            _ => {
+                let key = parse_value(tokens, index, interp, origin)?;
+                let key = MapKey::from_value(&key)
+                    .map_err(|_| ParseError::UnexpectedToken(format!("Invalid map key: {}", key)))?;
+                if matches!(tokens.get(*index), Some(token) if matches!(token.kind, TokenKind::RightBrace))
+                {
+                    return Err(ParseError::UnexpectedToken(format!(
+                        "Map key {} has no value",
+                        key
+                    )));
+                }
+                let value = parse_value(tokens, index, interp, origin)?;
+                entries.insert(key, value);
+            }
+        }
+    }
+
+    Ok(Value::Map(Rc::new(entries)))
+}
+
 // RUST CONCEPT: Conditional compilation and testing
 // #[cfg(test)] means this code only compiles when running tests
 // This keeps test code out of the release binary
@@ -556,7 +869,7 @@ mod tests {
         let expected_atoms = ["hello", "world", "+"];
         for (i, expected) in expected_atoms.iter().enumerate() {
             match &result[i] {
-                Value::Atom(atom) => {
+                Value::Atom(atom, _) => {
                     // RUST CONCEPT: Dereferencing Rc
                     // &**atom dereferences the Rc then takes a reference to the str
                     assert_eq!(&**atom, *expected);
@@ -568,7 +881,7 @@ mod tests {
         // RUST CONCEPT: Testing interning behavior
         // Same atoms should share the same Rc (pointer equality)
         let result2 = parse("hello", &mut interp).unwrap();
-        if let (Value::Atom(atom1), Value::Atom(atom2)) = (&result[0], &result2[0]) {
+        if let (Value::Atom(atom1, _), Value::Atom(atom2, _)) = (&result[0], &result2[0]) {
             assert!(Rc::ptr_eq(atom1, atom2)); // Same pointer = successful interning
         }
     }
@@ -650,6 +963,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_atoms_carry_source_spans() {
+        let mut interp = AsyncInterpreter::new();
+        interp.source_name = Some("demo.uni".into());
+
+        let result = parse("1\n  foo [bar]", &mut interp).unwrap();
+        match &result[1] {
+            Value::Atom(name, Some(span)) => {
+                assert_eq!(name.as_ref(), "foo");
+                assert_eq!((span.start.line, span.start.column), (2, 3));
+                assert_eq!((span.end.line, span.end.column), (2, 6));
+                assert_eq!(span.source.as_deref(), Some("demo.uni"));
+            }
+            other => panic!("Expected located atom, got {:?}", other),
+        }
+
+        // Atoms nested in lists are located too
+        match &result[2] {
+            Value::Pair(head, _) => {
+                assert!(matches!(head.as_ref(), Value::Atom(_, Some(span)) if span.start.column == 8));
+            }
+            other => panic!("Expected list, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_generated_has_no_spans() {
+        let mut interp = AsyncInterpreter::new();
+        let result = parse_generated("foo", &mut interp).unwrap();
+        assert!(matches!(&result[0], Value::Atom(name, None) if name.as_ref() == "foo"));
+    }
+
     #[test]
     fn test_parse_array_literal() {
         let mut interp = AsyncInterpreter::new();
@@ -680,6 +1025,62 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_map_literal() {
+        let mut interp = AsyncInterpreter::new();
+
+        let result = parse("{ name \"Ada\" 1 [x] 1n 2 }", &mut interp).unwrap();
+        assert_eq!(result.len(), 1);
+
+        match &result[0] {
+            Value::Map(map) => {
+                // 1 and 1n are the same key, so the later entry wins
+                assert_eq!(map.len(), 2);
+                let name = map.get(&MapKey::Atom("name".into())).unwrap();
+                assert!(matches!(name, Value::String(s) if s.as_ref() == "Ada"));
+                let one = map.get(&MapKey::Integer(BigInt::from(1))).unwrap();
+                assert!(matches!(one, Value::Int32(2)));
+            }
+            other => panic!("Expected map value, got {:?}", other),
+        }
+
+        assert!(matches!(parse("{}", &mut interp).unwrap()[0], Value::Map(ref m) if m.is_empty()));
+    }
+
+    #[test]
+    fn test_parse_map_literal_errors() {
+        let mut interp = AsyncInterpreter::new();
+
+        assert!(matches!(
+            parse("{ a }", &mut interp),
+            Err(ParseError::UnexpectedToken(msg)) if msg.contains("has no value")
+        ));
+        assert!(matches!(
+            parse("{ 1.5 a }", &mut interp),
+            Err(ParseError::UnexpectedToken(msg)) if msg.contains("Invalid map key")
+        ));
+        assert!(matches!(parse("{ a 1 ]", &mut interp), Err(ParseError::MismatchedBrackets)));
+        assert!(matches!(parse("{ a 1", &mut interp), Err(ParseError::UnexpectedEndOfInput)));
+        assert!(matches!(parse("}", &mut interp), Err(ParseError::MismatchedBrackets)));
+    }
+
+    #[test]
+    fn test_read_value() {
+        let mut interp = AsyncInterpreter::new();
+
+        assert!(matches!(read_value(" 3/4 ", &mut interp), Ok(Value::Rational(_))));
+        assert!(matches!(read_value("[1 foo]", &mut interp), Ok(Value::Pair(_, _))));
+        // Read atoms are data, not source - they carry no span
+        assert!(matches!(read_value("foo", &mut interp), Ok(Value::Atom(_, None))));
+
+        assert!(matches!(read_value("   ", &mut interp), Err(ParseError::UnexpectedEndOfInput)));
+        assert!(matches!(
+            read_value("1 2", &mut interp),
+            Err(ParseError::UnexpectedToken(msg)) if msg.contains("single value")
+        ));
+        assert!(read_value("[1 2", &mut interp).is_err());
+    }
+
     #[test]
     fn test_parse_pipe_notation() {
         let mut interp = AsyncInterpreter::new();
@@ -895,7 +1296,7 @@ mod tests {
 
                 match rest1.as_ref() {
                     Value::Pair(second, rest2) => {
-                        assert!(matches!(second.as_ref(), Value::Atom(a) if &**a == "hello"));
+                        assert!(matches!(second.as_ref(), Value::Atom(a, _) if &**a == "hello"));
 
                         match rest2.as_ref() {
                             Value::Pair(third, rest3) => {
@@ -1061,6 +1462,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_named_parameters() {
+        let mut interp = AsyncInterpreter::new();
+
+        // The header pops the last parameter first
+        let result = parse("[| a b | a b -]", &mut interp).unwrap();
+        assert_eq!(result[0].to_string(), "['b lval 'a lval a b -]");
+        let result = parse("[| x |]", &mut interp).unwrap();
+        assert_eq!(result[0].to_string(), "['x lval]");
+
+        // Headers need names, a closing pipe, and no name twice
+        assert!(matches!(parse("[| | x]", &mut interp), Err(ParseError::InvalidPipeNotation)));
+        assert!(matches!(parse("[| a 1 | a]", &mut interp), Err(ParseError::InvalidPipeNotation)));
+        assert!(matches!(parse("[| a", &mut interp), Err(ParseError::UnexpectedEndOfInput)));
+        assert!(parse("[| a a | a]", &mut interp).is_err());
+    }
+
+    #[test]
+    fn test_parse_quasiquote() {
+        let mut interp = AsyncInterpreter::new();
+
+        // The template is followed by the word that fills it in
+        let result = parse("`[a ,b ,@c]", &mut interp).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].to_string(), "[a [unquote b] [unquote-splicing c]]");
+        assert!(matches!(&result[1], Value::Atom(name, _) if &**name == "quasiquote"));
+
+        let result = parse("[1 `[,x]]", &mut interp).unwrap();
+        assert_eq!(result[0].to_string(), "[1 [[unquote x]] quasiquote]");
+
+        assert!(parse("`foo", &mut interp).is_err());
+        assert!(parse("#[`[1]]", &mut interp).is_err());
+    }
+
     #[test]
     fn test_parse_error_messages() {
         let mut interp = AsyncInterpreter::new();
@@ -1174,7 +1609,7 @@ mod tests {
         let mut interp = AsyncInterpreter::new();
 
         // Quoted booleans should be errors - booleans are data by default
-        let error_cases = vec![
+        let error_cases = [
             ("'true", "cannot be quoted"),
             ("'false", "cannot be quoted"),
             ("'null", "cannot be quoted"),
@@ -1212,7 +1647,7 @@ mod tests {
             result[0]
         );
         assert!(
-            !matches!(result[0], Value::Atom(_)),
+            !matches!(result[0], Value::Atom(_, _)),
             "true should NOT be parsed as an atom"
         );
 
@@ -1222,7 +1657,7 @@ mod tests {
             result[1]
         );
         assert!(
-            !matches!(result[1], Value::Atom(_)),
+            !matches!(result[1], Value::Atom(_, _)),
             "false should NOT be parsed as an atom"
         );
 
@@ -1232,7 +1667,7 @@ mod tests {
             result[2]
         );
         assert!(
-            !matches!(result[2], Value::Atom(_)),
+            !matches!(result[2], Value::Atom(_, _)),
             "null should NOT be parsed as an atom"
         );
 
@@ -1242,7 +1677,7 @@ mod tests {
 
         for (i, val) in result.iter().enumerate() {
             assert!(
-                matches!(val, Value::Atom(_)),
+                matches!(val, Value::Atom(_, _)),
                 "Element {} should be an atom, got: {:?}",
                 i,
                 val
@@ -0,0 +1,144 @@
+// Patterns: taking values apart for match
+//
+// A pattern is ordinary data written in match's clause list:
+//
+// - _ matches anything, and any other plain atom matches anything and binds
+//   that name to the value (the same name twice must match equal values);
+//   special forms like if can't be bound, just as lval refuses them
+// - 'name matches the atom name; numbers, strings, booleans and null match
+//   values equal to them
+// - [p1 p2 ...] matches a list of that many items, and [p1 p2 | rest] a
+//   list of at least two, matching rest against what follows them
+// - #[p1 p2 ...] matches an array of that many items
+// - #record[type p1 p2 ...] matches a record of that type with fields
+//   matching in order; #record[type] matches any record of the type
+//
+// Bindings go into a fresh local frame that the clause's body runs in.
+
+use crate::compat::Rc;
+use crate::compile::check_definable;
+use crate::value::{RuntimeError, Value};
+
+#[cfg(not(target_os = "none"))]
+use std::collections::HashMap;
+#[cfg(target_os = "none")]
+use alloc::collections::BTreeMap as HashMap;
+
+// Whether value matches pattern, adding the names the pattern binds to
+// bindings. On a failed match bindings may hold some of them. Binding a
+// name the evaluator treats as a special form is an error, as it is for lval.
+pub(crate) fn match_pattern(
+    pattern: &Value,
+    value: &Value,
+    bindings: &mut HashMap<Rc<str>, Value>,
+) -> Result<bool, RuntimeError> {
+    Ok(match (pattern, value.as_list()) {
+        (Value::Atom(name, _), _) if &**name == "_" => true,
+        (Value::Atom(name, _), _) => match bindings.get(name) {
+            Some(bound) => bound == value,
+            None => {
+                check_definable(name, "match")?;
+                bindings.insert(name.clone(), value.clone());
+                true
+            }
+        },
+        (Value::QuotedAtom(name), Value::Atom(atom, _)) => name == atom,
+        (Value::QuotedAtom(_), _) => false,
+
+        (Value::Pair(pattern_car, pattern_cdr), Value::Pair(car, cdr)) => {
+            match_pattern(pattern_car, car, bindings)? && match_pattern(pattern_cdr, cdr, bindings)?
+        }
+        (Value::Pair(_, _), _) => false,
+        (Value::Nil, value) => matches!(value, Value::Nil),
+
+        (Value::Array(patterns), Value::Array(items)) => {
+            let patterns = patterns.borrow();
+            let items = items.borrow();
+            patterns.len() == items.len() && match_all(&patterns, &items, bindings)?
+        }
+        (Value::Array(_), _) => false,
+
+        (
+            Value::Record {
+                type_name: pattern_type,
+                fields: patterns,
+            },
+            Value::Record { type_name, fields },
+        ) => {
+            if pattern_type != type_name {
+                return Ok(false);
+            }
+            let patterns = patterns.borrow();
+            let fields = fields.borrow();
+            patterns.is_empty()
+                || (patterns.len() == fields.len() && match_all(&patterns, &fields, bindings)?)
+        }
+        (Value::Record { .. }, _) => false,
+
+        // Anything else is a literal
+        (literal, value) => literal == value,
+    })
+}
+
+// Whether each value matches the pattern in the same place
+fn match_all(
+    patterns: &[Value],
+    values: &[Value],
+    bindings: &mut HashMap<Rc<str>, Value>,
+) -> Result<bool, RuntimeError> {
+    for (pattern, value) in patterns.iter().zip(values) {
+        if !match_pattern(pattern, value, bindings)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::AsyncInterpreter;
+    use crate::parser::parse;
+
+    fn try_match(pattern: &str, value: &str) -> Option<HashMap<Rc<str>, Value>> {
+        let mut interp = AsyncInterpreter::new();
+        let pattern = parse(pattern, &mut interp).unwrap().remove(0);
+        let value = parse(value, &mut interp).unwrap().remove(0);
+        let mut bindings = HashMap::new();
+        match_pattern(&pattern, &value, &mut bindings).unwrap().then_some(bindings)
+    }
+
+    #[test]
+    fn test_list_patterns_bind_heads_and_tails() {
+        let bindings = try_match("[a _ | rest]", "[1 2 3 4]").unwrap();
+        assert_eq!(bindings.len(), 2);
+        assert!(matches!(bindings.get("a"), Some(Value::Int32(1))));
+        assert_eq!(bindings.get("rest").unwrap().to_string(), "[3 4]");
+
+        assert!(try_match("[a b]", "[1 2 3]").is_none());
+        assert!(try_match("[x x]", "[5 5]").is_some());
+        assert!(try_match("[x x]", "[5 6]").is_none());
+        assert!(try_match("['ok 1]", "[ok 1]").is_some());
+    }
+
+    #[test]
+    fn test_array_and_record_patterns() {
+        let bindings = try_match("#[x 2]", "#[1 2]").unwrap();
+        assert!(matches!(bindings.get("x"), Some(Value::Int32(1))));
+        assert!(try_match("#[x]", "#[1 2]").is_none());
+
+        let bindings = try_match("#record[point x 0]", "#record[point 3 0]").unwrap();
+        assert!(matches!(bindings.get("x"), Some(Value::Int32(3))));
+        assert!(try_match("#record[point]", "#record[point 3 0]").is_some());
+        assert!(try_match("#record[size]", "#record[point 3 0]").is_none());
+    }
+
+    #[test]
+    fn test_special_forms_cannot_be_bound() {
+        let mut interp = AsyncInterpreter::new();
+        let pattern = parse("[x | exec]", &mut interp).unwrap().remove(0);
+        let value = parse("[1 2]", &mut interp).unwrap().remove(0);
+        let error = match_pattern(&pattern, &value, &mut HashMap::new()).unwrap_err();
+        assert!(matches!(error, RuntimeError::DomainError(message) if message.contains("exec")));
+    }
+}
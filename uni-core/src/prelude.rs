@@ -171,6 +171,11 @@ pub async fn load_prelude(interp: &mut AsyncInterpreter) -> Result<(), RuntimeEr
         ] def
         "( [condition] [body] -- ) Loop: executes body while condition returns truthy" doc
 
+        \\ Exception handling
+        \\ Built-in errors reach try handlers as instances of this record type
+        \\ kind is a string such as "division-by-zero"; position is [line column] or null
+        ["kind" "message" "position"] "error" make-record-type drop
+
         \\ Date/time operations
         \\ Date record type with calendar components
         \\ The 'now' primitive (written in Rust) returns instances of this record type
@@ -0,0 +1,67 @@
+//! Channel primitives - bounded queues that spawned tasks talk through
+//!
+//! make-channel builds a Value::Channel (see channels.rs); send and receive
+//! suspend only the calling task while the channel is full or empty.
+
+use crate::channels::Channel;
+use crate::compat::{format, Box, Rc};
+use crate::interpreter::AsyncInterpreter;
+use crate::value::{RuntimeError, Value};
+use core::future::Future;
+use core::pin::Pin;
+
+fn pop_channel(interp: &mut AsyncInterpreter, word: &str) -> Result<Rc<Channel>, RuntimeError> {
+    match interp.pop()? {
+        Value::Channel(channel) => Ok(channel),
+        other => Err(RuntimeError::TypeError(format!(
+            "{} expects a channel, got {}",
+            word,
+            other.type_name()
+        ))),
+    }
+}
+
+// make-channel: ( capacity -- channel )
+pub fn make_channel_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let capacity = interp.pop_integer().map_err(|_| {
+        RuntimeError::TypeError("make-channel expects a non-negative integer capacity".into())
+    })?;
+    let channel = Channel::new(capacity)?;
+    interp.push(Value::Channel(Rc::new(channel)));
+    Ok(())
+}
+
+// send: ( value channel -- ) waits while the channel is full
+pub fn send(interp: &mut AsyncInterpreter) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
+    Box::pin(async move {
+        let channel = pop_channel(interp, "send")?;
+        let value = interp.pop()?;
+        channel.send(value, &interp.cancel).await
+    })
+}
+
+// receive: ( channel -- value ) waits while the channel is empty
+pub fn receive(interp: &mut AsyncInterpreter) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
+    Box::pin(async move {
+        let channel = pop_channel(interp, "receive")?;
+        let value = channel.receive(&interp.cancel).await?;
+        interp.push(value);
+        Ok(())
+    })
+}
+
+// try-receive: ( channel -- value true | null false ) never waits
+pub fn try_receive_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let channel = pop_channel(interp, "try-receive")?;
+    match channel.try_receive() {
+        Some(value) => {
+            interp.push(value);
+            interp.push(Value::Boolean(true));
+        }
+        None => {
+            interp.push(Value::Null);
+            interp.push(Value::Boolean(false));
+        }
+    }
+    Ok(())
+}
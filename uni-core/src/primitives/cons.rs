@@ -5,8 +5,9 @@ use crate::interpreter::AsyncInterpreter;
 use crate::value::{RuntimeError, Value};
 
 // CONS: ( a b -- [a|b] )
+// Consing onto a closure gives a list, so the result is data like any other
 pub fn cons_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
-    let cdr = interp.pop()?;
+    let cdr = interp.pop()?.as_list().clone();
     let car = interp.pop()?;
     interp.push(Value::Pair(Rc::new(car), Rc::new(cdr)));
     Ok(())
@@ -1,6 +1,7 @@
 // DEF primitive - define an executable word
 
 use crate::compat::ToString;
+use crate::compile::compile;
 use crate::interpreter::{AsyncInterpreter, DictEntry};
 use crate::value::{RuntimeError, Value};
 
@@ -10,22 +11,30 @@ pub fn def_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
     let name_value = interp.pop()?;
 
     let atom = match name_value {
-        Value::Atom(atom) => atom,
+        Value::Atom(atom, _) => atom,
         _ => return Err(RuntimeError::TypeError(
             "DEF requires an atom as the first argument (use 'name [...] def)".to_string()
         )),
     };
+    crate::compile::check_definable(&atom, "def")?;
 
     // Store pending doc target for doc string attachment
     interp.set_pending_doc_target(atom.clone());
 
-    // Insert into dictionary as executable
+    // Insert into dictionary as executable, compiling list bodies once now
+    // rather than every time the word runs
+    let code = match &body {
+        Value::Pair(_, _) | Value::Nil => Some(compile(&body)),
+        _ => None,
+    };
     interp.dict_insert(
         atom,
         DictEntry {
             value: body,
             is_executable: true,
+            is_immediate: false,
             doc: None,
+            code,
         },
     );
 
@@ -42,7 +51,7 @@ mod tests {
         let mut interp = AsyncInterpreter::new();
 
         let name = interp.intern_atom("test");
-        interp.push(Value::Atom(name.clone()));
+        interp.push(Value::Atom(name.clone(), None));
         interp.push(interp.make_list(vec![Value::Number(42.0)]));
         def_impl(&mut interp).unwrap();
 
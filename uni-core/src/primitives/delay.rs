@@ -1,60 +1,159 @@
 //! Async delay primitive - waits for N milliseconds while letting other tasks run
 
-use crate::interpreter::AsyncInterpreter;
+use crate::interpreter::{AsyncInterpreter, CancelToken};
 use crate::value::{RuntimeError, Value};
-use core::future::Future;
-use core::pin::Pin;
-use crate::compat::Box;
+use core::future::{poll_fn, Future};
+use core::pin::{pin, Pin};
+use core::task::Poll;
+use crate::compat::{format, Box};
 
 pub fn delay(interp: &mut AsyncInterpreter) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
     Box::pin(async move {
-        // Pop milliseconds from stack
-        let ms_value = interp.stack.pop()
-            .ok_or_else(|| RuntimeError::StackUnderflow)?;
-
-        let ms = match ms_value {
-            Value::Int32(n) => {
-                if n < 0 {
-                    return Err(RuntimeError::DomainError("delay requires non-negative milliseconds".into()));
-                }
-                n as u64
+        let ms = pop_ms(interp, "delay")?;
+
+        // A cancel (Ctrl-C) ends the wait at once
+        cancellable(sleep_ms(ms), &interp.cancel).await?
+    })
+}
+
+// Pops a non-negative number of milliseconds for word
+pub(crate) fn pop_ms(interp: &mut AsyncInterpreter, word: &str) -> Result<u64, RuntimeError> {
+    let negative = || RuntimeError::DomainError(format!("{} requires non-negative milliseconds", word));
+    match interp.pop()? {
+        Value::Int32(n) => {
+            if n < 0 {
+                return Err(negative());
             }
-            Value::Number(f) => {
-                if f < 0.0 {
-                    return Err(RuntimeError::DomainError("delay requires non-negative milliseconds".into()));
-                }
-                f as u64
+            Ok(n as u64)
+        }
+        Value::Number(f) => {
+            if f < 0.0 {
+                return Err(negative());
             }
-            Value::Integer(ref i) => {
-                use num_traits::ToPrimitive;
-                let n = i.to_i64().ok_or_else(|| RuntimeError::DomainError("delay value too large".into()))?;
-                if n < 0 {
-                    return Err(RuntimeError::DomainError("delay requires non-negative milliseconds".into()));
-                }
-                n as u64
+            Ok(f as u64)
+        }
+        Value::Integer(ref i) => {
+            use num_traits::ToPrimitive;
+            let n = i.to_i64().ok_or_else(|| RuntimeError::DomainError(format!("{} value too large", word)))?;
+            if n < 0 {
+                return Err(negative());
             }
-            _ => return Err(RuntimeError::TypeError("delay requires a number".into())),
-        };
+            Ok(n as u64)
+        }
+        _ => Err(RuntimeError::TypeError(format!("{} requires a number", word))),
+    }
+}
 
-        // Platform-specific delay
-        #[cfg(feature = "target-stm32h753zi")]
-        {
-            embassy_time::Timer::after_millis(ms).await;
+// ASYNC CONCEPT: Waiting that Ctrl-C can still interrupt
+// Races the future against the cancel token: whichever is ready first wins,
+// and a cancel wakes the waiting task straight away through the token's waker.
+pub(crate) async fn cancellable<F: Future>(
+    future: F,
+    cancel: &CancelToken,
+) -> Result<F::Output, RuntimeError> {
+    if cancel.take() {
+        return Err(RuntimeError::Interrupted);
+    }
+    let mut future = pin!(future);
+    let mut cancelled = pin!(cancel.cancelled());
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        match cancelled.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                cancel.take();
+                Poll::Ready(Err(RuntimeError::Interrupted))
+            }
+            Poll::Pending => Poll::Pending,
         }
+    })
+    .await
+}
+
+// Platform-specific delay
+pub(crate) async fn sleep_ms(ms: u64) -> Result<(), RuntimeError> {
+    #[cfg(feature = "target-stm32h753zi")]
+    {
+        embassy_time::Timer::after_millis(ms).await;
+    }
 
-        #[cfg(not(feature = "target-stm32h753zi"))]
+    #[cfg(not(feature = "target-stm32h753zi"))]
+    {
+        // For std targets, use tokio
+        #[cfg(feature = "std")]
         {
-            // For std targets, use tokio
-            #[cfg(feature = "std")]
-            {
-                tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
-            }
-            #[cfg(not(feature = "std"))]
-            {
-                return Err(RuntimeError::TypeError("delay not supported on this platform".into()));
+            match crate::virtual_time::installed() {
+                Some(clock) => {
+                    let deadline = clock.now() + Duration::from_millis(ms);
+                    clock.sleep_until(deadline).await;
+                }
+                None => tokio::time::sleep(std::time::Duration::from_millis(ms)).await,
             }
         }
+        #[cfg(not(feature = "std"))]
+        {
+            return Err(RuntimeError::TypeError("delay not supported on this platform".into()));
+        }
+    }
 
-        Ok(())
-    })
+    Ok(())
+}
+
+// The clock delay sleeps on, for words that keep to a schedule (see tasks.rs)
+// Tests can swap in a virtual clock on Linux (see virtual_time.rs).
+#[cfg(feature = "target-stm32h753zi")]
+pub(crate) use embassy_time::{Duration, Instant};
+#[cfg(not(feature = "target-stm32h753zi"))]
+pub(crate) use tokio::time::{Duration, Instant};
+
+// The current time on that clock (virtual time while a test controls it)
+pub(crate) fn now() -> Instant {
+    #[cfg(feature = "std")]
+    if let Some(clock) = crate::virtual_time::installed() {
+        return clock.now();
+    }
+    Instant::now()
+}
+
+// Platform-specific wait for a point in time on that clock
+pub(crate) async fn sleep_until(deadline: Instant) {
+    #[cfg(feature = "target-stm32h753zi")]
+    embassy_time::Timer::at(deadline).await;
+
+    #[cfg(not(feature = "target-stm32h753zi"))]
+    {
+        #[cfg(feature = "std")]
+        if let Some(clock) = crate::virtual_time::installed() {
+            return clock.sleep_until(deadline).await;
+        }
+        tokio::time::sleep_until(deadline).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    #[tokio::test]
+    async fn test_cancel_wakes_the_wait_without_polling() {
+        let cancel = CancelToken::default();
+        let polls = Cell::new(0);
+        let never = poll_fn(|_| {
+            polls.set(polls.get() + 1);
+            Poll::<()>::Pending
+        });
+
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            canceller.cancel();
+        });
+        let result = cancellable(never, &cancel).await;
+        assert!(matches!(result, Err(RuntimeError::Interrupted)));
+        // Polled when the wait starts and once more when the cancel woke it
+        assert_eq!(polls.get(), 2);
+        assert!(!cancel.is_cancelled());
+    }
 }
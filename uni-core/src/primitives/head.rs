@@ -7,9 +7,9 @@ use crate::value::{RuntimeError, Value};
 // CAR: ( [a|b] -- a )
 pub fn car_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
     let pair = interp.pop()?;
-    match pair {
+    match pair.as_list() {
         Value::Pair(car, _) => {
-            interp.push((*car).clone());
+            interp.push((**car).clone());
             Ok(())
         }
         _ => Err(RuntimeError::TypeError("CAR requires a pair".to_string())),
@@ -7,7 +7,28 @@ use core::pin::Pin;
 const IF_DOC: &str = "Conditional branching. Usage: condition true-branch false-branch if";
 const EXEC_DOC: &str =
     "Execute the value at the top of the stack. Lists run as code, other values execute directly.";
+const TRY_DOC: &str = "Run body; if it raises an error, restore the stack depth and run handler with the error on the stack.\nUsage: [body] [handler] try\nExample: [1 0 /] [error-kind] try => \"division-by-zero\"";
 const QUIT_DOC: &str = "Exit the REPL or terminate script execution. Usage: quit";
+const COND_DOC: &str = "Run the body of the first test that leaves a truthy value, or the default if none does.\nUsage: [[test] [body] ... [default]] cond\nExample: 5 [[dup 0 <] [\"neg\"] [dup 0 =] [\"zero\"] [\"pos\"]] cond => 5 \"pos\"";
+const MATCH_DOC: &str = "Run the body of the first pattern the value fits, with the names it binds as locals. Patterns: _ (anything), name (binds), 'atom and other literals, [a b | rest], #[a b], #record[type a b].\nUsage: value [pattern [body] ...] match\nExample: [1 2 3] [[] [0] [x | _] [x]] match => 1";
+const CASE_DOC: &str = "Run the body of the first key equal to the value, or the default if none is. Keys are compared with =, so a list key matches an equal list.\nUsage: value [key [body] ... [default]] case\nExample: 2 [1 [\"one\"] 2 [\"two\"] [\"many\"]] case => \"two\"";
+
+const TIMES_DOC: &str = "Run a quotation n times. Inside the body, i is the iteration number from 0.\nUsage: n [body] times\nExample: 3 [i .] times => 0 1 2";
+const DO_DOC: &str = "Counted loop from start up to (not including) limit. Inside the body, i is the index and j the enclosing loop's index.\nUsage: limit start [body] do\nExample: 5 2 [i .] do => 2 3 4";
+const LOOP_DOC: &str = "Run a quotation repeatedly until leave is executed. i counts iterations from 0.\nUsage: [body] loop\nExample: [i 3 = [leave] [] if] loop";
+const RANGE_EACH_DOC: &str = "Run a quotation for each integer from start up to (not including) end, pushing it first.\nUsage: start end [body] range-each\nExample: 1 4 [.] range-each => 1 2 3";
+const LEAVE_DOC: &str = "Exit the innermost times/do/loop/range-each immediately, even from inside a map or filter quotation.\nUsage: leave\nExample: 10 [i 3 = [leave] [] if] times";
+// Loop words are evaluator special forms (which can't be redefined), so their docs live here too
+fn loop_word_doc(name: &str) -> Option<&'static str> {
+    match name {
+        "times" => Some(TIMES_DOC),
+        "do" => Some(DO_DOC),
+        "loop" => Some(LOOP_DOC),
+        "range-each" => Some(RANGE_EACH_DOC),
+        "leave" => Some(LEAVE_DOC),
+        _ => None,
+    }
+}
 
 pub fn help_builtin(interp: &mut AsyncInterpreter)
     -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>>
@@ -20,7 +41,7 @@ pub fn help_builtin(interp: &mut AsyncInterpreter)
 async fn help_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
     let word = interp.pop()?;
     let atom = match word {
-        Value::Atom(name) => name,
+        Value::Atom(name, _) => name,
         _ => {
             return Err(RuntimeError::TypeError(
                 "help expects an atom (use 'word help)".to_string(),
@@ -32,8 +53,18 @@ async fn help_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
         (Some(Rc::<str>::from(IF_DOC)), true)
     } else if atom.as_ref() == "exec" {
         (Some(Rc::<str>::from(EXEC_DOC)), true)
+    } else if atom.as_ref() == "try" {
+        (Some(Rc::<str>::from(TRY_DOC)), true)
     } else if atom.as_ref() == "quit" {
         (Some(Rc::<str>::from(QUIT_DOC)), true)
+    } else if atom.as_ref() == "cond" {
+        (Some(Rc::<str>::from(COND_DOC)), true)
+    } else if atom.as_ref() == "case" {
+        (Some(Rc::<str>::from(CASE_DOC)), true)
+    } else if atom.as_ref() == "match" {
+        (Some(Rc::<str>::from(MATCH_DOC)), true)
+    } else if let Some(doc) = loop_word_doc(&atom) {
+        (Some(Rc::<str>::from(doc)), true)
     } else {
         #[cfg(not(target_os = "none"))]
         let entry = {
@@ -139,17 +139,7 @@ pub fn i32_push_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError>
 
     // Convert value to i16
     let i32_value = match value_to_push {
-        Value::Int32(i) => {
-            if i < i32::MIN as i32 || i > i32::MAX as i32 {
-                return Err(RuntimeError::DomainError(format!(
-                    "Value {} out of i16 range ({} to {})",
-                    i,
-                    i32::MIN,
-                    i32::MAX
-                )));
-            }
-            i
-        }
+        Value::Int32(i) => i,
         Value::Number(n) => {
             if n < i32::MIN as f64 || n > i32::MAX as f64 {
                 return Err(RuntimeError::DomainError(format!(
@@ -201,7 +191,7 @@ pub fn i32_pop_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
             })?;
 
             interp.push(Value::I32Buffer(buffer));
-            interp.push(Value::Int32(popped as i32));
+            interp.push(Value::Int32(popped));
             Ok(())
         }
         _ => Err(RuntimeError::TypeError(
@@ -229,7 +219,7 @@ pub fn i32_max_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
             let max = *borrowed.iter().max().unwrap();
             drop(borrowed); // Release borrow before pushing
             interp.push(buffer_val); // Push buffer back
-            interp.push(Value::Int32(max as i32));
+            interp.push(Value::Int32(max));
             Ok(())
         }
         _ => Err(RuntimeError::TypeError(
@@ -257,7 +247,7 @@ pub fn i32_min_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
             let min = *borrowed.iter().min().unwrap();
             drop(borrowed); // Release borrow before pushing
             interp.push(buffer_val); // Push buffer back
-            interp.push(Value::Int32(min as i32));
+            interp.push(Value::Int32(min));
             Ok(())
         }
         _ => Err(RuntimeError::TypeError(
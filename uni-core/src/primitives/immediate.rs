@@ -0,0 +1,53 @@
+// IMMEDIATE primitive - turn a defined word into a macro (see macros.rs)
+
+use crate::compat::{format, ToString};
+use crate::interpreter::AsyncInterpreter;
+use crate::value::{RuntimeError, Value};
+
+// IMMEDIATE: ( 'name -- ) - Make a word run on the code after it before that code runs
+pub fn immediate_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let atom = match interp.pop()? {
+        Value::Atom(atom, _) => atom,
+        other => {
+            return Err(RuntimeError::TypeError(format!(
+                "immediate expects a quoted word name, got {}",
+                other.type_name()
+            )))
+        }
+    };
+
+    let mut entry = interp
+        .dict_get(&atom)
+        .ok_or_else(|| RuntimeError::UndefinedWord(atom.to_string()))?;
+    if !entry.is_executable {
+        return Err(RuntimeError::TypeError(format!(
+            "immediate needs a word defined with def, but {} is a value",
+            atom
+        )));
+    }
+
+    entry.is_immediate = true;
+    interp.dict_insert(atom, entry);
+    interp.macros_defined = true;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_immediate_impl() {
+        let mut interp = AsyncInterpreter::new();
+        let name = interp.intern_atom("drop");
+        interp.push(Value::Atom(name.clone(), None));
+        immediate_impl(&mut interp).unwrap();
+
+        assert!(interp.dict_get(&name).unwrap().is_immediate);
+        assert!(interp.macros_defined);
+
+        let missing = interp.intern_atom("no-such-word");
+        interp.push(Value::Atom(missing, None));
+        assert!(matches!(immediate_impl(&mut interp), Err(RuntimeError::UndefinedWord(_))));
+    }
+}
@@ -1,9 +1,11 @@
 // Local constant creation
 // Creates an immutable local constant in the current local frame
 
-use crate::compat::format;
+use crate::compat::{format, Box};
 use crate::interpreter::AsyncInterpreter;
 use crate::value::{RuntimeError, Value};
+use core::future::Future;
+use core::pin::Pin;
 
 #[cfg(target_os = "none")]
 use crate::compat::ToString;
@@ -24,7 +26,7 @@ pub fn lval_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
 
     // Extract the atom name
     let name = match name_val {
-        Value::Atom(ref atom) => atom.clone(),
+        Value::Atom(ref atom, _) => atom.clone(),
         _ => {
             return Err(RuntimeError::TypeError(format!(
                 "lval expects atom name, got {:?}",
@@ -32,6 +34,7 @@ pub fn lval_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
             )))
         }
     };
+    crate::compile::check_definable(&name, "lval")?;
 
     // Store in the current (top) local frame
     let frame = interp
@@ -43,6 +46,15 @@ pub fn lval_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
     Ok(())
 }
 
+// lval as registered in the dictionary
+// The bytecode VM checks `lval` still resolves to this before running its
+// local-set instruction as a plain call to lval_impl
+pub fn lval_builtin(
+    interp: &mut AsyncInterpreter,
+) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
+    Box::pin(async move { lval_impl(interp) })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,7 +74,7 @@ mod tests {
 
         let name = interp.intern_atom("x");
         interp.push(Value::Number(42.0));
-        interp.push(Value::Atom(name.clone()));
+        interp.push(Value::Atom(name.clone(), None));
         lval_impl(&mut interp).unwrap();
 
         let frame = &interp.local_frames[0];
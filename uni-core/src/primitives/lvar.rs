@@ -29,7 +29,7 @@ pub fn lvar_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
 
     // Extract the atom name
     let name = match name_val {
-        Value::Atom(ref atom) => atom.clone(),
+        Value::Atom(ref atom, _) => atom.clone(),
         _ => {
             return Err(RuntimeError::TypeError(format!(
                 "lvar expects atom name, got {:?}",
@@ -37,6 +37,7 @@ pub fn lvar_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
             )))
         }
     };
+    crate::compile::check_definable(&name, "lvar")?;
 
     // Create the variable (using RefCell for mutability)
     let var = Value::Variable(Rc::new(RefCell::new(initial_value)));
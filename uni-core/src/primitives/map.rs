@@ -0,0 +1,200 @@
+// Map primitives - lookup, update and iteration over { key value ... } maps
+// Maps are values: put/remove/merge return a new map and leave the original alone.
+// The underlying BTreeMap is only copied when another reference to it exists.
+
+use crate::compat::{format, Box, Rc, Vec};
+use crate::evaluator::execute_quotation;
+use crate::interpreter::AsyncInterpreter;
+use crate::value::{MapKey, RuntimeError, Value};
+use core::future::Future;
+use core::pin::Pin;
+
+fn pop_map(
+    interp: &mut AsyncInterpreter,
+    word: &str,
+) -> Result<Rc<crate::compat::BTreeMap<MapKey, Value>>, RuntimeError> {
+    match interp.pop()? {
+        Value::Map(map) => Ok(map),
+        other => Err(RuntimeError::TypeError(format!(
+            "{} expects a map, got {}",
+            word,
+            other.type_name()
+        ))),
+    }
+}
+
+fn pop_key(interp: &mut AsyncInterpreter) -> Result<MapKey, RuntimeError> {
+    MapKey::from_value(&interp.pop()?)
+}
+
+// map-get: ( map key -- value ) null when the key is missing
+pub fn map_get_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let key = pop_key(interp)?;
+    let map = pop_map(interp, "map-get")?;
+    interp.push(map.get(&key).cloned().unwrap_or(Value::Null));
+    Ok(())
+}
+
+// map-put: ( map key value -- map' )
+pub fn map_put_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let value = interp.pop()?;
+    let key = pop_key(interp)?;
+    let mut map = pop_map(interp, "map-put")?;
+    Rc::make_mut(&mut map).insert(key, value);
+    interp.push(Value::Map(map));
+    Ok(())
+}
+
+// map-remove: ( map key -- map' ) missing keys are ignored
+pub fn map_remove_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let key = pop_key(interp)?;
+    let mut map = pop_map(interp, "map-remove")?;
+    if map.contains_key(&key) {
+        Rc::make_mut(&mut map).remove(&key);
+    }
+    interp.push(Value::Map(map));
+    Ok(())
+}
+
+// map-contains?: ( map key -- bool )
+pub fn map_contains_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let key = pop_key(interp)?;
+    let map = pop_map(interp, "map-contains?")?;
+    interp.push(Value::Boolean(map.contains_key(&key)));
+    Ok(())
+}
+
+// map-size: ( map -- n )
+pub fn map_size_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let map = pop_map(interp, "map-size")?;
+    interp.push(crate::evaluator::index_value(map.len() as i64));
+    Ok(())
+}
+
+// map-keys: ( map -- list ) in key order
+pub fn map_keys_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let map = pop_map(interp, "map-keys")?;
+    let keys: Vec<Value> = map.keys().map(MapKey::to_value).collect();
+    interp.push(interp.make_list(keys));
+    Ok(())
+}
+
+// map-values: ( map -- list ) in key order
+pub fn map_values_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let map = pop_map(interp, "map-values")?;
+    let values: Vec<Value> = map.values().cloned().collect();
+    interp.push(interp.make_list(values));
+    Ok(())
+}
+
+// map-merge: ( map1 map2 -- map ) entries from map2 win on conflicts
+pub fn map_merge_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let right = pop_map(interp, "map-merge")?;
+    let mut left = pop_map(interp, "map-merge")?;
+    if !right.is_empty() {
+        let entries = Rc::make_mut(&mut left);
+        for (key, value) in right.iter() {
+            entries.insert(key.clone(), value.clone());
+        }
+    }
+    interp.push(Value::Map(left));
+    Ok(())
+}
+
+// map-each: ( map [q] -- ) runs q with ( key value -- ) for every entry, in key order
+pub fn map_each_builtin(
+    interp: &mut AsyncInterpreter,
+) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
+    Box::pin(async move {
+        let quotation = interp.pop()?;
+        let map = pop_map(interp, "map-each")?;
+        for (key, value) in map.iter() {
+            interp.push(key.to_value());
+            interp.push(value.clone());
+            execute_quotation(&quotation, interp).await?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compat::BTreeMap;
+
+    fn sample() -> Value {
+        let mut entries = BTreeMap::new();
+        entries.insert(MapKey::Atom("a".into()), Value::Int32(1));
+        entries.insert(MapKey::String("b".into()), Value::Int32(2));
+        Value::Map(Rc::new(entries))
+    }
+
+    #[test]
+    fn test_get_put_remove() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(sample());
+        interp.push(Value::QuotedAtom("a".into()));
+        map_get_impl(&mut interp).unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Int32(1)));
+
+        interp.push(sample());
+        interp.push(Value::Int32(9));
+        map_get_impl(&mut interp).unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Null));
+
+        interp.push(sample());
+        interp.push(Value::Int32(3));
+        interp.push(Value::Boolean(true));
+        map_put_impl(&mut interp).unwrap();
+        interp.push(Value::String("b".into()));
+        map_remove_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "{3 true a 1}");
+    }
+
+    #[test]
+    fn test_put_leaves_original_untouched() {
+        let mut interp = AsyncInterpreter::new();
+        let original = sample();
+
+        interp.push(original.clone());
+        interp.push(Value::QuotedAtom("c".into()));
+        interp.push(Value::Int32(3));
+        map_put_impl(&mut interp).unwrap();
+
+        assert!(matches!(interp.pop().unwrap(), Value::Map(m) if m.len() == 3));
+        assert!(matches!(original, Value::Map(m) if m.len() == 2));
+    }
+
+    #[test]
+    fn test_keys_values_merge() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(sample());
+        map_keys_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "[\"b\" a]");
+
+        interp.push(sample());
+        map_values_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "[2 1]");
+
+        let mut override_entries = BTreeMap::new();
+        override_entries.insert(MapKey::Atom("a".into()), Value::Int32(10));
+        interp.push(sample());
+        interp.push(Value::Map(Rc::new(override_entries)));
+        map_merge_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "{\"b\" 2 a 10}");
+    }
+
+    #[test]
+    fn test_invalid_key_and_non_map() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(sample());
+        interp.push(Value::Number(1.5));
+        assert!(matches!(map_get_impl(&mut interp), Err(RuntimeError::TypeError(_))));
+
+        interp.push(Value::Int32(1));
+        assert!(matches!(map_size_impl(&mut interp), Err(RuntimeError::TypeError(_))));
+    }
+}
@@ -2,6 +2,7 @@
 
 // Async I/O primitives
 pub mod print;
+pub mod write;
 pub mod emit;
 pub mod words;
 pub mod space;
@@ -9,6 +10,12 @@ pub mod space;
 // Async concurrency primitives
 pub mod delay;
 pub mod spawn;
+pub mod timer;
+pub mod select;
+pub mod channel;
+
+// Exception handling
+pub mod throw;
 
 // Utility primitives
 pub mod help;
@@ -66,6 +73,8 @@ pub mod greater_equal;
 pub mod def;
 pub mod val;
 pub mod doc;
+pub mod immediate;
+pub mod quasiquote;
 
 // Variables
 pub mod var;
@@ -83,6 +92,12 @@ pub mod list;
 // Vectors
 pub mod vector;
 
+// Higher-order sequence words over lists and vectors
+pub mod sequence;
+
+// Maps
+pub mod map;
+
 // Date/time (now superseded by platform-specific primitives in hardware/)
 // pub mod now;
 
@@ -101,6 +116,8 @@ pub mod shl;
 pub mod shr;
 
 // String/type operations
+pub mod read;
+pub mod string;
 pub mod to_string;
 pub mod truthy;
 pub mod type_of;
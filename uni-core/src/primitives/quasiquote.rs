@@ -0,0 +1,151 @@
+//! Quasiquote primitive - fills in code templates
+//!
+//! `[a ,b ,@c] is read as [a [unquote b] [unquote-splicing c]] quasiquote.
+//! quasiquote runs each unquoted item in order, the way exec would (a word or
+//! local is looked up, a list runs as code), and takes the value it leaves on
+//! top. An unquote is replaced by that value and an unquote-splicing by the
+//! items of the list it gave. Everything else is copied as it is. Templates
+//! nested inside a template are filled in along with it; there are no levels.
+
+use crate::compat::{format, Box, Rc, Vec};
+use crate::evaluator::execute_quotation;
+use crate::interpreter::AsyncInterpreter;
+use crate::value::{RuntimeError, Value};
+use core::future::Future;
+use core::pin::Pin;
+
+#[derive(Clone, Copy)]
+enum Unquote {
+    Value,
+    Splice,
+}
+
+// quasiquote: ( template -- list )
+pub fn quasiquote(interp: &mut AsyncInterpreter) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
+    Box::pin(async move {
+        // A template mentioning locals arrives as a closure over them, and
+        // those are the locals its unquotes see anyway
+        let template = match interp.pop()? {
+            Value::Closure(closure) => closure.body.clone(),
+            template @ (Value::Pair(_, _) | Value::Nil) => template,
+            other => {
+                return Err(RuntimeError::TypeError(format!(
+                    "quasiquote expects a list template, got {}",
+                    other.type_name()
+                )))
+            }
+        };
+
+        let mut unquoted = Vec::new();
+        collect_unquoted(&template, &mut unquoted);
+        let mut values = Vec::with_capacity(unquoted.len());
+        for item in unquoted {
+            execute_quotation(&item, interp).await?;
+            values.push(interp.pop()?);
+        }
+
+        let filled = fill(&template, &mut values.into_iter())?;
+        interp.push(filled);
+        Ok(())
+    })
+}
+
+// Fills in a template's unquotes, in order, with values
+// make-record-type uses this directly for the words it generates.
+pub(crate) fn fill(
+    template: &Value,
+    values: &mut impl Iterator<Item = Value>,
+) -> Result<Value, RuntimeError> {
+    let mut items = Vec::new();
+    let mut current = template;
+    while let Value::Pair(car, cdr) = current {
+        match unquote_of(car) {
+            Some((kind, _)) => {
+                let value = values.next().ok_or_else(|| {
+                    RuntimeError::TypeError("quasiquote: no value for an unquote".into())
+                })?;
+                match kind {
+                    Unquote::Value => items.push(value),
+                    Unquote::Splice => splice(value, &mut items)?,
+                }
+            }
+            None if matches!(**car, Value::Pair(_, _)) => items.push(fill(car, values)?),
+            None => items.push((**car).clone()),
+        }
+        current = cdr;
+    }
+
+    Ok(items
+        .into_iter()
+        .rev()
+        .fold(current.clone(), |acc, item| Value::Pair(Rc::new(item), Rc::new(acc))))
+}
+
+fn splice(list: Value, items: &mut Vec<Value>) -> Result<(), RuntimeError> {
+    let mut current = list.as_list();
+    loop {
+        match current {
+            Value::Pair(car, cdr) => {
+                items.push((**car).clone());
+                current = cdr;
+            }
+            Value::Nil => return Ok(()),
+            _ => {
+                return Err(RuntimeError::TypeError(format!(
+                    "unquote-splicing needs a list, got {}",
+                    list.type_name()
+                )))
+            }
+        }
+    }
+}
+
+// The unquoted items of a template and the templates inside it, in order
+fn collect_unquoted(template: &Value, unquoted: &mut Vec<Value>) {
+    let mut current = template;
+    while let Value::Pair(car, cdr) = current {
+        match unquote_of(car) {
+            Some((_, item)) => unquoted.push(item.clone()),
+            None => collect_unquoted(car, unquoted),
+        }
+        current = cdr;
+    }
+}
+
+// Only list elements are checked, since [a | ,b] is the same list as [a unquote b]
+fn unquote_of(element: &Value) -> Option<(Unquote, &Value)> {
+    if let Value::Pair(head, rest) = element
+        && let Value::Atom(name, _) = &**head
+        && let Value::Pair(item, end) = &**rest
+        && matches!(**end, Value::Nil)
+    {
+        return match &**name {
+            "unquote" => Some((Unquote::Value, item)),
+            "unquote-splicing" => Some((Unquote::Splice, item)),
+            _ => None,
+        };
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_fill_replaces_unquotes_in_order() {
+        let mut interp = AsyncInterpreter::new();
+        let template = parse("[a ,x [b ,y] ,@z c]", &mut interp).unwrap().remove(0);
+        let spliced = interp.make_list(vec![Value::Int32(3), Value::Int32(4)]);
+        let values = vec![Value::Int32(1), Value::Int32(2), spliced];
+
+        let filled = fill(&template, &mut values.into_iter()).unwrap();
+        assert_eq!(filled.to_string(), "[a 1 [b 2] 3 4 c]");
+
+        // Splicing needs a list
+        let template = parse("[,@x]", &mut interp).unwrap().remove(0);
+        let result = fill(&template, &mut vec![Value::Int32(1)].into_iter());
+        assert!(matches!(result, Err(RuntimeError::TypeError(_))));
+    }
+}
@@ -0,0 +1,66 @@
+// Reading values back from text
+// Both words go through parser::read_value, so they accept exactly the
+// literal syntax the tokenizer does (Int32, BigInt, rationals, complex, ...)
+
+use crate::interpreter::AsyncInterpreter;
+use crate::parser::read_value;
+use crate::value::{RuntimeError, Value};
+
+// string->number: ( s -- n ) null if the text isn't a single number literal
+pub fn string_to_number_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let text = interp.pop_string("string->number")?;
+    let result = match read_value(&text, interp) {
+        Ok(value) if value.is_number() => value,
+        _ => Value::Null,
+    };
+    interp.push(result);
+    Ok(())
+}
+
+// read: ( s -- value ) parses one value without executing it
+pub fn read_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let text = interp.pop_string("read")?;
+    let value = read_value(&text, interp)?;
+    interp.push(value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_to_number() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(Value::String("-42".into()));
+        string_to_number_impl(&mut interp).unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Int32(-42)));
+
+        interp.push(Value::String("123456789012345678901234567890".into()));
+        string_to_number_impl(&mut interp).unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Integer(_)));
+
+        interp.push(Value::String("2.5e3".into()));
+        string_to_number_impl(&mut interp).unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Number(n) if n == 2500.0));
+
+        for bad in ["", "abc", "1 2", "[1]", "12abc"] {
+            interp.push(Value::String(bad.into()));
+            string_to_number_impl(&mut interp).unwrap();
+            assert!(matches!(interp.pop().unwrap(), Value::Null), "{:?} should not parse", bad);
+        }
+    }
+
+    #[test]
+    fn test_read() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(Value::String("[1 \"two\" three]".into()));
+        read_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "[1 \"two\" three]");
+
+        interp.push(Value::String("[1".into()));
+        assert!(matches!(read_impl(&mut interp), Err(RuntimeError::TypeError(_))));
+    }
+}
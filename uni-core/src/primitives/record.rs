@@ -2,8 +2,11 @@
 // This module implements record types similar to R7RS Scheme's define-record-type
 // Records are named product types with labeled fields
 
-use crate::compat::{format, Rc, ToString, Vec};
+use crate::compat::{format, Rc, String, ToString, Vec};
+use crate::compile::compile;
 use crate::interpreter::{DictEntry, AsyncInterpreter};
+use crate::parser::parse_generated;
+use crate::primitives::quasiquote::fill;
 use crate::value::{RuntimeError, Value};
 
 #[cfg(not(target_os = "none"))]
@@ -26,7 +29,7 @@ pub fn make_record_type_impl(interp: &mut AsyncInterpreter) -> Result<(), Runtim
     // Extract type name string
     let type_name = match type_name_val {
         Value::String(s) => s,
-        Value::Atom(a) => a,
+        Value::Atom(a, _) => a,
         _ => {
             return Err(RuntimeError::TypeError(
                 "make-record-type: type name must be string or atom".to_string(),
@@ -36,7 +39,7 @@ pub fn make_record_type_impl(interp: &mut AsyncInterpreter) -> Result<(), Runtim
 
     // Extract field names from list
     let mut field_names: Vec<Rc<str>> = Vec::new();
-    let mut current = &field_names_list;
+    let mut current = field_names_list.as_list();
 
     loop {
         match current {
@@ -44,7 +47,7 @@ pub fn make_record_type_impl(interp: &mut AsyncInterpreter) -> Result<(), Runtim
             Value::Pair(head, tail) => {
                 match head.as_ref() {
                     Value::String(s) => field_names.push(s.clone()),
-                    Value::Atom(a) => field_names.push(a.clone()),
+                    Value::Atom(a, _) => field_names.push(a.clone()),
                     _ => {
                         return Err(RuntimeError::TypeError(
                             "make-record-type: field names must be strings or atoms".to_string(),
@@ -74,108 +77,67 @@ pub fn make_record_type_impl(interp: &mut AsyncInterpreter) -> Result<(), Runtim
         DictEntry {
             value: record_type.clone(),
             is_executable: false,
+            is_immediate: false,
             doc: None,
+            code: None,
         },
     );
 
     // Generate constructor (make-<type>)
-    let constructor_name = format!("make-{}", type_name);
-    let constructor_atom = interp.intern_atom(&constructor_name);
-
-    let constructor_type_name = type_name.clone();
-    let constructor_field_count = field_names.len();
-
-    // Create a list that will be executed to construct the record
-    let constructor_code = format!(
-        "[{} \"{}\" construct-record]",
-        constructor_field_count, constructor_type_name
+    let field_count = field_names.len();
+    let constructor = generated_body(
+        interp,
+        "[,field-count ,type-name construct-record]",
+        [Value::Int32(field_count as i32), Value::String(type_name.clone())],
+    )?;
+    define_generated(
+        interp,
+        &format!("make-{}", type_name),
+        constructor,
+        format!(
+            "Constructor for {} record type. Takes {} field values from stack.",
+            type_name, field_count
+        ),
     );
 
-    // Parse and store as executable definition
-    use crate::parser::parse;
-    let parsed_values = parse(&constructor_code, interp)
-        .map_err(|e| RuntimeError::TypeError(format!("Failed to parse constructor: {:?}", e)))?;
-
-    if let Some(parsed) = parsed_values.into_iter().next() {
-        interp.dict_insert(
-            constructor_atom.clone(),
-            DictEntry {
-                value: parsed,
-                is_executable: true,
-                doc: Some(Rc::<str>::from(format!(
-                    "Constructor for {} record type. Takes {} field values from stack.",
-                    type_name, constructor_field_count
-                ))),
-            },
-        );
-    }
-
     // Generate type predicate (<type>?)
-    let predicate_name = format!("{}?", type_name);
-    let predicate_atom = interp.intern_atom(&predicate_name);
-
-    let predicate_code = format!("[\"{}\" is-record-type?]", type_name);
-    let parsed_values = parse(&predicate_code, interp)
-        .map_err(|e| RuntimeError::TypeError(format!("Failed to parse predicate: {:?}", e)))?;
-
-    if let Some(parsed) = parsed_values.into_iter().next() {
-        interp.dict_insert(
-            predicate_atom.clone(),
-            DictEntry {
-                value: parsed,
-                is_executable: true,
-                doc: Some(Rc::<str>::from(format!(
-                    "Type predicate for {} record type.",
-                    type_name
-                ))),
-            },
-        );
-    }
+    let predicate = generated_body(
+        interp,
+        "[,type-name is-record-type?]",
+        [Value::String(type_name.clone())],
+    )?;
+    define_generated(
+        interp,
+        &format!("{}?", type_name),
+        predicate,
+        format!("Type predicate for {} record type.", type_name),
+    );
 
-    // Generate field accessors (<type>-<field>)
+    // Generate field accessors (<type>-<field>) and mutators (<type>-<field>!)
     for (field_index, field_name) in field_names.iter().enumerate() {
-        let accessor_name = format!("{}-{}", type_name, field_name);
-        let accessor_atom = interp.intern_atom(&accessor_name);
-
-        let accessor_code = format!("[\"{}\" {} get-record-field]", type_name, field_index);
-        let parsed_values = parse(&accessor_code, interp)
-            .map_err(|e| RuntimeError::TypeError(format!("Failed to parse accessor: {:?}", e)))?;
-
-        if let Some(parsed) = parsed_values.into_iter().next() {
-            interp.dict_insert(
-                accessor_atom.clone(),
-                DictEntry {
-                    value: parsed,
-                    is_executable: true,
-                    doc: Some(Rc::<str>::from(format!(
-                        "Get {} field from {} record.",
-                        field_name, type_name
-                    ))),
-                },
-            );
-        }
+        let accessor = generated_body(
+            interp,
+            "[,type-name ,field-index get-record-field]",
+            [Value::String(type_name.clone()), Value::Int32(field_index as i32)],
+        )?;
+        define_generated(
+            interp,
+            &format!("{}-{}", type_name, field_name),
+            accessor,
+            format!("Get {} field from {} record.", field_name, type_name),
+        );
 
-        // Generate field mutators (<type>-<field>!)
-        let mutator_name = format!("{}-{}!", type_name, field_name);
-        let mutator_atom = interp.intern_atom(&mutator_name);
-
-        let mutator_code = format!("[\"{}\" {} set-record-field!]", type_name, field_index);
-        let parsed_values = parse(&mutator_code, interp)
-            .map_err(|e| RuntimeError::TypeError(format!("Failed to parse mutator: {:?}", e)))?;
-
-        if let Some(parsed) = parsed_values.into_iter().next() {
-            interp.dict_insert(
-                mutator_atom.clone(),
-                DictEntry {
-                    value: parsed,
-                    is_executable: true,
-                    doc: Some(Rc::<str>::from(format!(
-                        "Set {} field in {} record.",
-                        field_name, type_name
-                    ))),
-                },
-            );
-        }
+        let mutator = generated_body(
+            interp,
+            "[,type-name ,field-index set-record-field!]",
+            [Value::String(type_name.clone()), Value::Int32(field_index as i32)],
+        )?;
+        define_generated(
+            interp,
+            &format!("{}-{}!", type_name, field_name),
+            mutator,
+            format!("Set {} field in {} record.", field_name, type_name),
+        );
     }
 
     // Push the record type descriptor to stack
@@ -184,6 +146,34 @@ pub fn make_record_type_impl(interp: &mut AsyncInterpreter) -> Result<(), Runtim
     Ok(())
 }
 
+// The body of a generated word: a code template with its unquotes filled in
+// by values (see quasiquote.rs), so names go into the code as strings rather
+// than being pasted into source text
+fn generated_body<const N: usize>(
+    interp: &mut AsyncInterpreter,
+    template: &str,
+    values: [Value; N],
+) -> Result<Value, RuntimeError> {
+    let template = parse_generated(template, interp)
+        .map_err(|e| RuntimeError::TypeError(format!("Failed to parse template: {:?}", e)))?
+        .remove(0);
+    fill(&template, &mut values.into_iter())
+}
+
+fn define_generated(interp: &mut AsyncInterpreter, name: &str, body: Value, doc: String) {
+    let atom = interp.intern_atom(name);
+    interp.dict_insert(
+        atom,
+        DictEntry {
+            code: Some(compile(&body)),
+            value: body,
+            is_executable: true,
+            is_immediate: false,
+            doc: Some(Rc::<str>::from(doc)),
+        },
+    );
+}
+
 // Helper builtin to construct record instances
 // Stack: field_values... field_count type_name -- record
 pub fn construct_record_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
@@ -193,7 +183,7 @@ pub fn construct_record_impl(interp: &mut AsyncInterpreter) -> Result<(), Runtim
     // Extract type name
     let type_name = match type_name_val {
         Value::String(s) => s,
-        Value::Atom(a) => a,
+        Value::Atom(a, _) => a,
         _ => {
             return Err(RuntimeError::TypeError(
                 "construct-record: type name must be string or atom".to_string(),
@@ -244,7 +234,7 @@ pub fn is_record_type_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeE
     // Extract type name
     let expected_type_name = match type_name_val {
         Value::String(s) => s,
-        Value::Atom(a) => a,
+        Value::Atom(a, _) => a,
         _ => {
             return Err(RuntimeError::TypeError(
                 "is-record-type?: type name must be string or atom".to_string(),
@@ -272,7 +262,7 @@ pub fn get_record_field_impl(interp: &mut AsyncInterpreter) -> Result<(), Runtim
     // Extract type name
     let expected_type_name = match type_name_val {
         Value::String(s) => s,
-        Value::Atom(a) => a,
+        Value::Atom(a, _) => a,
         _ => {
             return Err(RuntimeError::TypeError(
                 "get-record-field: type name must be string or atom".to_string(),
@@ -339,7 +329,7 @@ pub fn set_record_field_impl(interp: &mut AsyncInterpreter) -> Result<(), Runtim
     // Extract type name
     let expected_type_name = match type_name_val {
         Value::String(s) => s,
-        Value::Atom(a) => a,
+        Value::Atom(a, _) => a,
         _ => {
             return Err(RuntimeError::TypeError(
                 "set-record-field!: type name must be string or atom".to_string(),
@@ -0,0 +1,174 @@
+//! Select primitive - waits for whichever of several events happens first
+//!
+//! select takes a list of [source handler] clauses. A source is a channel
+//! (fires with the next value received from it), a task (fires with its final
+//! stack, as task-join gives it) or a number of milliseconds (a timeout). Sources
+//! written as words are looked up, so vals holding channels can be used
+//! directly. The handler of the first source to fire runs with that value on
+//! the stack; if several are ready at once, the earliest clause wins. The
+//! waits on the other sources are dropped, so they take nothing from their
+//! channels. A clause list that captured locals (see
+//! closures.rs) hands them on to its handlers.
+
+use crate::channels::Channel;
+use crate::closures::Closure;
+use crate::compat::{format, Box, Rc, Vec};
+use crate::evaluator::{execute, execute_quotation};
+use crate::interpreter::AsyncInterpreter;
+use crate::primitives::delay::{cancellable, sleep_ms};
+use crate::primitives::spawn::{finished, task_result};
+use crate::tasks::Task;
+use crate::value::{RuntimeError, Value};
+use core::future::Future;
+use core::pin::Pin;
+
+enum Source {
+    Channel(Rc<Channel>),
+    Task(Rc<Task>),
+    Timeout(u64),
+}
+
+// What a source produced when it fired
+enum Fired {
+    Received(Value),
+    Finished(Rc<Task>),
+    TimedOut,
+}
+
+type Event = Pin<Box<dyn Future<Output = Result<Fired, RuntimeError>>>>;
+
+// The event that fired first and its clause's position
+type FirstFired = (Result<Fired, RuntimeError>, usize);
+
+impl Source {
+    fn wait(self) -> Event {
+        match self {
+            Source::Channel(channel) => Box::pin(async move {
+                channel.next_value().await.map(Fired::Received)
+            }),
+            Source::Task(task) => Box::pin(async move {
+                finished(&task).await;
+                Ok(Fired::Finished(task))
+            }),
+            Source::Timeout(ms) => Box::pin(async move {
+                sleep_ms(ms).await?;
+                Ok(Fired::TimedOut)
+            }),
+        }
+    }
+}
+
+// select: ( clauses -- ... ) runs the handler of the first source to fire
+pub fn select(interp: &mut AsyncInterpreter) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
+    Box::pin(async move {
+        let (clauses, captured) = match interp.pop()? {
+            Value::Closure(closure) => (closure.body.clone(), Some(closure)),
+            clauses => (clauses, None),
+        };
+        let mut events = Vec::new();
+        let mut handlers = Vec::new();
+
+        let mut current = &clauses;
+        loop {
+            match current {
+                Value::Pair(clause, rest) => {
+                    let (source, handler) = clause_parts(clause)?;
+                    events.push(resolve_source(source, interp).await?.wait());
+                    handlers.push(match &captured {
+                        Some(closure) => Value::Closure(Rc::new(Closure {
+                            body: handler,
+                            captured: closure.captured.clone(),
+                        })),
+                        None => handler,
+                    });
+                    current = rest;
+                }
+                Value::Nil => break,
+                _ => return Err(RuntimeError::TypeError("select requires a list of [source handler] clauses".into())),
+            }
+        }
+        if events.is_empty() {
+            return Err(RuntimeError::DomainError("select requires at least one clause".into()));
+        }
+
+        let (fired, index) = cancellable(first_of(&mut events), &interp.cancel).await?;
+        // Drop the losing waits before the handler runs, so none of them can
+        // still take a value from a channel the handler uses
+        drop(events);
+
+        match fired? {
+            Fired::Received(value) => interp.push(value),
+            Fired::Finished(task) => {
+                let result = task_result(interp, &task)?;
+                interp.push(result);
+            }
+            Fired::TimedOut => {}
+        }
+        execute_quotation(&handlers[index], interp).await
+    })
+}
+
+fn clause_parts(clause: &Value) -> Result<(Value, Value), RuntimeError> {
+    if let Value::Pair(source, rest) = clause
+        && let Value::Pair(handler, end) = &**rest
+        && matches!(**end, Value::Nil)
+        && matches!(**handler, Value::Pair(_, _) | Value::Nil)
+    {
+        return Ok(((**source).clone(), (**handler).clone()));
+    }
+    Err(RuntimeError::TypeError(format!(
+        "select clause must be [source [handler]], got {}",
+        clause
+    )))
+}
+
+async fn resolve_source(source: Value, interp: &mut AsyncInterpreter) -> Result<Source, RuntimeError> {
+    let source = match source {
+        Value::Atom(..) => {
+            execute(&source, interp).await?;
+            interp.pop()?
+        }
+        other => other,
+    };
+    match source {
+        Value::Channel(channel) => Ok(Source::Channel(channel)),
+        Value::Task(task) => Ok(Source::Task(task)),
+        number @ (Value::Int32(_) | Value::Integer(_) | Value::Number(_)) => {
+            interp.push(number);
+            Ok(Source::Timeout(crate::primitives::delay::pop_ms(interp, "select")?))
+        }
+        other => Err(RuntimeError::TypeError(format!(
+            "select sources must be channels, tasks or timeouts in milliseconds, got {}",
+            other.type_name()
+        ))),
+    }
+}
+
+// ASYNC CONCEPT: Racing any number of futures
+// Finishes with the first event ready, along with its position. Events are
+// always polled in clause order, so when several are ready at once the
+// earliest clause wins; a later clause only fires while the ones before it
+// are still waiting.
+#[cfg(feature = "target-stm32h753zi")]
+async fn first_of(events: &mut [Event]) -> FirstFired {
+    embassy_futures::select::select_slice(Pin::new(events)).await
+}
+
+// tokio::select! races a fixed set of branches, so the clauses are raced as
+// the first against the rest, recursively; biased keeps it in clause order
+#[cfg(not(feature = "target-stm32h753zi"))]
+fn first_of(events: &mut [Event]) -> Pin<Box<dyn Future<Output = FirstFired> + '_>> {
+    Box::pin(async move {
+        let Some((first, rest)) = events.split_first_mut() else {
+            return core::future::pending().await;
+        };
+        if rest.is_empty() {
+            return (first.as_mut().await, 0);
+        }
+        tokio::select! {
+            biased;
+            fired = first.as_mut() => (fired, 0),
+            (fired, index) = first_of(rest) => (fired, index + 1),
+        }
+    })
+}
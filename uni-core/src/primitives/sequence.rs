@@ -0,0 +1,400 @@
+// Higher-order sequence primitives over lists and vectors
+// map, filter, fold, reduce, find, any?, all? and sort-by call quotations through
+// the evaluator (so async words work inside them); the rest are plain data operations.
+// Results keep the shape of the input: lists give lists, vectors give vectors.
+// Each call must replace its arguments with exactly one value; a quotation that
+// leaves more, or eats into the caller's stack, is an error rather than a silent mess.
+
+use crate::compat::{Box, format, Vec};
+use crate::evaluator::execute_quotation;
+use crate::interpreter::AsyncInterpreter;
+use crate::value::{RuntimeError, Value};
+use core::future::Future;
+use core::pin::Pin;
+
+type BuiltinFuture<'a> = Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + 'a>>;
+
+// Whether a sequence arrived as a list or a vector
+#[derive(Clone, Copy)]
+pub(crate) enum SeqKind {
+    List,
+    Vector,
+}
+
+// Snapshot the elements of a list or vector
+// Vectors are copied so a quotation that mutates the vector can't disturb iteration
+pub(crate) fn seq_items(value: &Value, word: &str) -> Result<(SeqKind, Vec<Value>), RuntimeError> {
+    let value = value.as_list();
+    match value {
+        Value::Array(array) => Ok((SeqKind::Vector, array.borrow().clone())),
+        Value::Nil | Value::Pair(_, _) => {
+            let mut items = Vec::new();
+            let mut current = value;
+            loop {
+                match current {
+                    Value::Pair(car, cdr) => {
+                        items.push((**car).clone());
+                        current = cdr;
+                    }
+                    Value::Nil => break,
+                    _ => {
+                        return Err(RuntimeError::TypeError(format!(
+                            "{} expects a proper list",
+                            word
+                        )))
+                    }
+                }
+            }
+            Ok((SeqKind::List, items))
+        }
+        other => Err(RuntimeError::TypeError(format!(
+            "{} expects a list or vector, got {}",
+            word,
+            other.type_name()
+        ))),
+    }
+}
+
+fn rebuild(kind: SeqKind, items: Vec<Value>, interp: &AsyncInterpreter) -> Value {
+    match kind {
+        SeqKind::List => interp.make_list(items),
+        SeqKind::Vector => interp.make_array(items),
+    }
+}
+
+fn pop_count(interp: &mut AsyncInterpreter, word: &str) -> Result<usize, RuntimeError> {
+    interp.pop_integer().map_err(|_| {
+        RuntimeError::TypeError(format!("{} expects a non-negative integer count", word))
+    })
+}
+
+// Run a quotation on the given arguments and take the single value it leaves
+// The stack below the arguments must come back untouched, with one value on top
+async fn call(
+    word: &str,
+    quotation: &Value,
+    args: Vec<Value>,
+    interp: &mut AsyncInterpreter,
+) -> Result<Value, RuntimeError> {
+    let depth = interp.stack.len();
+    let arity = args.len();
+    for arg in args {
+        interp.push(arg);
+    }
+    execute_quotation(quotation, interp).await?;
+    let expected = depth + 1;
+    let actual = interp.stack.len();
+    if actual != expected {
+        let (by, way) = if actual > expected {
+            (actual - expected, "more")
+        } else {
+            (expected - actual, "fewer")
+        };
+        return Err(RuntimeError::TypeError(format!(
+            "{} expects its quotation to turn {} value{} into 1, but it left {} {} on the stack",
+            word,
+            arity,
+            if arity == 1 { "" } else { "s" },
+            by,
+            way
+        )));
+    }
+    interp.pop()
+}
+
+async fn test_each(
+    word: &str,
+    items: &[Value],
+    quotation: &Value,
+    interp: &mut AsyncInterpreter,
+    stop_when: bool,
+) -> Result<Option<Value>, RuntimeError> {
+    for item in items {
+        let result = call(word, quotation, crate::compat::vec![item.clone()], interp).await?;
+        if interp.is_truthy(&result) == stop_when {
+            return Ok(Some(item.clone()));
+        }
+    }
+    Ok(None)
+}
+
+// Stable sort on precomputed keys, using the total order on values
+fn sort_by_keys(mut pairs: Vec<(Value, Value)>) -> Vec<Value> {
+    pairs.sort_by(|(ka, _), (kb, _)| ka.cmp(kb));
+    pairs.into_iter().map(|(_, item)| item).collect()
+}
+
+// map: ( seq [q] -- seq' ) where q is ( x -- y )
+pub fn map_builtin(interp: &mut AsyncInterpreter) -> BuiltinFuture<'_> {
+    Box::pin(async move {
+        let quotation = interp.pop()?;
+        let (kind, items) = seq_items(&interp.pop()?, "map")?;
+        let mut results = Vec::with_capacity(items.len());
+        for item in items {
+            results.push(call("map", &quotation, crate::compat::vec![item], interp).await?);
+        }
+        let result = rebuild(kind, results, interp);
+        interp.push(result);
+        Ok(())
+    })
+}
+
+// filter: ( seq [pred] -- seq' ) keeps elements where pred is truthy
+pub fn filter_builtin(interp: &mut AsyncInterpreter) -> BuiltinFuture<'_> {
+    Box::pin(async move {
+        let quotation = interp.pop()?;
+        let (kind, items) = seq_items(&interp.pop()?, "filter")?;
+        let mut kept = Vec::new();
+        for item in items {
+            let keep = call("filter", &quotation, crate::compat::vec![item.clone()], interp).await?;
+            if interp.is_truthy(&keep) {
+                kept.push(item);
+            }
+        }
+        let result = rebuild(kind, kept, interp);
+        interp.push(result);
+        Ok(())
+    })
+}
+
+// fold: ( seq init [q] -- acc ) where q is ( acc x -- acc' )
+pub fn fold_builtin(interp: &mut AsyncInterpreter) -> BuiltinFuture<'_> {
+    Box::pin(async move {
+        let quotation = interp.pop()?;
+        let init = interp.pop()?;
+        let (_, items) = seq_items(&interp.pop()?, "fold")?;
+        let mut acc = init;
+        for item in items {
+            acc = call("fold", &quotation, crate::compat::vec![acc, item], interp).await?;
+        }
+        interp.push(acc);
+        Ok(())
+    })
+}
+
+// reduce: ( seq [q] -- acc ) like fold, seeded with the first element
+pub fn reduce_builtin(interp: &mut AsyncInterpreter) -> BuiltinFuture<'_> {
+    Box::pin(async move {
+        let quotation = interp.pop()?;
+        let (_, items) = seq_items(&interp.pop()?, "reduce")?;
+        let mut items = items.into_iter();
+        let mut acc = items.next().ok_or_else(|| {
+            RuntimeError::TypeError("reduce of an empty sequence".into())
+        })?;
+        for item in items {
+            acc = call("reduce", &quotation, crate::compat::vec![acc, item], interp).await?;
+        }
+        interp.push(acc);
+        Ok(())
+    })
+}
+
+// find: ( seq [pred] -- x ) first element where pred is truthy, or null
+pub fn find_builtin(interp: &mut AsyncInterpreter) -> BuiltinFuture<'_> {
+    Box::pin(async move {
+        let quotation = interp.pop()?;
+        let (_, items) = seq_items(&interp.pop()?, "find")?;
+        let found = test_each("find", &items, &quotation, interp, true).await?;
+        interp.push(found.unwrap_or(Value::Null));
+        Ok(())
+    })
+}
+
+// any?: ( seq [pred] -- bool ) stops at the first truthy result
+pub fn any_builtin(interp: &mut AsyncInterpreter) -> BuiltinFuture<'_> {
+    Box::pin(async move {
+        let quotation = interp.pop()?;
+        let (_, items) = seq_items(&interp.pop()?, "any?")?;
+        let found = test_each("any?", &items, &quotation, interp, true).await?;
+        interp.push(Value::Boolean(found.is_some()));
+        Ok(())
+    })
+}
+
+// all?: ( seq [pred] -- bool ) stops at the first falsy result
+pub fn all_builtin(interp: &mut AsyncInterpreter) -> BuiltinFuture<'_> {
+    Box::pin(async move {
+        let quotation = interp.pop()?;
+        let (_, items) = seq_items(&interp.pop()?, "all?")?;
+        let failed = test_each("all?", &items, &quotation, interp, false).await?;
+        interp.push(Value::Boolean(failed.is_none()));
+        Ok(())
+    })
+}
+
+// sort-by: ( seq [key] -- seq' ) stable sort on the key computed for each element
+pub fn sort_by_builtin(interp: &mut AsyncInterpreter) -> BuiltinFuture<'_> {
+    Box::pin(async move {
+        let quotation = interp.pop()?;
+        let (kind, items) = seq_items(&interp.pop()?, "sort-by")?;
+        let mut keyed = Vec::with_capacity(items.len());
+        for item in items {
+            let key = call("sort-by", &quotation, crate::compat::vec![item.clone()], interp).await?;
+            keyed.push((key, item));
+        }
+        let sorted = sort_by_keys(keyed);
+        let result = rebuild(kind, sorted, interp);
+        interp.push(result);
+        Ok(())
+    })
+}
+
+// sort: ( seq -- seq' ) stable ascending sort
+pub fn sort_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let (kind, mut items) = seq_items(&interp.pop()?, "sort")?;
+    items.sort();
+    let sorted = items;
+    let result = rebuild(kind, sorted, interp);
+    interp.push(result);
+    Ok(())
+}
+
+// zip: ( seq1 seq2 -- seq ) two-element lists, as long as the shorter input
+pub fn zip_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let (_, right) = seq_items(&interp.pop()?, "zip")?;
+    let (kind, left) = seq_items(&interp.pop()?, "zip")?;
+    let pairs = left
+        .into_iter()
+        .zip(right)
+        .map(|(a, b)| interp.make_list(crate::compat::vec![a, b]))
+        .collect();
+    let result = rebuild(kind, pairs, interp);
+    interp.push(result);
+    Ok(())
+}
+
+// range: ( start end -- list ) integers from start up to (not including) end
+pub fn range_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let end = pop_bound(interp)?;
+    let start = pop_bound(interp)?;
+    let items = (start..end.max(start))
+        .map(crate::evaluator::index_value)
+        .collect();
+    interp.push(interp.make_list(items));
+    Ok(())
+}
+
+fn pop_bound(interp: &mut AsyncInterpreter) -> Result<i64, RuntimeError> {
+    use num_traits::ToPrimitive;
+    match interp.pop()? {
+        Value::Int32(i) => Ok(i as i64),
+        Value::Integer(i) => i
+            .to_i64()
+            .ok_or_else(|| RuntimeError::TypeError("range bound out of range".into())),
+        Value::Number(n) if n.fract() == 0.0 && n.is_finite() => Ok(n as i64),
+        other => Err(RuntimeError::TypeError(format!(
+            "range expects integer bounds, got {}",
+            other.type_name()
+        ))),
+    }
+}
+
+// reverse: ( seq -- seq' )
+pub fn reverse_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let (kind, mut items) = seq_items(&interp.pop()?, "reverse")?;
+    items.reverse();
+    let result = rebuild(kind, items, interp);
+    interp.push(result);
+    Ok(())
+}
+
+// take: ( seq n -- seq' ) the first n elements (or all, if shorter)
+pub fn take_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let count = pop_count(interp, "take")?;
+    let (kind, mut items) = seq_items(&interp.pop()?, "take")?;
+    items.truncate(count);
+    let result = rebuild(kind, items, interp);
+    interp.push(result);
+    Ok(())
+}
+
+// skip: ( seq n -- seq' ) everything after the first n elements
+// (named skip because drop is the stack word)
+pub fn skip_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let count = pop_count(interp, "skip")?;
+    let (kind, items) = seq_items(&interp.pop()?, "skip")?;
+    let rest = items.into_iter().skip(count).collect();
+    let result = rebuild(kind, rest, interp);
+    interp.push(result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_of(interp: &AsyncInterpreter, items: &[i32]) -> Value {
+        interp.make_list(items.iter().map(|&i| Value::Int32(i)).collect())
+    }
+
+    #[test]
+    fn test_reverse_take_skip_keep_shape() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(list_of(&interp, &[1, 2, 3]));
+        reverse_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "[3 2 1]");
+
+        interp.push(interp.make_array(crate::compat::vec![Value::Int32(1), Value::Int32(2)]));
+        interp.push(Value::Int32(5));
+        take_impl(&mut interp).unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Array(a) if a.borrow().len() == 2));
+
+        interp.push(list_of(&interp, &[1, 2, 3]));
+        interp.push(Value::Int32(1));
+        skip_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "[2 3]");
+    }
+
+    #[test]
+    fn test_range_and_zip() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(Value::Int32(2));
+        interp.push(Value::Int32(5));
+        range_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "[2 3 4]");
+
+        interp.push(Value::Int32(5));
+        interp.push(Value::Int32(2));
+        range_impl(&mut interp).unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Nil));
+
+        interp.push(list_of(&interp, &[1, 2, 3]));
+        interp.push(list_of(&interp, &[10, 20]));
+        zip_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "[[1 10] [2 20]]");
+    }
+
+    #[test]
+    fn test_sort_mixed_values() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(interp.make_list(crate::compat::vec![
+            Value::Int32(3),
+            Value::Number(1.5),
+            Value::Int32(-2),
+        ]));
+        sort_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "[-2 1.5 3]");
+
+        // Different kinds of value follow the total order instead of failing
+        interp.push(interp.make_list(crate::compat::vec![
+            Value::String("a".into()),
+            Value::Int32(1),
+            Value::Null,
+        ]));
+        sort_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "[null 1 \"a\"]");
+    }
+
+    #[test]
+    fn test_improper_list_rejected() {
+        let mut interp = AsyncInterpreter::new();
+        interp.push(Value::Pair(
+            crate::compat::Rc::new(Value::Int32(1)),
+            crate::compat::Rc::new(Value::Int32(2)),
+        ));
+        assert!(matches!(reverse_impl(&mut interp), Err(RuntimeError::TypeError(_))));
+    }
+}
@@ -1,39 +1,241 @@
 //! Async spawn primitive - spawns a quotation as a background task
+//!
+//! spawn returns a task handle (see tasks.rs); task-join, cancel, task-status and
+//! tasks below work with it on both the tokio and Embassy paths. The timer
+//! words in timer.rs start their tasks through start_task as well.
 
 use crate::interpreter::AsyncInterpreter;
+use crate::tasks::{Schedule, Task, TaskRegistry, TaskState};
 use crate::value::RuntimeError;
-use crate::compat::Box;
-use core::future::Future;
+use crate::compat::{format, Box, Rc};
+use crate::primitives::delay::{cancellable, now, sleep_until, Duration};
+use core::future::{poll_fn, Future};
 use core::pin::Pin;
+use core::task::Poll;
 
 // Import Value for both targets
 use crate::value::Value;
 
+// Most background tasks (spawn, after and every each use one) that can run at
+// once on the STM32. Embassy reserves a slot for every one of them statically,
+// so each costs RAM whether it is used or not. Set UNI_MAX_BACKGROUND_TASKS
+// when building to change it; spawning past the limit is a domain-error.
+#[cfg(feature = "target-stm32h753zi")]
+pub const MAX_BACKGROUND_TASKS: usize = match option_env!("UNI_MAX_BACKGROUND_TASKS") {
+    Some(limit) => parse_task_limit(limit),
+    None => 8,
+};
+
+#[cfg(feature = "target-stm32h753zi")]
+const fn parse_task_limit(text: &str) -> usize {
+    let digits = text.as_bytes();
+    assert!(!digits.is_empty(), "UNI_MAX_BACKGROUND_TASKS must be a positive number");
+    let mut limit = 0;
+    let mut at = 0;
+    while at < digits.len() {
+        assert!(digits[at].is_ascii_digit(), "UNI_MAX_BACKGROUND_TASKS must be a positive number");
+        limit = limit * 10 + (digits[at] - b'0') as usize;
+        at += 1;
+    }
+    assert!(limit > 0, "UNI_MAX_BACKGROUND_TASKS must be a positive number");
+    limit
+}
+
 // Import HashMap for type signatures (BTreeMap for STM32, HashMap for Linux)
 #[cfg(feature = "target-stm32h753zi")]
 use alloc::collections::BTreeMap as HashMap;
 #[cfg(not(feature = "target-stm32h753zi"))]
 use std::collections::HashMap;
 
+// Execution settings a spawned task takes over from the interpreter that spawned it
+#[derive(Clone, Copy)]
+struct TaskSettings {
+    backend: crate::interpreter::Backend,
+    yield_interval: Option<u64>,
+    fuel: Option<u64>,
+}
+
+impl TaskSettings {
+    fn of(interp: &AsyncInterpreter) -> Self {
+        TaskSettings {
+            backend: interp.backend,
+            yield_interval: interp.yield_interval,
+            fuel: interp.fuel,
+        }
+    }
+
+    fn apply(self, interp: &mut AsyncInterpreter) {
+        interp.backend = self.backend;
+        interp.yield_interval = self.yield_interval;
+        interp.fuel = self.fuel;
+    }
+}
+
+// ( quotation -- task )
 pub fn spawn(interp: &mut AsyncInterpreter) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
     Box::pin(async move {
         // Pop quotation from stack
         let quotation = interp.stack.pop()
-            .ok_or_else(|| RuntimeError::StackUnderflow)?;
+            .ok_or(RuntimeError::StackUnderflow)?;
+
+        let task = start_task(quotation, Schedule::Once, interp).await?;
+        interp.push(Value::Task(task));
+        Ok(())
+    })
+}
 
-        #[cfg(feature = "target-stm32h753zi")]
-        {
-            spawn_task_embassy(quotation, interp).await
+// Starts a background task that runs quotation on the given schedule
+pub(crate) async fn start_task(
+    quotation: Value,
+    schedule: Schedule,
+    interp: &mut AsyncInterpreter,
+) -> Result<Rc<Task>, RuntimeError> {
+    #[cfg(feature = "target-stm32h753zi")]
+    return spawn_task_embassy(quotation, schedule, interp).await;
+    #[cfg(not(feature = "target-stm32h753zi"))]
+    return spawn_task_tokio(quotation, schedule, interp).await;
+}
+
+fn pop_task(interp: &mut AsyncInterpreter, word: &str) -> Result<Rc<Task>, RuntimeError> {
+    match interp.pop()? {
+        Value::Task(task) => Ok(task),
+        other => Err(RuntimeError::TypeError(format!(
+            "{} expects a task, got {}",
+            word,
+            other.type_name()
+        ))),
+    }
+}
+
+// task-join: ( task -- list ) waits for the task and pushes its final stack as a
+// list, bottom first. If the task failed, task-join raises the same error value.
+pub fn task_join(interp: &mut AsyncInterpreter) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
+    Box::pin(async move {
+        let task = pop_task(interp, "task-join")?;
+        cancellable(finished(&task), &interp.cancel).await?;
+        let result = task_result(interp, &task)?;
+        interp.push(result);
+        Ok(())
+    })
+}
+
+// Resolves once the task has finished
+pub(crate) fn finished(task: &Task) -> impl Future<Output = ()> + '_ {
+    poll_fn(|cx| {
+        if !task.is_running() {
+            return Poll::Ready(());
         }
-        #[cfg(not(feature = "target-stm32h753zi"))]
-        {
-            spawn_task_tokio(quotation, interp).await
+        task.wake_on_finish(cx.waker());
+        Poll::Pending
+    })
+}
+
+// What task-join gives for a finished task: its final stack as a list, or its error
+pub(crate) fn task_result(interp: &AsyncInterpreter, task: &Task) -> Result<Value, RuntimeError> {
+    match task.state() {
+        TaskState::Done(stack) => Ok(interp.make_list(stack)),
+        TaskState::Failed(error) | TaskState::Cancelled(error) => Err(RuntimeError::Thrown(error)),
+        TaskState::Running => unreachable!("only called once the task has finished"),
+    }
+}
+
+// cancel: ( task -- ) interrupts the task; it finishes as cancelled, and
+// task-join raises an interrupted error. Cancelling a finished task does nothing.
+pub fn cancel_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let task = pop_task(interp, "cancel")?;
+    if task.is_running() {
+        task.cancel.cancel();
+    }
+    Ok(())
+}
+
+// task-status: ( task -- string ) "running", "done", "failed" or "cancelled"
+pub fn task_status_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let task = pop_task(interp, "task-status")?;
+    interp.push(Value::String(task.status().into()));
+    Ok(())
+}
+
+// tasks: ( -- ) lists the running tasks with the quotation each one runs
+pub fn tasks_builtin(interp: &mut AsyncInterpreter) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
+    Box::pin(async move {
+        let running = interp.tasks.running();
+        let mut lines = crate::compat::Vec::new();
+        lines.push(format!("Running tasks ({}):", running.len()));
+        for task in &running {
+            match task.schedule {
+                Schedule::Once => lines.push(format!("  #{} {}", task.id, task.origin)),
+                schedule => lines.push(format!("  #{} {} {}", task.id, task.origin, schedule)),
+            }
+        }
+        for line in lines {
+            interp.writeln_async(&line).await.map_err(|_| {
+                RuntimeError::TypeError("Failed to write to output".into())
+            })?;
         }
+        Ok(())
     })
 }
 
+// Runs a task's quotation to the end on its own interpreter and records how
+// it finished; the caller has already shared the dictionary and settings
+async fn run_task(mut task_interp: AsyncInterpreter, task: Rc<Task>, registry: Rc<TaskRegistry>) {
+    task_interp.cancel = task.cancel.clone();
+    task_interp.tasks = registry.clone();
+
+    let state = match run_schedule(&mut task_interp, &task).await {
+        Ok(()) => {
+            #[cfg(feature = "target-stm32h753zi")]
+            defmt::info!("Background task {} completed successfully", task.id);
+            TaskState::Done(core::mem::take(&mut task_interp.stack))
+        }
+        // Being cancelled is how the task was asked to stop, not news
+        Err(error) if matches!(error.root(), RuntimeError::Interrupted) => {
+            TaskState::Cancelled(error.into_value())
+        }
+        Err(error) => {
+            #[cfg(feature = "target-stm32h753zi")]
+            defmt::error!("Background task {} error: {:?}", task.id, defmt::Debug2Format(&error));
+            #[cfg(not(feature = "target-stm32h753zi"))]
+            eprintln!("Background task {} error: {}", task.id, error);
+            // Whoever joins the task gets the error as try would have
+            TaskState::Failed(error.into_value())
+        }
+    };
+    registry.finish(&task, state);
+}
+
+// Runs the task's quotation when its schedule says to
+async fn run_schedule(interp: &mut AsyncInterpreter, task: &Task) -> Result<(), RuntimeError> {
+    match task.schedule {
+        Schedule::Once => run_origin(interp, task).await,
+        Schedule::After(ms) => {
+            let deadline = now() + Duration::from_millis(ms);
+            cancellable(sleep_until(deadline), &interp.cancel).await?;
+            run_origin(interp, task).await
+        }
+        Schedule::Every(ms) => {
+            let period = Duration::from_millis(ms);
+            let mut deadline = now();
+            loop {
+                deadline = Schedule::next_tick(deadline, period, now());
+                cancellable(sleep_until(deadline), &interp.cancel).await?;
+                // Each tick starts on an empty stack
+                interp.stack.clear();
+                run_origin(interp, task).await?;
+            }
+        }
+    }
+}
+
+// Execute the quotation by pushing it and calling exec
+async fn run_origin(interp: &mut AsyncInterpreter, task: &Task) -> Result<(), RuntimeError> {
+    interp.stack.push(task.origin.clone());
+    crate::evaluator::execute_string("exec", interp).await
+}
+
 #[cfg(feature = "target-stm32h753zi")]
-async fn spawn_task_embassy(quotation: Value, interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+async fn spawn_task_embassy(quotation: Value, schedule: Schedule, interp: &mut AsyncInterpreter) -> Result<Rc<Task>, RuntimeError> {
     // Check if spawner is available
     let spawner = interp.spawner.as_ref()
         .ok_or_else(|| RuntimeError::DomainError("No spawner available - cannot spawn tasks".into()))?
@@ -41,37 +243,56 @@ async fn spawn_task_embassy(quotation: Value, interp: &mut AsyncInterpreter) ->
 
     // Validate it's a list/quotation
     match &quotation {
-        Value::Pair(_, _) | Value::Nil => {
+        Value::Pair(_, _) | Value::Nil | Value::Closure(_) => {
             #[cfg(feature = "target-stm32h753zi")]
             defmt::info!("Spawning background task with quotation");
 
-            // Clone quotation and dictionary Rc for the spawned task
-            let quotation_clone = quotation.clone();
+            // Register the task and clone the dictionary Rc for it
+            let registry = interp.tasks.clone();
+            let task = registry.start(quotation, schedule);
             let dict_rc = interp.dictionary.clone();
+            let version_rc = interp.dictionary_version.clone();
+            let settings = TaskSettings::of(interp);
 
             // Spawn the task
-            spawner.spawn(background_task(quotation_clone, dict_rc))
-                .map_err(|_| {
-                    #[cfg(feature = "target-stm32h753zi")]
-                    defmt::error!("Failed to spawn task - spawner full");
-                    RuntimeError::DomainError("Failed to spawn task - spawner full".into())
-                })?;
+            let spawned = spawner.spawn(background_task(
+                task.clone(),
+                registry.clone(),
+                dict_rc,
+                version_rc,
+                settings,
+            ));
+            if spawned.is_err() {
+                #[cfg(feature = "target-stm32h753zi")]
+                defmt::error!("Failed to spawn task - all {} task slots in use", MAX_BACKGROUND_TASKS);
+                let message = format!(
+                    "too many background tasks: at most {} can run at once",
+                    MAX_BACKGROUND_TASKS
+                );
+                let never_ran = RuntimeError::DomainError(message.clone()).into_value();
+                registry.finish(&task, TaskState::Failed(never_ran));
+                return Err(RuntimeError::DomainError(message));
+            }
 
             #[cfg(feature = "target-stm32h753zi")]
-            defmt::info!("Background task spawned successfully");
+            defmt::info!("Background task {} spawned successfully", task.id);
 
-            Ok(())
+            Ok(task)
         }
         _ => Err(RuntimeError::TypeError("spawn requires a quotation (list)".into())),
     }
 }
 
 // Embassy task that executes Uni code in the background
+// Its pool holds MAX_BACKGROUND_TASKS of them, one per task running at once
 #[cfg(feature = "target-stm32h753zi")]
-#[embassy_executor::task]
+#[embassy_executor::task(pool_size = MAX_BACKGROUND_TASKS)]
 async fn background_task(
-    quotation: Value,
+    task: Rc<Task>,
+    registry: Rc<TaskRegistry>,
     dictionary: crate::compat::Arc<core::cell::RefCell<HashMap<crate::compat::Rc<str>, crate::interpreter::DictEntry>>>,
+    dictionary_version: crate::compat::Arc<core::sync::atomic::AtomicUsize>,
+    settings: TaskSettings,
 ) {
     use crate::interpreter::AsyncInterpreter;
     use crate::compat::Box;
@@ -81,8 +302,10 @@ async fn background_task(
     // Create new interpreter for this task
     let mut task_interp = AsyncInterpreter::new();
 
-    // Share the dictionary with the main task
+    // Share the dictionary (and its version, so cached lookups see redefinitions) with the main task
     task_interp.dictionary = dictionary;
+    task_interp.dictionary_version = dictionary_version;
+    settings.apply(&mut task_interp);
 
     // Set up output to use the same USB channel
     let output = Box::new(UsbOutputForTask::new());
@@ -92,16 +315,7 @@ async fn background_task(
     #[cfg(target_os = "none")]
     defmt::info!("Background task executing quotation with {} items in dict", task_interp.dictionary.borrow().len());
 
-    // Execute the quotation by pushing it and calling exec
-    // Push quotation to stack
-    task_interp.stack.push(quotation.clone());
-
-    // Execute "exec" to actually run the quotation
-    use crate::evaluator::execute_string;
-    match execute_string("exec", &mut task_interp).await {
-        Ok(_) => defmt::info!("Background task completed successfully"),
-        Err(e) => defmt::error!("Background task error: {:?}", defmt::Debug2Format(&e)),
-    }
+    run_task(task_interp, task, registry).await;
 }
 
 // Output implementation for spawned tasks on STM32
@@ -154,22 +368,32 @@ impl crate::output::AsyncOutput for UsbOutputForTask {
 
 // Tokio spawn implementation for Linux/std targets
 #[cfg(not(feature = "target-stm32h753zi"))]
-async fn spawn_task_tokio(quotation: Value, interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+async fn spawn_task_tokio(quotation: Value, schedule: Schedule, interp: &mut AsyncInterpreter) -> Result<Rc<Task>, RuntimeError> {
     // Validate it's a list/quotation
     match &quotation {
-        Value::Pair(_, _) | Value::Nil => {
-            // Clone quotation and dictionary for the spawned task
-            let quotation_clone = quotation.clone();
+        Value::Pair(_, _) | Value::Nil | Value::Closure(_) => {
+            // Register the task and clone the dictionary for it
+            let registry = interp.tasks.clone();
+            let task = registry.start(quotation, schedule);
             let dict_clone = interp.dictionary.clone();
+            let version_clone = interp.dictionary_version.clone();
+            let settings = TaskSettings::of(interp);
 
             // Check if we have async output
             let has_output = interp.has_async_output();
 
             // Spawn the task using tokio::task::spawn_local
             // This allows us to use !Send types like Rc<>
-            tokio::task::spawn_local(background_task_tokio(quotation_clone, dict_clone, has_output));
-
-            Ok(())
+            tokio::task::spawn_local(background_task_tokio(
+                task.clone(),
+                registry,
+                dict_clone,
+                version_clone,
+                settings,
+                has_output,
+            ));
+
+            Ok(task)
         }
         _ => Err(RuntimeError::TypeError("spawn requires a quotation (list)".into())),
     }
@@ -178,8 +402,11 @@ async fn spawn_task_tokio(quotation: Value, interp: &mut AsyncInterpreter) -> Re
 // Tokio background task for executing Uni code
 #[cfg(not(feature = "target-stm32h753zi"))]
 async fn background_task_tokio(
-    quotation: Value,
+    task: Rc<Task>,
+    registry: Rc<TaskRegistry>,
     dictionary: std::sync::Arc<std::sync::Mutex<HashMap<std::rc::Rc<str>, crate::interpreter::DictEntry>>>,
+    dictionary_version: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    settings: TaskSettings,
     has_output: bool,
 ) {
     use crate::interpreter::AsyncInterpreter;
@@ -187,8 +414,10 @@ async fn background_task_tokio(
     // Create new interpreter for this task
     let mut task_interp = AsyncInterpreter::new();
 
-    // Share the dictionary with the main task
+    // Share the dictionary (and its version, so cached lookups see redefinitions) with the main task
     task_interp.dictionary = dictionary;
+    task_interp.dictionary_version = dictionary_version;
+    settings.apply(&mut task_interp);
 
     // Set up output if needed
     if has_output {
@@ -196,12 +425,5 @@ async fn background_task_tokio(
         task_interp.set_async_output(output);
     }
 
-    // Execute the quotation by pushing it and calling exec
-    task_interp.stack.push(quotation.clone());
-
-    // Execute "exec" to actually run the quotation
-    use crate::evaluator::execute_string;
-    if let Err(e) = execute_string("exec", &mut task_interp).await {
-        eprintln!("Background task error: {}", e);
-    }
+    run_task(task_interp, task, registry).await;
 }
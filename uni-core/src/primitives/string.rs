@@ -0,0 +1,303 @@
+// String primitives
+// Positions and lengths count Unicode scalar values (chars), never bytes, so
+// slicing can't split a multi-byte character. A "char" on the stack is a
+// one-character string.
+
+use crate::compat::{format, String, ToString, Vec};
+use crate::interpreter::AsyncInterpreter;
+use crate::primitives::sequence::seq_items;
+use crate::value::{RuntimeError, Value};
+
+fn pop_index(interp: &mut AsyncInterpreter, word: &str) -> Result<usize, RuntimeError> {
+    interp
+        .pop_integer()
+        .map_err(|_| RuntimeError::TypeError(format!("{} expects a non-negative integer index", word)))
+}
+
+fn push_str(interp: &mut AsyncInterpreter, s: &str) {
+    interp.push(Value::String(s.into()));
+}
+
+// Byte offset of the char at char_index; char_index may equal the length
+fn byte_offset(s: &str, char_index: usize, word: &str) -> Result<usize, RuntimeError> {
+    if char_index == 0 {
+        return Ok(0);
+    }
+    match s.char_indices().nth(char_index) {
+        Some((offset, _)) => Ok(offset),
+        None if s.chars().count() == char_index => Ok(s.len()),
+        None => Err(RuntimeError::DomainError(format!(
+            "{} index {} out of bounds for length {}",
+            word,
+            char_index,
+            s.chars().count()
+        ))),
+    }
+}
+
+// Text used when gluing arbitrary values into a string: strings go in as-is
+fn display_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_string(),
+        other => other.to_string(),
+    }
+}
+
+// string-length: ( s -- n )
+pub fn string_length_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let s = interp.pop_string("string-length")?;
+    interp.push(crate::evaluator::index_value(s.chars().count() as i64));
+    Ok(())
+}
+
+// substring: ( s start end -- s' ) chars from start up to (not including) end
+pub fn substring_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let end = pop_index(interp, "substring")?;
+    let start = pop_index(interp, "substring")?;
+    let s = interp.pop_string("substring")?;
+    if start > end {
+        return Err(RuntimeError::DomainError(format!(
+            "substring start {} is after end {}",
+            start, end
+        )));
+    }
+    let from = byte_offset(&s, start, "substring")?;
+    let to = byte_offset(&s, end, "substring")?;
+    push_str(interp, &s[from..to]);
+    Ok(())
+}
+
+// char-at: ( s index -- char )
+pub fn char_at_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let index = pop_index(interp, "char-at")?;
+    let s = interp.pop_string("char-at")?;
+    let ch = s.chars().nth(index).ok_or_else(|| {
+        RuntimeError::DomainError(format!(
+            "char-at index {} out of bounds for length {}",
+            index,
+            s.chars().count()
+        ))
+    })?;
+    interp.push(Value::String(ch.to_string().into()));
+    Ok(())
+}
+
+// index-of: ( s needle -- n ) char position of the first match, or null
+pub fn index_of_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let needle = interp.pop_string("index-of")?;
+    let s = interp.pop_string("index-of")?;
+    let result = match s.find(needle.as_ref()) {
+        Some(offset) => crate::evaluator::index_value(s[..offset].chars().count() as i64),
+        None => Value::Null,
+    };
+    interp.push(result);
+    Ok(())
+}
+
+// split: ( s sep -- list ) an empty separator splits into chars
+pub fn split_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let sep = interp.pop_string("split")?;
+    let s = interp.pop_string("split")?;
+    let parts: Vec<Value> = if sep.is_empty() {
+        s.chars().map(|c| Value::String(c.to_string().into())).collect()
+    } else {
+        s.split(sep.as_ref()).map(|part| Value::String(part.into())).collect()
+    };
+    interp.push(interp.make_list(parts));
+    Ok(())
+}
+
+// join: ( seq sep -- s ) non-string elements are shown as with ->string
+pub fn join_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let sep = interp.pop_string("join")?;
+    let (_, items) = seq_items(&interp.pop()?, "join")?;
+    let parts: Vec<String> = items.iter().map(display_text).collect();
+    push_str(interp, &parts.join(sep.as_ref()));
+    Ok(())
+}
+
+// trim: ( s -- s' ) strips leading and trailing whitespace
+pub fn trim_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let s = interp.pop_string("trim")?;
+    push_str(interp, s.trim());
+    Ok(())
+}
+
+// upper: ( s -- s' )
+pub fn upper_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let s = interp.pop_string("upper")?;
+    push_str(interp, &s.to_uppercase());
+    Ok(())
+}
+
+// lower: ( s -- s' )
+pub fn lower_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let s = interp.pop_string("lower")?;
+    push_str(interp, &s.to_lowercase());
+    Ok(())
+}
+
+// starts-with?: ( s prefix -- bool )
+pub fn starts_with_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let prefix = interp.pop_string("starts-with?")?;
+    let s = interp.pop_string("starts-with?")?;
+    interp.push(Value::Boolean(s.starts_with(prefix.as_ref())));
+    Ok(())
+}
+
+// ends-with?: ( s suffix -- bool )
+pub fn ends_with_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let suffix = interp.pop_string("ends-with?")?;
+    let s = interp.pop_string("ends-with?")?;
+    interp.push(Value::Boolean(s.ends_with(suffix.as_ref())));
+    Ok(())
+}
+
+// replace: ( s from to -- s' ) replaces every occurrence of from
+pub fn replace_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let to = interp.pop_string("replace")?;
+    let from = interp.pop_string("replace")?;
+    let s = interp.pop_string("replace")?;
+    if from.is_empty() {
+        return Err(RuntimeError::DomainError("replace: pattern must not be empty".to_string()));
+    }
+    push_str(interp, &s.replace(from.as_ref(), to.as_ref()));
+    Ok(())
+}
+
+// string->list: ( s -- list ) one single-char string per character
+pub fn string_to_list_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let s = interp.pop_string("string->list")?;
+    let chars: Vec<Value> = s.chars().map(|c| Value::String(c.to_string().into())).collect();
+    interp.push(interp.make_list(chars));
+    Ok(())
+}
+
+// chars->string: ( seq -- s ) accepts strings and integer code points
+pub fn chars_to_string_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let (_, items) = seq_items(&interp.pop()?, "chars->string")?;
+    let mut result = String::new();
+    for item in items {
+        match item {
+            Value::String(s) => result.push_str(&s),
+            Value::Int32(code) => {
+                let ch = u32::try_from(code).ok().and_then(char::from_u32).ok_or_else(|| {
+                    RuntimeError::DomainError(format!("chars->string: {} is not a valid code point", code))
+                })?;
+                result.push(ch);
+            }
+            other => {
+                return Err(RuntimeError::TypeError(format!(
+                    "chars->string expects strings or code points, got {}",
+                    other.type_name()
+                )))
+            }
+        }
+    }
+    push_str(interp, &result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> Value {
+        Value::String(s.into())
+    }
+
+    fn pop_str(interp: &mut AsyncInterpreter) -> String {
+        match interp.pop().unwrap() {
+            Value::String(s) => s.to_string(),
+            other => panic!("Expected string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_lengths_and_slices_count_chars() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(string("héllo→"));
+        string_length_impl(&mut interp).unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Int32(6)));
+
+        interp.push(string("héllo→"));
+        interp.push(Value::Int32(1));
+        interp.push(Value::Int32(6));
+        substring_impl(&mut interp).unwrap();
+        assert_eq!(pop_str(&mut interp), "éllo→");
+
+        interp.push(string("héllo→"));
+        interp.push(Value::Int32(5));
+        char_at_impl(&mut interp).unwrap();
+        assert_eq!(pop_str(&mut interp), "→");
+
+        interp.push(string("héllo"));
+        interp.push(string("l"));
+        index_of_impl(&mut interp).unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::Int32(2)));
+    }
+
+    #[test]
+    fn test_out_of_bounds_is_error() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(string("abc"));
+        interp.push(Value::Int32(1));
+        interp.push(Value::Int32(4));
+        assert!(matches!(substring_impl(&mut interp), Err(RuntimeError::DomainError(_))));
+
+        interp.push(string("abc"));
+        interp.push(Value::Int32(3));
+        assert!(matches!(char_at_impl(&mut interp), Err(RuntimeError::DomainError(_))));
+
+        interp.push(string("abc"));
+        interp.push(Value::Int32(2));
+        interp.push(Value::Int32(1));
+        assert!(matches!(substring_impl(&mut interp), Err(RuntimeError::DomainError(_))));
+
+        // A non-string is still a type error
+        interp.push(Value::Int32(7));
+        interp.push(Value::Int32(0));
+        assert!(matches!(char_at_impl(&mut interp), Err(RuntimeError::TypeError(_))));
+    }
+
+    #[test]
+    fn test_split_and_join() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(string("a,b,,c"));
+        interp.push(string(","));
+        split_impl(&mut interp).unwrap();
+        assert_eq!(interp.pop().unwrap().to_string(), "[\"a\" \"b\" \"\" \"c\"]");
+
+        interp.push(interp.make_list(crate::compat::vec![string("x"), Value::Int32(1)]));
+        interp.push(string("-"));
+        join_impl(&mut interp).unwrap();
+        assert_eq!(pop_str(&mut interp), "x-1");
+    }
+
+    #[test]
+    fn test_case_and_trim() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(string("  Straße \n"));
+        trim_impl(&mut interp).unwrap();
+        upper_impl(&mut interp).unwrap();
+        assert_eq!(pop_str(&mut interp), "STRASSE");
+    }
+
+    #[test]
+    fn test_chars_round_trip() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(string("añb"));
+        string_to_list_impl(&mut interp).unwrap();
+        chars_to_string_impl(&mut interp).unwrap();
+        assert_eq!(pop_str(&mut interp), "añb");
+
+        interp.push(interp.make_list(crate::compat::vec![Value::Int32(72), string("i")]));
+        chars_to_string_impl(&mut interp).unwrap();
+        assert_eq!(pop_str(&mut interp), "Hi");
+    }
+}
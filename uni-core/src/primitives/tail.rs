@@ -7,9 +7,9 @@ use crate::value::{RuntimeError, Value};
 // CDR: ( [a|b] -- b )
 pub fn cdr_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
     let pair = interp.pop()?;
-    match pair {
+    match pair.as_list() {
         Value::Pair(_, cdr) => {
-            interp.push((*cdr).clone());
+            interp.push((**cdr).clone());
             Ok(())
         }
         _ => Err(RuntimeError::TypeError("CDR requires a pair".to_string())),
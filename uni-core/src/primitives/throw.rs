@@ -0,0 +1,29 @@
+// Throw primitive - raises a value as an exception
+// The value unwinds to the nearest `try` handler, which receives it unchanged
+
+use crate::interpreter::AsyncInterpreter;
+use crate::value::RuntimeError;
+
+pub fn throw_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let value = interp.pop()?;
+    Err(RuntimeError::Thrown(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::Value;
+
+    #[test]
+    fn test_throw_impl() {
+        let mut interp = AsyncInterpreter::new();
+
+        interp.push(Value::Int32(42));
+        let result = throw_impl(&mut interp);
+        assert!(matches!(result, Err(RuntimeError::Thrown(Value::Int32(42)))));
+        assert_eq!(interp.stack.len(), 0);
+
+        // Nothing to throw
+        assert!(matches!(throw_impl(&mut interp), Err(RuntimeError::StackUnderflow)));
+    }
+}
@@ -0,0 +1,49 @@
+//! Timer primitives - run a quotation later, or over and over on a period
+//!
+//! A timer is a task with a schedule (see tasks.rs), so after and every push
+//! the same handle spawn does: cancel stops the timer and task-join waits for it.
+
+use crate::compat::{format, Box};
+use crate::interpreter::AsyncInterpreter;
+use crate::primitives::delay::pop_ms;
+use crate::primitives::spawn::start_task;
+use crate::tasks::Schedule;
+use crate::value::{RuntimeError, Value};
+use core::future::Future;
+use core::pin::Pin;
+
+fn pop_quotation(interp: &mut AsyncInterpreter, word: &str) -> Result<Value, RuntimeError> {
+    match interp.pop()? {
+        quotation @ (Value::Pair(_, _) | Value::Nil | Value::Closure(_)) => Ok(quotation),
+        other => Err(RuntimeError::TypeError(format!(
+            "{} requires a quotation (list), got {}",
+            word,
+            other.type_name()
+        ))),
+    }
+}
+
+// after: ( ms quotation -- task ) runs the quotation once, ms from now
+pub fn after(interp: &mut AsyncInterpreter) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
+    Box::pin(async move {
+        let quotation = pop_quotation(interp, "after")?;
+        let ms = pop_ms(interp, "after")?;
+        let task = start_task(quotation, Schedule::After(ms), interp).await?;
+        interp.push(Value::Task(task));
+        Ok(())
+    })
+}
+
+// every: ( ms quotation -- task ) runs the quotation every ms until cancelled
+pub fn every(interp: &mut AsyncInterpreter) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
+    Box::pin(async move {
+        let quotation = pop_quotation(interp, "every")?;
+        let ms = pop_ms(interp, "every")?;
+        if ms == 0 {
+            return Err(RuntimeError::DomainError("every requires a period of at least 1 ms".into()));
+        }
+        let task = start_task(quotation, Schedule::Every(ms), interp).await?;
+        interp.push(Value::Task(task));
+        Ok(())
+    })
+}
@@ -10,11 +10,12 @@ pub fn val_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
     let name_value = interp.pop()?;
 
     let atom = match name_value {
-        Value::Atom(atom) => atom,
+        Value::Atom(atom, _) => atom,
         _ => return Err(RuntimeError::TypeError(
             "VAL requires an atom as the first argument (use 'name value val)".to_string()
         )),
     };
+    crate::compile::check_definable(&atom, "val")?;
 
     // Insert into dictionary as non-executable (constant)
     interp.dict_insert(
@@ -22,7 +23,9 @@ pub fn val_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
         DictEntry {
             value,
             is_executable: false,
+            is_immediate: false,
             doc: None,
+            code: None,
         },
     );
 
@@ -39,7 +42,7 @@ mod tests {
         let mut interp = AsyncInterpreter::new();
 
         let name = interp.intern_atom("pi");
-        interp.push(Value::Atom(name.clone()));
+        interp.push(Value::Atom(name.clone(), None));
         interp.push(Value::Number(3.14159));
         val_impl(&mut interp).unwrap();
 
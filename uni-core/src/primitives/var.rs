@@ -17,7 +17,7 @@ pub fn var_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
     let initial_value = interp.pop()?;
 
     let name = match name_val {
-        Value::Atom(ref atom) => atom.clone(),
+        Value::Atom(ref atom, _) => atom.clone(),
         _ => {
             return Err(RuntimeError::TypeError(format!(
                 "var expects atom name, got {:?}",
@@ -25,13 +25,16 @@ pub fn var_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
             )))
         }
     };
+    crate::compile::check_definable(&name, "var")?;
 
     let var = Value::Variable(Rc::new(RefCell::new(initial_value)));
 
     let dict_entry = crate::interpreter::DictEntry {
         value: var,
         is_executable: true,
+        is_immediate: false,
         doc: None,
+        code: None,
     };
 
     interp.dict_insert(name, dict_entry);
@@ -51,7 +54,7 @@ mod tests {
         // Create variable: 0 'counter var
         interp.push(Value::Int32(0));
         let name = interp.intern_atom("counter");
-        interp.push(Value::Atom(name.clone()));
+        interp.push(Value::Atom(name.clone(), None));
         
         var_impl(&mut interp).unwrap();
 
@@ -65,7 +68,7 @@ mod tests {
 
         interp.push(Value::Int32(42));
         let name = interp.intern_atom("x");
-        interp.push(Value::Atom(name.clone()));
+        interp.push(Value::Atom(name.clone(), None));
         var_impl(&mut interp).unwrap();
 
         // Verify it's marked as executable
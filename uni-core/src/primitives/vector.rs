@@ -35,7 +35,7 @@ fn expect_index(index: f64, op_name: &str) -> Result<usize, RuntimeError> {
 
 fn collect_list_elements(list: Value) -> Result<Vec<Value>, RuntimeError> {
     let mut elements = Vec::new();
-    let mut current = list;
+    let mut current = list.as_list().clone();
 
     loop {
         match current {
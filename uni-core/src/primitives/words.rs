@@ -38,6 +38,13 @@ pub fn words_builtin(interp: &mut AsyncInterpreter)
         words.push(String::from("exec"));
         words.push(String::from("if"));
         words.push(String::from("quit"));
+        words.push(String::from("try"));
+        words.push(String::from("cond"));
+        words.push(String::from("case"));
+        words.push(String::from("match"));
+        for loop_word in ["times", "do", "loop", "range-each", "leave"] {
+            words.push(String::from(loop_word));
+        }
         words.sort();
 
         // Display header
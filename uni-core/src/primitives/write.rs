@@ -0,0 +1,45 @@
+// Writing values as re-readable Uni source
+// write prints like . does, but strings keep their quotes and escapes and
+// every data value comes out in a form that read parses back unchanged.
+
+use crate::compat::Box;
+use crate::interpreter::AsyncInterpreter;
+use crate::value::{RuntimeError, Value};
+use crate::writer::write_value;
+use core::future::Future;
+use core::pin::Pin;
+
+// ->source: ( value -- string )
+pub fn to_source_impl(interp: &mut AsyncInterpreter) -> Result<(), RuntimeError> {
+    let value = interp.pop()?;
+    let text = write_value(&value)?;
+    interp.push(Value::String(text.into()));
+    Ok(())
+}
+
+// write: ( value -- )
+pub fn write_builtin(
+    interp: &mut AsyncInterpreter,
+) -> Pin<Box<dyn Future<Output = Result<(), RuntimeError>> + '_>> {
+    Box::pin(async move {
+        let value = interp.pop()?;
+        let text = write_value(&value)?;
+        interp
+            .write_str_async(&text)
+            .await
+            .map_err(|_| RuntimeError::TypeError("Failed to write to output".into()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_source_quotes_strings() {
+        let mut interp = AsyncInterpreter::new();
+        interp.push(Value::String("say \"hi\"".into()));
+        to_source_impl(&mut interp).unwrap();
+        assert!(matches!(interp.pop().unwrap(), Value::String(s) if s.as_ref() == "\"say \\\"hi\\\"\""));
+    }
+}
@@ -9,10 +9,79 @@ use crate::interpreter::AsyncInterpreter;
 #[cfg(feature = "repl")]
 use crate::evaluator::execute_string;
 
+/// Format a runtime error and its Uni backtrace for display in a REPL.
+///
+/// Frames are listed most recent call first, one per line, each line ending
+/// with `newline` (`"\r\n"` for serial terminals).
+#[cfg(feature = "repl")]
+pub fn format_error(error: &crate::value::RuntimeError, newline: &str) -> crate::compat::String {
+    use core::fmt::Write;
+
+    let mut text = crate::compat::String::new();
+    let _ = write!(text, "Error: {}{}", error, newline);
+    let backtrace = error.backtrace();
+    if !backtrace.is_empty() {
+        let _ = write!(text, "Backtrace (most recent call first):{}", newline);
+        for frame in backtrace.iter().rev() {
+            let _ = write!(text, "  {}{}", frame, newline);
+        }
+    }
+    text
+}
+
+/// The byte a terminal sends for Ctrl-C.
+#[cfg(feature = "repl")]
+const CTRL_C: u8 = 0x03;
+
+/// Execute a line while watching the terminal for Ctrl-C.
+///
+/// Ctrl-C cancels the interpreter's cancel token, so the line stops with
+/// `RuntimeError::Interrupted`. Anything else typed while the line runs is
+/// discarded.
+#[cfg(feature = "repl")]
+async fn execute_interruptibly<T>(
+    line: &str,
+    interpreter: &mut AsyncInterpreter,
+    terminal: &mut T,
+) -> Result<(), crate::value::RuntimeError>
+where
+    T: editline::AsyncTerminal,
+{
+    use core::future::{poll_fn, Future};
+    use core::pin::pin;
+    use core::task::Poll;
+
+    // A Ctrl-C pressed before this line belongs to nothing
+    let cancel = interpreter.cancel.clone();
+    cancel.reset();
+
+    let mut execution = pin!(execute_string(line, interpreter));
+    let mut watcher = pin!(async {
+        while let Ok(byte) = terminal.read_byte().await {
+            if byte == CTRL_C {
+                cancel.cancel();
+            }
+        }
+    });
+    let mut watching = true; // Until the terminal stops giving us bytes
+
+    poll_fn(|cx| {
+        if let Poll::Ready(result) = execution.as_mut().poll(cx) {
+            return Poll::Ready(result);
+        }
+        if watching && watcher.as_mut().poll(cx).is_ready() {
+            watching = false;
+        }
+        Poll::Pending
+    })
+    .await
+}
+
 /// Run an async REPL loop with the given terminal and interpreter.
 ///
 /// This is the basic REPL without async output support. Use this for simple
 /// terminals or when spawned tasks don't need to interrupt the prompt.
+/// Ctrl-C interrupts the line being executed.
 ///
 /// # Arguments
 ///
@@ -50,7 +119,7 @@ where
         match editor.read_line(terminal).await {
             Ok(line) => {
                 if !line.trim().is_empty() {
-                    match execute_string(line.as_str(), interpreter).await {
+                    match execute_interruptibly(line.as_str(), interpreter, terminal).await {
                         Ok(_) => {
                             // Print blank line, then stack top
                             if let Some(value) = interpreter.stack.last() {
@@ -70,16 +139,10 @@ where
                         }
                         Err(e) => {
                             #[cfg(target_os = "none")]
-                            {
-                                use crate::compat::format;
-                                let err_str = format!("Error: {}\r\n", e);
-                                let _ = terminal.write(err_str.as_bytes()).await;
-                            }
+                            let newline = "\r\n";
                             #[cfg(not(target_os = "none"))]
-                            {
-                                let err_str = format!("Error: {}\n", e);
-                                let _ = terminal.write(err_str.as_bytes()).await;
-                            }
+                            let newline = "\n";
+                            let _ = terminal.write(format_error(&e, newline).as_bytes()).await;
                             let _ = terminal.flush().await;
                         }
                     }
@@ -108,7 +171,8 @@ where
 /// Run an async REPL with support for real-time output from spawned tasks.
 ///
 /// This version uses editline's `read_line_with_async_output()` to allow
-/// background tasks to interrupt the prompt with output.
+/// background tasks to interrupt the prompt with output. Ctrl-C interrupts
+/// the line being executed.
 ///
 /// # Arguments
 ///
@@ -138,7 +202,7 @@ where
     Fut: core::future::Future<Output = Option<heapless::Vec<u8, 256>>>,
 {
     use editline::AsyncLineEditor;
-    use embassy_futures::select::{select, Either};
+    use embassy_futures::select::{select3, Either3};
     use core::pin::pin;
 
     let mut editor = AsyncLineEditor::new(256, 10);
@@ -160,21 +224,42 @@ where
                 if !line.trim().is_empty() {
                     // Execute code while draining output in real-time
                     let exec_result = {
+                        // A Ctrl-C pressed before this line belongs to nothing
+                        let cancel = interpreter.cancel.clone();
+                        cancel.reset();
+
                         let exec_fut = pin!(execute_string(line.as_str(), interpreter));
                         let mut exec_fut = exec_fut;
+                        let mut watching = true; // Until the terminal stops giving us bytes
 
                         // Continuously drain output until execution completes
                         loop {
-                            match select(&mut exec_fut, crate::platform_output::WRITE_CHANNEL.receive()).await {
-                                Either::First(result) => {
+                            // Bound first so the read no longer borrows the terminal below
+                            let event = select3(
+                                &mut exec_fut,
+                                crate::platform_output::WRITE_CHANNEL.receive(),
+                                async {
+                                    if watching {
+                                        terminal.read_byte().await
+                                    } else {
+                                        core::future::pending().await
+                                    }
+                                },
+                            )
+                            .await;
+                            match event {
+                                Either3::First(result) => {
                                     // Execution completed
                                     break result;
                                 }
-                                Either::Second(data) => {
+                                Either3::Second(data) => {
                                     // Output available - write it immediately
                                     let _ = terminal.write(&data).await;
                                     let _ = terminal.flush().await;
                                 }
+                                Either3::Third(Ok(CTRL_C)) => cancel.cancel(),
+                                Either3::Third(Ok(_)) => {} // Other input is discarded while a line runs
+                                Either3::Third(Err(_)) => watching = false, // Don't spin on a failing terminal
                             }
                         }
                     };
@@ -197,9 +282,7 @@ where
                             }
                         }
                         Err(e) => {
-                            use crate::compat::format;
-                            let err_str = format!("Error: {}\r\n", e);
-                            let _ = terminal.write(err_str.as_bytes()).await;
+                            let _ = terminal.write(format_error(&e, "\r\n").as_bytes()).await;
                             let _ = terminal.flush().await;
                         }
                     }
@@ -23,6 +23,13 @@ impl StdoutOutput {
     }
 }
 
+#[cfg(feature = "std")]
+impl Default for StdoutOutput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(feature = "std")]
 impl AsyncOutput for StdoutOutput {
     fn write<'a>(&'a mut self, data: &'a [u8])
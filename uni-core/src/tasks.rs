@@ -0,0 +1,174 @@
+// Tasks: handles to quotations running in the background
+//
+// spawn runs a quotation on an interpreter of its own (sharing the dictionary)
+// and hands back a Value::Task. The handle is how everyone else sees the task:
+// task-join waits for it and receives its final stack, cancel interrupts it through
+// its interpreter's cancel token, and task-status reports how it is doing.
+// An interpreter and every task it spawns share one registry, which numbers
+// the tasks and lists the ones still running.
+//
+// Timers are tasks too: after and every start a task whose schedule says
+// when to run its quotation, so the same handle cancels or joins them.
+
+use crate::compat::{Rc, Vec};
+use crate::interpreter::CancelToken;
+use crate::primitives::delay::{Duration, Instant};
+use crate::value::Value;
+use core::cell::{Cell, RefCell};
+use core::task::Waker;
+
+pub struct Task {
+    pub id: usize,
+    pub origin: Value,       // The quotation the task runs
+    pub schedule: Schedule,  // When it runs it
+    pub cancel: CancelToken, // The task interpreter's token
+    state: RefCell<TaskState>,
+    waiters: RefCell<Vec<Waker>>, // Woken when the task finishes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    Once,       // spawn: right away
+    After(u64), // after: once, this many milliseconds from now
+    Every(u64), // every: each time this many milliseconds pass, until cancelled
+}
+
+impl Schedule {
+    // The tick of a periodic schedule that follows deadline. Ticks stay on the
+    // grid laid out from the first one, so slow runs don't push later ticks
+    // back; ticks already missed by now are skipped rather than run in a burst.
+    pub(crate) fn next_tick(deadline: Instant, period: Duration, now: Instant) -> Instant {
+        let mut next = deadline + period;
+        while next < now {
+            next += period;
+        }
+        next
+    }
+}
+
+impl core::fmt::Display for Schedule {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Schedule::Once => Ok(()),
+            Schedule::After(ms) => write!(f, "after {} ms", ms),
+            Schedule::Every(ms) => write!(f, "every {} ms", ms),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum TaskState {
+    Running,
+    Done(Vec<Value>), // Final stack, bottom first
+    Failed(Value),    // The error, as a try handler would have received it
+    Cancelled(Value), // Stopped by cancel; the interrupted error task-join raises
+}
+
+impl Task {
+    pub fn state(&self) -> TaskState {
+        self.state.borrow().clone()
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(*self.state.borrow(), TaskState::Running)
+    }
+
+    // The name task-status reports
+    pub fn status(&self) -> &'static str {
+        match *self.state.borrow() {
+            TaskState::Running => "running",
+            TaskState::Done(_) => "done",
+            TaskState::Failed(_) => "failed",
+            TaskState::Cancelled(_) => "cancelled",
+        }
+    }
+
+    pub(crate) fn finish(&self, state: TaskState) {
+        *self.state.borrow_mut() = state;
+        for waker in self.waiters.borrow_mut().drain(..) {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn wake_on_finish(&self, waker: &Waker) {
+        let mut waiters = self.waiters.borrow_mut();
+        if !waiters.iter().any(|waiter| waiter.will_wake(waker)) {
+            waiters.push(waker.clone());
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TaskRegistry {
+    last_id: Cell<usize>,
+    running: RefCell<Vec<Rc<Task>>>,
+}
+
+impl TaskRegistry {
+    // Registers a task for origin that the caller is about to start
+    pub(crate) fn start(&self, origin: Value, schedule: Schedule) -> Rc<Task> {
+        let id = self.last_id.get() + 1;
+        self.last_id.set(id);
+        let task = Rc::new(Task {
+            id,
+            origin,
+            schedule,
+            cancel: CancelToken::default(),
+            state: RefCell::new(TaskState::Running),
+            waiters: RefCell::new(Vec::new()),
+        });
+        self.running.borrow_mut().push(task.clone());
+        #[cfg(feature = "std")]
+        crate::virtual_time::note_activity();
+        task
+    }
+
+    pub(crate) fn finish(&self, task: &Task, state: TaskState) {
+        self.running.borrow_mut().retain(|running| running.id != task.id);
+        task.finish(state);
+        #[cfg(feature = "std")]
+        crate::virtual_time::note_activity();
+    }
+
+    // Tasks that haven't finished yet, oldest first
+    pub fn running(&self) -> Vec<Rc<Task>> {
+        self.running.borrow().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_tracks_running_tasks() {
+        let registry = TaskRegistry::default();
+        let first = registry.start(Value::Nil, Schedule::Once);
+        let second = registry.start(Value::Nil, Schedule::Every(10));
+        assert_eq!((first.id, second.id), (1, 2));
+        assert_eq!(registry.running().len(), 2);
+
+        registry.finish(&first, TaskState::Done(Vec::new()));
+        assert_eq!(first.status(), "done");
+        assert!(second.is_running());
+        let running: Vec<usize> = registry.running().iter().map(|task| task.id).collect();
+        assert_eq!(running, [2]);
+
+        registry.finish(&second, TaskState::Cancelled(Value::Null));
+        assert_eq!(second.status(), "cancelled");
+        assert!(registry.running().is_empty());
+    }
+
+    #[test]
+    fn test_periodic_ticks_stay_on_the_grid() {
+        let start = Instant::now();
+        let period = Duration::from_millis(100);
+        let at = |ms| start + Duration::from_millis(ms);
+
+        // A run that ended before the next tick doesn't move it
+        assert_eq!(Schedule::next_tick(at(100), period, at(130)), at(200));
+        // One that overran skips the ticks it missed and keeps the phase
+        assert_eq!(Schedule::next_tick(at(100), period, at(350)), at(400));
+        assert_eq!(Schedule::next_tick(at(100), period, at(300)), at(300));
+    }
+}
@@ -1,5 +1,5 @@
 // Temporary new tokenizer implementation with complete position tracking
-use crate::compat::{fmt, String, ToString, Vec};
+use crate::compat::{fmt, format, Rc, String, ToString, Vec};
 
 // RUST CONCEPT: Source position for rich error messages
 #[derive(Debug, Clone, PartialEq)]
@@ -19,6 +19,15 @@ impl SourcePos {
     }
 }
 
+// RUST CONCEPT: Source span carried by parsed atoms
+// Lets the evaluator report where a failing word was written
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceSpan {
+    pub start: SourcePos,
+    pub end: SourcePos,
+    pub source: Option<Rc<str>>, // File or input name, None for anonymous input
+}
+
 // RUST CONCEPT: Token with embedded source position
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token {
@@ -60,7 +69,12 @@ pub enum TokenKind {
     LeftBracket,
     ArrayLeftBracket,
     RightBracket,
+    LeftBrace,  // Opens a map literal { key value ... }
+    RightBrace,
     Quote,
+    Quasiquote,      // ` before a list template
+    Unquote,         // , inside a template
+    UnquoteSplicing, // ,@ inside a template
     Pipe, // For cons pair notation like [1 | rest]
 }
 
@@ -82,7 +96,12 @@ impl fmt::Display for TokenKind {
             TokenKind::LeftBracket => write!(f, "["),
             TokenKind::ArrayLeftBracket => write!(f, "#["),
             TokenKind::RightBracket => write!(f, "]"),
+            TokenKind::LeftBrace => write!(f, "{{"),
+            TokenKind::RightBrace => write!(f, "}}"),
             TokenKind::Quote => write!(f, "'"),
+            TokenKind::Quasiquote => write!(f, "`"),
+            TokenKind::Unquote => write!(f, ","),
+            TokenKind::UnquoteSplicing => write!(f, ",@"),
             TokenKind::Pipe => write!(f, "|"),
         }
     }
@@ -94,6 +113,18 @@ impl fmt::Display for Token {
     }
 }
 
+// One part of a complex literal: a float, or one of the non-finite float
+// literals (+inf.0, -inf.0, +nan.0) the writer uses
+#[cfg(feature = "complex_numbers")]
+pub(crate) fn parse_float_part(text: &str) -> Option<f64> {
+    match text {
+        "+inf.0" => Some(f64::INFINITY),
+        "-inf.0" => Some(f64::NEG_INFINITY),
+        "+nan.0" => Some(f64::NAN),
+        _ => text.parse().ok(),
+    }
+}
+
 pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
     let mut tokens = Vec::new();
     let mut chars = input.chars().peekable();
@@ -112,6 +143,12 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
         *offset += ch.len_utf8();
     }
 
+    // Optional sign followed by digits - any size, the parser makes it a BigInt if needed
+    fn is_integer_text(s: &str) -> bool {
+        let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+        !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+    }
+
     // Helper function to classify atom-like strings into appropriate token types
     fn classify_atom(s: String) -> TokenKind {
         // Check for BigInt suffix (e.g., 123n, -456n, 123456789012345678901234567890n)
@@ -132,39 +169,41 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
         // Check for rational (e.g., 3/4)
         if s.contains('/') {
             let parts: Vec<&str> = s.split('/').collect();
-            if parts.len() == 2
-                && let (Ok(_), Ok(_)) = (parts[0].parse::<i64>(), parts[1].parse::<i64>())
-            {
+            if parts.len() == 2 && is_integer_text(parts[0]) && is_integer_text(parts[1]) {
                 return TokenKind::Rational(parts[0].to_string(), parts[1].to_string());
             }
         }
 
-        // Check for complex/gaussian (e.g., 3+4i, 3.0+4.0i, 5i)
+        // Check for complex/gaussian (e.g., 3+4i, 3.0+4.0i, 5i, 1e-7+2.5i)
         #[cfg(feature = "complex_numbers")]
         if s.ends_with('i') && s.len() > 1 {
             let num_part = &s[..s.len() - 1];
-            // Find the last + or - that's not at the start
-            if let Some(op_pos) = num_part.char_indices().skip(1).find(|(_, c)| *c == '+' || *c == '-').map(|(pos, _)| pos) {
+            // Find the last + or - that's not at the start or an exponent's sign
+            let bytes = num_part.as_bytes();
+            let op_pos = (1..bytes.len())
+                .rev()
+                .find(|&pos| matches!(bytes[pos], b'+' | b'-') && !matches!(bytes[pos - 1], b'e' | b'E'));
+            if let Some(op_pos) = op_pos {
                 let real_part = &num_part[..op_pos];
                 let imag_part = &num_part[op_pos..];
 
                 // Check if both parts are integers (Gaussian)
-                if let (Ok(_), Ok(_)) = (real_part.parse::<i64>(), imag_part.parse::<i64>()) {
+                if is_integer_text(real_part) && is_integer_text(imag_part) {
                     return TokenKind::GaussianInt(real_part.to_string(), imag_part.to_string());
                 }
 
                 // Check if either part is a float (Complex)
-                if real_part.parse::<f64>().is_ok() && imag_part.parse::<f64>().is_ok() {
+                if parse_float_part(real_part).is_some() && parse_float_part(imag_part).is_some() {
                     return TokenKind::Complex(real_part.to_string(), imag_part.to_string());
                 }
             } else {
                 // Pure imaginary (e.g., 5i, -5i, 3.5i)
                 // Try integer first
-                if num_part.parse::<i64>().is_ok() {
+                if is_integer_text(num_part) {
                     return TokenKind::GaussianInt("0".to_string(), num_part.to_string());
                 }
                 // Try float
-                if num_part.parse::<f64>().is_ok() {
+                if parse_float_part(num_part).is_some() {
                     return TokenKind::Complex("0".to_string(), num_part.to_string());
                 }
             }
@@ -212,7 +251,7 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                     Some(_) => {
                         let mut atom = String::from("#");
                         while let Some(&ch) = chars.peek() {
-                            if ch.is_whitespace() || "[]|\'\"\\\\".contains(ch) {
+                            if ch.is_whitespace() || "[]{}|\'\"\\\\".contains(ch) {
                                 break;
                             }
                             atom.push(ch);
@@ -245,6 +284,26 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                 ));
             }
 
+            '{' => {
+                let consumed = chars.next().unwrap();
+                advance_pos(consumed, &mut line, &mut column, &mut offset);
+                tokens.push(Token::new(
+                    TokenKind::LeftBrace,
+                    SourcePos::new(start_line, start_column, start_offset),
+                    SourcePos::new(line, column, offset),
+                ));
+            }
+
+            '}' => {
+                let consumed = chars.next().unwrap();
+                advance_pos(consumed, &mut line, &mut column, &mut offset);
+                tokens.push(Token::new(
+                    TokenKind::RightBrace,
+                    SourcePos::new(start_line, start_column, start_offset),
+                    SourcePos::new(line, column, offset),
+                ));
+            }
+
             '\'' => {
                 let consumed = chars.next().unwrap();
                 advance_pos(consumed, &mut line, &mut column, &mut offset);
@@ -255,6 +314,34 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                 ));
             }
 
+            // Only at the start of a token, so atoms may still contain them
+            '`' => {
+                let consumed = chars.next().unwrap();
+                advance_pos(consumed, &mut line, &mut column, &mut offset);
+                tokens.push(Token::new(
+                    TokenKind::Quasiquote,
+                    SourcePos::new(start_line, start_column, start_offset),
+                    SourcePos::new(line, column, offset),
+                ));
+            }
+
+            ',' => {
+                let consumed = chars.next().unwrap();
+                advance_pos(consumed, &mut line, &mut column, &mut offset);
+                let kind = if chars.peek() == Some(&'@') {
+                    let consumed = chars.next().unwrap();
+                    advance_pos(consumed, &mut line, &mut column, &mut offset);
+                    TokenKind::UnquoteSplicing
+                } else {
+                    TokenKind::Unquote
+                };
+                tokens.push(Token::new(
+                    kind,
+                    SourcePos::new(start_line, start_column, start_offset),
+                    SourcePos::new(line, column, offset),
+                ));
+            }
+
             '\\' => {
                 // Skip comments - consume everything until newline
                 let consumed = chars.next().unwrap(); // consume the backslash
@@ -284,14 +371,32 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                 let mut string = String::new();
                 let mut escaped = false;
 
-                for ch in chars.by_ref() {
+                while let Some(ch) = chars.next() {
                     advance_pos(ch, &mut line, &mut column, &mut offset);
                     if escaped {
                         match ch {
                             'n' => string.push('\n'),
                             't' => string.push('\t'),
+                            'r' => string.push('\r'),
                             '\\' => string.push('\\'),
                             '"' => string.push('"'),
+                            // \u{hex} - any Unicode scalar value, e.g. control characters
+                            'u' if chars.peek() == Some(&'{') => {
+                                let mut hex = String::new();
+                                for digit in chars.by_ref() {
+                                    advance_pos(digit, &mut line, &mut column, &mut offset);
+                                    if digit == '}' {
+                                        break;
+                                    }
+                                    if digit != '{' {
+                                        hex.push(digit);
+                                    }
+                                }
+                                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                                    Some(decoded) => string.push(decoded),
+                                    None => return Err(format!("Invalid unicode escape: \\u{{{}}}", hex)),
+                                }
+                            }
                             _ => {
                                 string.push('\\');
                                 string.push(ch);
@@ -351,7 +456,7 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                     // Continue collecting as an atom-like string (extended number literal)
                     // Don't break on '.' since we might have decimal complex numbers like "-1.5+2.5i"
                     while let Some(&ch) = chars.peek() {
-                        if ch.is_whitespace() || "[]|\'\"\\\\".contains(ch) {
+                        if ch.is_whitespace() || "[]{}|\'\"\\\\".contains(ch) {
                             break;
                         }
                         num_str.push(ch);
@@ -386,7 +491,7 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                                 // If it's not a valid number, treat it as an atom
                                 // Continue collecting non-whitespace chars
                                 while let Some(&ch) = chars.peek() {
-                                    if ch.is_whitespace() || "[]|\'\"\\\\".contains(ch) {
+                                    if ch.is_whitespace() || "[]{}|\'\"\\\\".contains(ch) {
                                         break;
                                     }
                                     num_str.push(ch);
@@ -439,7 +544,7 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                     // Continue collecting as an atom-like string (extended number literal)
                     // Don't break on '.' since we might have decimal complex numbers like "1.5+2.5i"
                     while let Some(&ch) = chars.peek() {
-                        if ch.is_whitespace() || "[]|\'\"\\\\".contains(ch) {
+                        if ch.is_whitespace() || "[]{}|\'\"\\\\".contains(ch) {
                             break;
                         }
                         num_str.push(ch);
@@ -475,7 +580,7 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                                 // If it's not a valid number, treat it as an atom
                                 // Continue collecting non-whitespace chars
                                 while let Some(&ch) = chars.peek() {
-                                    if ch.is_whitespace() || "[]|\'\"\\\\".contains(ch) {
+                                    if ch.is_whitespace() || "[]{}|\'\"\\\\".contains(ch) {
                                         break;
                                     }
                                     num_str.push(ch);
@@ -497,7 +602,7 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                 let mut atom = String::new();
 
                 while let Some(&ch) = chars.peek() {
-                    if ch.is_whitespace() || "[]|\\'\"\\\\".contains(ch) {
+                    if ch.is_whitespace() || "[]{}|\\'\"\\\\".contains(ch) {
                         break;
                     }
                     atom.push(ch);
@@ -512,6 +617,15 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                         "true" => TokenKind::Boolean(true),
                         "false" => TokenKind::Boolean(false),
                         "null" => TokenKind::Null,
+                        // Non-finite floats have no digits, so they get explicit literals
+                        "+inf.0" => TokenKind::Number(f64::INFINITY),
+                        "-inf.0" => TokenKind::Number(f64::NEG_INFINITY),
+                        "+nan.0" => TokenKind::Number(f64::NAN),
+                        // A complex number whose real part is one of those
+                        #[cfg(feature = "complex_numbers")]
+                        _ if atom.ends_with('i') && ["+inf.0", "-inf.0", "+nan.0"].iter().any(|p| atom.starts_with(p)) => {
+                            classify_atom(atom)
+                        }
                         _ => TokenKind::Atom(atom),
                     };
                     tokens.push(Token::new(
@@ -590,6 +704,29 @@ mod tests {
         assert!(matches!(tokens[3].kind, TokenKind::RightBracket));
     }
 
+    #[test]
+    fn test_tokenize_braces() {
+        // Braces end atoms and numbers, so no spaces are needed around them
+        let tokens = tokenize("{a 1}").unwrap();
+        assert_eq!(tokens.len(), 4);
+        assert!(matches!(tokens[0].kind, TokenKind::LeftBrace));
+        assert!(matches!(&tokens[1].kind, TokenKind::Atom(s) if s == "a"));
+        assert!(matches!(&tokens[2].kind, TokenKind::Integer(s) if s == "1"));
+        assert!(matches!(tokens[3].kind, TokenKind::RightBrace));
+    }
+
+    #[test]
+    fn test_tokenize_quasiquote() {
+        let tokens = tokenize("`[a ,b ,@c d,e]").unwrap();
+        assert_eq!(tokens.len(), 9);
+        assert!(matches!(tokens[0].kind, TokenKind::Quasiquote));
+        assert!(matches!(tokens[3].kind, TokenKind::Unquote));
+        assert!(matches!(&tokens[4].kind, TokenKind::Atom(s) if s == "b"));
+        assert!(matches!(tokens[5].kind, TokenKind::UnquoteSplicing));
+        // Commas only count at the start of a token
+        assert!(matches!(&tokens[7].kind, TokenKind::Atom(s) if s == "d,e"));
+    }
+
     #[test]
     fn test_tokenize_position_tracking() {
         let tokens = tokenize("hello\nworld").unwrap();
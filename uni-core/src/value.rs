@@ -1,5 +1,6 @@
-use crate::compat::{Box, Rc, String, Vec, fmt};
-use crate::tokenizer::SourcePos;
+use crate::compat::{BTreeMap, Box, Rc, String, ToString, Vec, fmt, format, vec};
+use crate::tokenizer::{SourcePos, SourceSpan};
+use core::cmp::Ordering;
 
 #[cfg(not(target_os = "none"))]
 use std::cell::RefCell;
@@ -36,13 +37,14 @@ pub enum Value {
     GaussianInt(BigInt, BigInt),    // Gaussian integer (real, imaginary) - both integers
     #[cfg(feature = "complex_numbers")]
     Complex(Complex64),             // Complex number (a + bi) - floating point components
-    Atom(Rc<str>),                  // Interned atoms for efficiency
+    Atom(Rc<str>, Option<Rc<SourceSpan>>), // Interned atom plus where the parser found it
     QuotedAtom(Rc<str>),            // Quoted atoms - push without executing
     String(Rc<str>),                // Literal strings - ref counted but not interned
     Boolean(bool),                  // True/false boolean values
     Null,                           // Null/undefined value (distinct from Nil empty list)
     Pair(Rc<Value>, Rc<Value>),    // Cons cell for lists
     Array(Rc<RefCell<Vec<Value>>>), // Mutable array/vector
+    Map(Rc<BTreeMap<MapKey, Value>>), // Immutable key/value map - updates copy on write
     Variable(Rc<RefCell<Value>>),   // Mutable variable (Forth-style)
     Nil,                            // Empty list marker
 
@@ -75,6 +77,18 @@ pub enum Value {
     // Stores 32-bit floats (standard for audio/DSP, GPU compute)
     // More memory-efficient than f64 for large datasets
     F32Buffer(Rc<RefCell<Vec<f32>>>),
+
+    // ASYNC CONCEPT: Handle to a spawned task
+    // Returned by spawn; task-join, cancel and task-status take it (see tasks.rs)
+    Task(Rc<crate::tasks::Task>),
+
+    // ASYNC CONCEPT: Bounded channel shared between tasks
+    // Made by make-channel; send, receive and try-receive take it (see channels.rs)
+    Channel(Rc<crate::channels::Channel>),
+
+    // RUST CONCEPT: Quotation plus the locals it refers to
+    // Pushed in place of a quotation that mentions bound locals (see closures.rs)
+    Closure(Rc<crate::closures::Closure>),
 }
 
 // Implement Debug manually since AsyncPrimitiveFn doesn't implement Debug
@@ -90,19 +104,23 @@ impl fmt::Debug for Value {
             Value::GaussianInt(re, im) => write!(f, "GaussianInt({}, {})", re, im),
             #[cfg(feature = "complex_numbers")]
             Value::Complex(c) => write!(f, "Complex({:?})", c),
-            Value::Atom(a) => write!(f, "Atom({})", a),
+            Value::Atom(a, _) => write!(f, "Atom({})", a),
             Value::QuotedAtom(a) => write!(f, "QuotedAtom({})", a),
             Value::String(s) => write!(f, "String({})", s),
             Value::Boolean(b) => write!(f, "Boolean({})", b),
             Value::Null => write!(f, "Null"),
             Value::Pair(car, cdr) => write!(f, "Pair({:?}, {:?})", car, cdr),
             Value::Array(arr) => write!(f, "Array({:?})", arr),
+            Value::Map(map) => write!(f, "Map({:?})", map),
             Value::Variable(v) => write!(f, "Variable({:?})", v),
             Value::Nil => write!(f, "Nil"),
             Value::Record { type_name, fields } => write!(f, "Record({}:{:?})", type_name, fields),
             Value::RecordType { type_name, field_names } => write!(f, "RecordType({}:{:?})", type_name, field_names),
             Value::I32Buffer(buf) => write!(f, "I32Buffer({:?})", buf),
             Value::F32Buffer(buf) => write!(f, "F32Buffer({:?})", buf),
+            Value::Task(task) => write!(f, "Task({})", task.id),
+            Value::Channel(channel) => write!(f, "Channel({}/{})", channel.len(), channel.capacity()),
+            Value::Closure(closure) => write!(f, "Closure({:?})", closure.body),
         }
     }
 }
@@ -120,13 +138,14 @@ impl Value {
             Value::GaussianInt(_, _) => "gaussian",
             #[cfg(feature = "complex_numbers")]
             Value::Complex(_) => "complex",
-            Value::Atom(_) => "atom",
+            Value::Atom(_, _) => "atom",
             Value::QuotedAtom(_) => "quoted-atom",
             Value::String(_) => "string",
             Value::Boolean(_) => "boolean",
             Value::Null => "null",
             Value::Pair(_, _) => "list",
             Value::Array(_) => "vector",
+            Value::Map(_) => "map",
             Value::Variable(_) => "variable",
             Value::Nil => "nil",
             Value::AsyncBuiltin(_) => "builtin",
@@ -134,6 +153,9 @@ impl Value {
             Value::RecordType { .. } => "record-type",
             Value::I32Buffer(_) => "i32-buffer",
             Value::F32Buffer(_) => "f32-buffer",
+            Value::Task(_) => "task",
+            Value::Channel(_) => "channel",
+            Value::Closure(_) => "closure",
         }
     }
 
@@ -196,6 +218,244 @@ impl Value {
     }
 }
 
+// RUST CONCEPT: Deep structural equality and a total order over all values
+// Numbers compare by value across the numeric tower, so 1, 1n, 2/2 and 1.0 are
+// all equal, and an exact number is compared with a float exactly rather than
+// rounded to one. Floats follow IEEE except that NaN equals itself and sorts above
+// every other number, which keeps the order total. Values of different kinds
+// are ordered by kind_rank. Lists, vectors, maps, records and buffers compare
+// element by element, like strings do. Variables and builtins only have
+// identity, so they compare by address, and so do task handles. A closure
+// compares as the quotation it holds.
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (left, right) = (self.as_list(), other.as_list());
+        match (left, right) {
+            (Value::Null, Value::Null) | (Value::Nil, Value::Nil) => Ordering::Equal,
+            (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+            (a, b) if a.is_number() && b.is_number() => compare_numbers(a, b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Atom(a, _), Value::Atom(b, _)) => a.cmp(b),
+            (Value::QuotedAtom(a), Value::QuotedAtom(b)) => a.cmp(b),
+            (Value::Pair(_, _), Value::Pair(_, _)) => compare_lists(left, right),
+            (Value::Nil, Value::Pair(_, _)) => Ordering::Less,
+            (Value::Pair(_, _), Value::Nil) => Ordering::Greater,
+            (Value::Array(a), Value::Array(b)) => {
+                if Rc::ptr_eq(a, b) {
+                    return Ordering::Equal;
+                }
+                a.borrow().iter().cmp(b.borrow().iter())
+            }
+            (Value::Map(a), Value::Map(b)) => a.iter().cmp(b.iter()),
+            (
+                Value::Record { type_name: ta, fields: fa },
+                Value::Record { type_name: tb, fields: fb },
+            ) => ta
+                .cmp(tb)
+                .then_with(|| fa.borrow().iter().cmp(fb.borrow().iter())),
+            (
+                Value::RecordType { type_name: ta, field_names: fa },
+                Value::RecordType { type_name: tb, field_names: fb },
+            ) => ta.cmp(tb).then_with(|| fa.cmp(fb)),
+            (Value::I32Buffer(a), Value::I32Buffer(b)) => {
+                a.borrow().as_slice().cmp(b.borrow().as_slice())
+            }
+            (Value::F32Buffer(a), Value::F32Buffer(b)) => {
+                let (a, b) = (a.borrow(), b.borrow());
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(x, y)| compare_floats(*x as f64, *y as f64))
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or_else(|| a.len().cmp(&b.len()))
+            }
+            (Value::Variable(a), Value::Variable(b)) => Rc::as_ptr(a).cmp(&Rc::as_ptr(b)),
+            (Value::Task(a), Value::Task(b)) => Rc::as_ptr(a).cmp(&Rc::as_ptr(b)),
+            (Value::Channel(a), Value::Channel(b)) => Rc::as_ptr(a).cmp(&Rc::as_ptr(b)),
+            (Value::AsyncBuiltin(a), Value::AsyncBuiltin(b)) => (*a as usize).cmp(&(*b as usize)),
+            (a, b) => a.kind_rank().cmp(&b.kind_rank()),
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Value {}
+
+impl Value {
+    // A closure is its quotation to anything that reads it as a list, so
+    // lists written next to bound locals stay usable as data
+    pub fn as_list(&self) -> &Value {
+        match self {
+            Value::Closure(closure) => &closure.body,
+            other => other,
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        match self {
+            Value::Number(_) | Value::Int32(_) | Value::Integer(_) | Value::Rational(_) => true,
+            #[cfg(feature = "complex_numbers")]
+            Value::GaussianInt(_, _) | Value::Complex(_) => true,
+            _ => false,
+        }
+    }
+
+    // Position of each kind of value in the total order
+    fn kind_rank(&self) -> u8 {
+        match self {
+            Value::Null => 0,
+            Value::Boolean(_) => 1,
+            Value::Number(_) | Value::Int32(_) | Value::Integer(_) | Value::Rational(_) => 2,
+            #[cfg(feature = "complex_numbers")]
+            Value::GaussianInt(_, _) | Value::Complex(_) => 2,
+            Value::String(_) => 3,
+            Value::Atom(_, _) => 4,
+            Value::QuotedAtom(_) => 5,
+            Value::Nil | Value::Pair(_, _) | Value::Closure(_) => 6,
+            Value::Array(_) => 7,
+            Value::Map(_) => 8,
+            Value::Record { .. } => 9,
+            Value::RecordType { .. } => 10,
+            Value::I32Buffer(_) => 11,
+            Value::F32Buffer(_) => 12,
+            Value::Variable(_) => 13,
+            Value::AsyncBuiltin(_) => 14,
+            Value::Task(_) => 15,
+            Value::Channel(_) => 16,
+        }
+    }
+}
+
+// -0.0 equals 0.0; NaN equals NaN and is greater than everything else
+fn compare_floats(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b)
+        .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+}
+
+fn compare_numbers(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(x), exact) if is_exact(exact) => return compare_exact_with_float(exact, *x).reverse(),
+        (exact, Value::Number(y)) if is_exact(exact) => return compare_exact_with_float(exact, *y),
+        _ => {}
+    }
+
+    let (a, b) = crate::primitives::numeric_promotion::promote_pair(a, b);
+    match (&a, &b) {
+        (Value::Int32(x), Value::Int32(y)) => x.cmp(y),
+        (Value::Integer(x), Value::Integer(y)) => x.cmp(y),
+        (Value::Rational(x), Value::Rational(y)) => x.cmp(y),
+        (Value::Number(x), Value::Number(y)) => compare_floats(*x, *y),
+        // Complex numbers have no natural order - compare real parts, then imaginary
+        #[cfg(feature = "complex_numbers")]
+        (Value::GaussianInt(xr, xi), Value::GaussianInt(yr, yi)) => {
+            xr.cmp(yr).then_with(|| xi.cmp(yi))
+        }
+        #[cfg(feature = "complex_numbers")]
+        (Value::Complex(x), Value::Complex(y)) => {
+            compare_floats(x.re, y.re).then_with(|| compare_floats(x.im, y.im))
+        }
+        // promote_pair always yields two values of the same numeric type
+        _ => Ordering::Equal,
+    }
+}
+
+fn is_exact(value: &Value) -> bool {
+    matches!(value, Value::Int32(_) | Value::Integer(_) | Value::Rational(_))
+}
+
+// Promoting the exact number to f64 would round it, so 2^53 + 1 and 2^53
+// would both equal 2^53 as a float without equalling each other. Instead the
+// float is made exact (every finite f64 is a rational) and the two compared
+// as rationals.
+fn compare_exact_with_float(exact: &Value, float: f64) -> Ordering {
+    if float.is_nan() {
+        return Ordering::Less;
+    }
+    if float.is_infinite() {
+        return if float > 0.0 { Ordering::Less } else { Ordering::Greater };
+    }
+    let exact = match exact {
+        // Every i32 is exactly representable as an f64
+        Value::Int32(i) => return compare_floats(*i as f64, float),
+        Value::Integer(i) => BigRational::from_integer(i.clone()),
+        Value::Rational(r) => r.clone(),
+        _ => return Ordering::Equal,
+    };
+    match BigRational::from_float(float) {
+        Some(float) => exact.cmp(&float),
+        None => Ordering::Equal, // Only NaN and infinities, handled above
+    }
+}
+
+// Walks the spine iteratively so long lists don't recurse once per element
+fn compare_lists(mut a: &Value, mut b: &Value) -> Ordering {
+    loop {
+        match (a, b) {
+            (Value::Pair(a_head, a_tail), Value::Pair(b_head, b_tail)) => {
+                let ordering = a_head.as_ref().cmp(b_head.as_ref());
+                if ordering != Ordering::Equal || Rc::ptr_eq(a_tail, b_tail) {
+                    return ordering;
+                }
+                a = a_tail;
+                b = b_tail;
+            }
+            _ => return a.cmp(b),
+        }
+    }
+}
+
+// RUST CONCEPT: Map keys need a total order
+// Only booleans, integers, strings and atoms can be keys. Every integer is
+// stored as a BigInt so 1 and 1n name the same entry.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MapKey {
+    Boolean(bool),
+    Integer(BigInt),
+    String(Rc<str>),
+    Atom(Rc<str>), // Both atoms and quoted atoms
+}
+
+impl MapKey {
+    pub fn from_value(value: &Value) -> Result<MapKey, RuntimeError> {
+        match value {
+            Value::Boolean(b) => Ok(MapKey::Boolean(*b)),
+            Value::Int32(i) => Ok(MapKey::Integer(BigInt::from(*i))),
+            Value::Integer(i) => Ok(MapKey::Integer(i.clone())),
+            Value::String(s) => Ok(MapKey::String(s.clone())),
+            Value::Atom(a, _) | Value::QuotedAtom(a) => Ok(MapKey::Atom(a.clone())),
+            other => Err(RuntimeError::TypeError(format!(
+                "map keys must be booleans, integers, strings or atoms, got {}",
+                other.type_name()
+            ))),
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            MapKey::Boolean(b) => Value::Boolean(*b),
+            MapKey::Integer(i) => Value::Integer(i.clone()).demote(),
+            MapKey::String(s) => Value::String(s.clone()),
+            MapKey::Atom(a) => Value::Atom(a.clone(), None),
+        }
+    }
+}
+
+impl fmt::Display for MapKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_value())
+    }
+}
+
 #[derive(Debug)]
 pub enum RuntimeError {
     StackUnderflow,
@@ -205,7 +465,109 @@ pub enum RuntimeError {
     DivisionByZero,
     ModuloByZero,
     DomainError(String),
+    Thrown(Value), // Value raised by `throw`, delivered unchanged to a `try` handler
+    // An error raised while executing a word that came from source text
+    // The evaluator wraps errors once, using the innermost located word
+    Located {
+        error: Box<RuntimeError>,
+        word: Rc<str>,
+        pos: SourcePos,
+        source: Option<Rc<str>>, // File or input name, if known
+        backtrace: Vec<crate::interpreter::CallFrame>, // Defined words active at the time, innermost last
+    },
     QuitRequested, // Special error to signal clean exit from REPL/script
+    FuelExhausted { steps: u64 }, // The execute call used up its fuel; like quit, try can't catch it
+    Interrupted, // The interpreter's cancel token was cancelled (e.g. Ctrl-C); try can't catch it either
+    Leave, // `leave` inside a quotation a builtin runs (e.g. map), heading for the loop around the builtin
+}
+
+impl RuntimeError {
+    // RUST CONCEPT: Stable, lowercase names for error kinds
+    // These are what `try` handlers see in the kind field of an error record
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RuntimeError::StackUnderflow | RuntimeError::StackUnderflowAt { .. } => {
+                "stack-underflow"
+            }
+            RuntimeError::TypeError(_) => "type-error",
+            RuntimeError::UndefinedWord(_) => "undefined-word",
+            RuntimeError::DivisionByZero => "division-by-zero",
+            RuntimeError::ModuloByZero => "modulo-by-zero",
+            RuntimeError::DomainError(_) => "domain-error",
+            RuntimeError::Thrown(_) => "thrown",
+            RuntimeError::Located { error, .. } => error.kind(),
+            RuntimeError::QuitRequested => "quit",
+            RuntimeError::FuelExhausted { .. } => "fuel-exhausted",
+            RuntimeError::Interrupted => "interrupted",
+            RuntimeError::Leave => "leave",
+        }
+    }
+
+    // RUST CONCEPT: Peeling off location wrappers
+    // Returns the underlying error so callers can match on what actually failed
+    pub fn root(&self) -> &RuntimeError {
+        match self {
+            RuntimeError::Located { error, .. } => error.root(),
+            other => other,
+        }
+    }
+
+    // Chain of defined words that led to this error, innermost last
+    pub fn backtrace(&self) -> &[crate::interpreter::CallFrame] {
+        match self {
+            RuntimeError::Located { backtrace, .. } => backtrace,
+            _ => &[],
+        }
+    }
+
+    // Source position attached to this error, if any
+    pub fn position(&self) -> Option<&SourcePos> {
+        match self {
+            RuntimeError::Located { pos, .. } | RuntimeError::StackUnderflowAt { pos, .. } => {
+                Some(pos)
+            }
+            _ => None,
+        }
+    }
+
+    // RUST CONCEPT: Converting an error into a first-class Uni value
+    // Thrown values are handed back as-is; built-in errors become an "error" record
+    // with fields kind, message, and position ([line column] or null)
+    pub fn into_value(self) -> Value {
+        let position = match self.position() {
+            Some(pos) => Value::Pair(
+                Rc::new(Value::Int32(pos.line as i32)),
+                Rc::new(Value::Pair(
+                    Rc::new(Value::Int32(pos.column as i32)),
+                    Rc::new(Value::Nil),
+                )),
+            ),
+            None => Value::Null,
+        };
+
+        // The message describes the failure itself; the location lives in position
+        let error = match self {
+            RuntimeError::Located { error, .. } => *error,
+            other => other,
+        };
+
+        match error {
+            RuntimeError::Located { .. } => error.into_value(),
+            RuntimeError::Thrown(value) => value,
+            error => {
+                let kind: Rc<str> = error.kind().into();
+                let message: Rc<str> = error.to_string().into();
+                Value::Record {
+                    type_name: "error".into(),
+                    fields: Rc::new(RefCell::new(vec![
+                        Value::String(kind),
+                        Value::String(message),
+                        position,
+                    ])),
+                }
+            }
+        }
+    }
 }
 
 // RUST CONCEPT: Implementing traits for custom error types
@@ -226,7 +588,36 @@ impl fmt::Display for RuntimeError {
             RuntimeError::DivisionByZero => write!(f, "Division by zero"),
             RuntimeError::ModuloByZero => write!(f, "Modulo by zero"),
             RuntimeError::DomainError(msg) => write!(f, "Domain error: {}", msg),
+            RuntimeError::Thrown(value) => write!(f, "Uncaught exception: {}", value),
+            RuntimeError::Located {
+                error,
+                word,
+                pos,
+                source,
+                ..
+            } => {
+                // Stack underflow already knows where it happened - avoid repeating it
+                match error.as_ref() {
+                    RuntimeError::StackUnderflowAt { context, .. } => {
+                        write!(f, "Stack underflow: {}", context)?
+                    }
+                    other => write!(f, "{}", other)?,
+                }
+                match source {
+                    Some(name) => write!(f, " (in '{}' at {}:{}:{})", word, name, pos.line, pos.column),
+                    None => write!(
+                        f,
+                        " (in '{}' at line {}, column {})",
+                        word, pos.line, pos.column
+                    ),
+                }
+            }
             RuntimeError::QuitRequested => write!(f, "Quit requested"),
+            RuntimeError::FuelExhausted { steps } => {
+                write!(f, "Fuel exhausted after {} steps", steps)
+            }
+            RuntimeError::Interrupted => write!(f, "Interrupted"),
+            RuntimeError::Leave => write!(f, "leave used outside of a loop"),
         }
     }
 }
@@ -279,7 +670,7 @@ impl fmt::Display for Value {
                     write!(f, "{}{}i", c.re, c.im)
                 }
             }
-            Value::Atom(a) => write!(f, "{}", a),
+            Value::Atom(a, _) => write!(f, "{}", a),
             Value::QuotedAtom(a) => write!(f, "'{}", a),
             Value::String(s) => write!(f, "\"{}\"", s), // Strings WITH quotes
             Value::Boolean(b) => write!(f, "{}", if *b { "true" } else { "false" }),
@@ -315,11 +706,24 @@ impl fmt::Display for Value {
                 }
                 write!(f, "]")
             }
+            Value::Map(map) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in map.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{} {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
             Value::Variable(cell) => {
                 write!(f, "<variable:{}>", cell.borrow())
             }
             Value::Nil => write!(f, "[]"),
             Value::AsyncBuiltin(_) => write!(f, "<builtin>"),
+            Value::Task(task) => write!(f, "#<task:{}>", task.id),
+            Value::Channel(channel) => write!(f, "#<channel:{}/{}>", channel.len(), channel.capacity()),
+            Value::Closure(closure) => write!(f, "#<closure:{}>", closure.body),
             // RUST CONCEPT: Display for record instances
             // Shows the type name and field values
             Value::Record { type_name, fields } => {
@@ -387,3 +791,115 @@ impl fmt::Display for Value {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(items: Vec<Value>) -> Value {
+        items
+            .into_iter()
+            .rev()
+            .fold(Value::Nil, |acc, v| Value::Pair(Rc::new(v), Rc::new(acc)))
+    }
+
+    fn vector(items: Vec<Value>) -> Value {
+        Value::Array(Rc::new(RefCell::new(items)))
+    }
+
+    #[test]
+    fn test_numbers_equal_across_the_tower() {
+        let one = [
+            Value::Int32(1),
+            Value::Integer(BigInt::from(1)),
+            Value::Rational(BigRational::new(BigInt::from(2), BigInt::from(2))),
+            Value::Number(1.0),
+        ];
+        for a in &one {
+            for b in &one {
+                assert_eq!(a, b);
+            }
+        }
+        assert!(Value::Rational(BigRational::new(BigInt::from(1), BigInt::from(3))) < Value::Number(0.5));
+        assert!(Value::Integer(BigInt::from(1) << 100) > Value::Int32(i32::MAX));
+        assert_eq!(Value::Number(0.0), Value::Number(-0.0));
+        assert_eq!(Value::Number(f64::NAN), Value::Number(f64::NAN));
+        assert!(Value::Number(f64::NAN) > Value::Number(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_big_integers_compare_exactly_with_floats() {
+        let two_53: BigInt = BigInt::from(1) << 53;
+        let a = Value::Integer(two_53.clone() + 1);
+        let b = Value::Integer(two_53);
+        let float = Value::Number(9007199254740992.0);
+
+        // 2^53 + 1 rounds to the float, but isn't equal to it
+        assert_eq!(b, float);
+        assert_ne!(a, float);
+        assert!(a > float);
+        assert_ne!(a, b);
+
+        let third = Value::Rational(BigRational::new(BigInt::from(1), BigInt::from(3)));
+        assert_ne!(third, Value::Number(1.0 / 3.0));
+        assert!(Value::Integer(BigInt::from(1) << 2000) < Value::Number(f64::INFINITY));
+        assert!(Value::Integer(BigInt::from(1) << 2000) < Value::Number(f64::NAN));
+
+        // Sorting a mix of them is consistent
+        let mut values = [a.clone(), float, Value::Number(9007199254740994.0)];
+        values.sort();
+        assert_eq!(values[2], Value::Number(9007199254740994.0));
+        assert_eq!(values[1], a);
+    }
+
+    #[test]
+    fn test_deep_equality_of_containers() {
+        let a = list(vec![Value::Int32(1), vector(vec![Value::String("x".into())])]);
+        let b = list(vec![Value::Number(1.0), vector(vec![Value::String("x".into())])]);
+        assert_eq!(a, b);
+
+        let record = |y: i32| Value::Record {
+            type_name: "point".into(),
+            fields: Rc::new(RefCell::new(vec![Value::Int32(3), Value::Int32(y)])),
+        };
+        assert_eq!(record(4), record(4));
+        assert!(record(4) < record(5));
+
+        let buffer = |samples: Vec<f32>| Value::F32Buffer(Rc::new(RefCell::new(samples)));
+        assert_eq!(buffer(vec![1.0, 2.0]), buffer(vec![1.0, 2.0]));
+        assert!(buffer(vec![1.0]) < buffer(vec![1.0, 0.0]));
+        assert_ne!(
+            Value::I32Buffer(Rc::new(RefCell::new(vec![1]))),
+            Value::I32Buffer(Rc::new(RefCell::new(vec![2])))
+        );
+    }
+
+    #[test]
+    fn test_lists_order_lexicographically() {
+        assert!(Value::Nil < list(vec![Value::Int32(0)]));
+        assert!(list(vec![Value::Int32(1), Value::Int32(2)]) < list(vec![Value::Int32(1), Value::Int32(3)]));
+        assert!(list(vec![Value::Int32(1)]) < list(vec![Value::Int32(1), Value::Int32(0)]));
+
+        // The spine is walked in a loop rather than recursing per element
+        let long = |last: i32| {
+            let mut items: Vec<Value> = (0..10_000).map(Value::Int32).collect();
+            items.push(Value::Int32(last));
+            list(items)
+        };
+        assert!(long(1) < long(2));
+    }
+
+    #[test]
+    fn test_kinds_are_totally_ordered() {
+        let mut values = [
+            Value::String("a".into()),
+            Value::Int32(5),
+            Value::Null,
+            Value::Boolean(true),
+            Value::Atom("a".into(), None),
+        ];
+        values.sort();
+        let shown: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        assert_eq!(shown, ["null", "true", "5", "\"a\"", "a"]);
+    }
+}
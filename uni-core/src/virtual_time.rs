@@ -0,0 +1,256 @@
+// Virtual time: a clock tests control, for running timed Uni code instantly
+//
+// While a VirtualClock is installed on a thread, every sleep that goes through
+// primitives/delay.rs on that thread (delay, after, every, and select's
+// timeouts) sleeps on it instead of on tokio's clock. Waits for a task or a
+// channel don't sleep at all, so they never give the clock a deadline of
+// their own. Virtual time only moves when the test says so:
+//
+// - run_until_idle lets every task run until they are all waiting on the clock
+// - advance_by moves the clock forward, stopping at each deadline on the way
+//   so timers fire in order and see the time they were due
+// - run drives a future (usually an execute_string call) to completion,
+//   jumping straight to the next deadline whenever everything is waiting
+//
+// "Everything is waiting" is worked out from activity: sleeps starting or
+// ending, tasks starting or finishing, and the evaluator yielding in a long
+// computation. A round of the executor in which none of that happens means
+// no task could make progress without the clock moving. Spawned tasks run in
+// a LocalSet, so the clock is driven from inside one too.
+
+use std::cell::{Cell, RefCell};
+use std::future::{poll_fn, Future};
+use std::pin::{pin, Pin};
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use tokio::time::{Duration, Instant};
+
+thread_local! {
+    static INSTALLED: RefCell<Option<Rc<ClockState>>> = const { RefCell::new(None) };
+}
+
+pub(crate) struct ClockState {
+    origin: Instant, // What virtual time zero stands for, so Instants can be used with it
+    now: Cell<u64>,  // Milliseconds since the clock was installed
+    sleepers: RefCell<Vec<Sleeper>>,
+    last_id: Cell<u64>,
+    activity: Cell<u64>,
+}
+
+struct Sleeper {
+    id: u64,
+    deadline: u64,
+    waker: Waker,
+}
+
+pub struct VirtualClock {
+    state: Rc<ClockState>,
+}
+
+impl VirtualClock {
+    // Makes waits on this thread use a new virtual clock, starting at zero,
+    // until the returned clock is dropped
+    pub fn install() -> Self {
+        let state = Rc::new(ClockState {
+            origin: Instant::now(),
+            now: Cell::new(0),
+            sleepers: RefCell::new(Vec::new()),
+            last_id: Cell::new(0),
+            activity: Cell::new(0),
+        });
+        INSTALLED.with(|installed| *installed.borrow_mut() = Some(state.clone()));
+        VirtualClock { state }
+    }
+
+    // Milliseconds of virtual time since the clock was installed
+    pub fn now_ms(&self) -> u64 {
+        self.state.now.get()
+    }
+
+    // Lets every task run until all of them are waiting on the clock
+    pub async fn run_until_idle(&self) {
+        loop {
+            let before = self.state.activity.get();
+            tokio::task::yield_now().await;
+            if self.state.activity.get() == before {
+                return;
+            }
+        }
+    }
+
+    // Moves the clock forward ms milliseconds, running everything that
+    // becomes due along the way
+    pub async fn advance_by(&self, ms: u64) {
+        let target = self.state.now.get() + ms;
+        loop {
+            self.run_until_idle().await;
+            match self.state.next_deadline() {
+                Some(deadline) if deadline <= target => self.state.set_now(deadline),
+                _ => break,
+            }
+        }
+        self.state.set_now(target);
+        self.run_until_idle().await;
+    }
+
+    // Runs future to completion, advancing the clock whenever nothing else
+    // can happen. Panics if the future is waiting on something other than
+    // the clock with no deadline left that could change that, such as a
+    // task and the script each waiting for the other.
+    pub async fn run<F: Future>(&self, future: F) -> F::Output {
+        let mut future = pin!(future);
+        loop {
+            let before = self.state.activity.get();
+            if let Poll::Ready(output) = poll_fn(|cx| Poll::Ready(future.as_mut().poll(cx))).await {
+                return output;
+            }
+            tokio::task::yield_now().await;
+            if self.state.activity.get() != before {
+                continue;
+            }
+            match self.state.next_deadline() {
+                Some(deadline) => self.state.set_now(deadline),
+                None => panic!("virtual clock: nothing is waiting on the clock, so the future can never finish"),
+            }
+        }
+    }
+}
+
+impl Drop for VirtualClock {
+    fn drop(&mut self) {
+        INSTALLED.with(|installed| {
+            let mut installed = installed.borrow_mut();
+            if installed.as_ref().is_some_and(|state| Rc::ptr_eq(state, &self.state)) {
+                *installed = None;
+            }
+        });
+    }
+}
+
+impl ClockState {
+    fn next_deadline(&self) -> Option<u64> {
+        self.sleepers.borrow().iter().map(|sleeper| sleeper.deadline).min()
+    }
+
+    fn set_now(&self, now: u64) {
+        self.now.set(now);
+        for sleeper in self.sleepers.borrow().iter() {
+            if sleeper.deadline <= now {
+                sleeper.waker.wake_by_ref();
+            }
+        }
+    }
+
+    pub(crate) fn now(&self) -> Instant {
+        self.origin + Duration::from_millis(self.now.get())
+    }
+
+    pub(crate) fn sleep_until(self: Rc<Self>, deadline: Instant) -> Sleep {
+        let deadline = deadline.saturating_duration_since(self.origin).as_millis() as u64;
+        Sleep {
+            clock: self,
+            deadline,
+            id: None,
+        }
+    }
+
+    fn remove(&self, id: u64) {
+        self.sleepers.borrow_mut().retain(|sleeper| sleeper.id != id);
+        note(self);
+    }
+}
+
+fn note(state: &ClockState) {
+    state.activity.set(state.activity.get() + 1);
+}
+
+// The clock waits on this thread should use, if one is installed
+pub(crate) fn installed() -> Option<Rc<ClockState>> {
+    INSTALLED.with(|installed| installed.borrow().clone())
+}
+
+// Records progress that waits on the clock wouldn't show, such as a task
+// finishing or a long computation yielding
+pub(crate) fn note_activity() {
+    if let Some(state) = installed() {
+        note(&state);
+    }
+}
+
+pub(crate) struct Sleep {
+    clock: Rc<ClockState>,
+    deadline: u64,
+    id: Option<u64>, // Set once the sleep is waiting
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.clock.now.get() >= self.deadline {
+            if let Some(id) = self.id.take() {
+                self.clock.remove(id);
+            }
+            return Poll::Ready(());
+        }
+
+        match self.id {
+            Some(id) => {
+                let mut sleepers = self.clock.sleepers.borrow_mut();
+                if let Some(sleeper) = sleepers.iter_mut().find(|sleeper| sleeper.id == id) {
+                    sleeper.waker.clone_from(cx.waker());
+                }
+            }
+            None => {
+                let id = self.clock.last_id.get() + 1;
+                self.clock.last_id.set(id);
+                self.clock.sleepers.borrow_mut().push(Sleeper {
+                    id,
+                    deadline: self.deadline,
+                    waker: cx.waker().clone(),
+                });
+                self.id = Some(id);
+                note(&self.clock);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            self.clock.remove(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_advance_wakes_sleeps_in_deadline_order() {
+        let clock = VirtualClock::install();
+        let state = installed().unwrap();
+        let order = RefCell::new(Vec::new());
+
+        let sleep_then_log = |ms: u64, name: &'static str| {
+            let sleep = state.clone().sleep_until(state.now() + Duration::from_millis(ms));
+            let order = &order;
+            let clock = &clock;
+            async move {
+                sleep.await;
+                order.borrow_mut().push((name, clock.now_ms()));
+            }
+        };
+        let advance = async {
+            clock.advance_by(25).await;
+        };
+        tokio::join!(sleep_then_log(20, "late"), sleep_then_log(10, "early"), advance);
+
+        assert_eq!(*order.borrow(), [("early", 10), ("late", 20)]);
+        assert_eq!(clock.now_ms(), 25);
+        assert!(state.sleepers.borrow().is_empty());
+    }
+}
@@ -0,0 +1,404 @@
+// Writer: turns data values back into Uni source text
+//
+// Display (value.rs) is for people: it abbreviates buffers, drops the type of
+// numbers (1.0 prints as 1) and doesn't escape strings. The writer is for
+// programs: for every data value v, parsing write_value(v) gives back v.
+//
+// Values with no literal syntax of their own use tagged literals that the
+// parser understands: #record[point 3 4], #record-type[point x y],
+// #i32[1 2 3] and #f32[0.5 1.0]; a record or field name that isn't a plain
+// atom is written as a string there. Builtins and variables are not data and
+// can't be written. A closure is written as its quotation, without the
+// locals it captured.
+
+use crate::compat::{fmt::Write, format, String, ToString};
+use crate::tokenizer::{tokenize, TokenKind};
+use crate::value::{RuntimeError, Value};
+
+pub fn write_value(value: &Value) -> Result<String, RuntimeError> {
+    let mut out = String::new();
+    write_into(&mut out, value)?;
+    Ok(out)
+}
+
+fn write_into(out: &mut String, value: &Value) -> Result<(), RuntimeError> {
+    match value {
+        Value::Number(n) => write_float(out, *n),
+        Value::Int32(i) => push_fmt(out, format_args!("{}", i)),
+        // The n suffix keeps small BigInts from reading back as Int32
+        Value::Integer(i) => push_fmt(out, format_args!("{}n", i)),
+        Value::Rational(r) => push_fmt(out, format_args!("{}/{}", r.numer(), r.denom())),
+        #[cfg(feature = "complex_numbers")]
+        Value::GaussianInt(re, im) => {
+            // Always write both parts so 0+1i doesn't come back as the atom i
+            let sign = if im.sign() == num_bigint::Sign::Minus { "" } else { "+" };
+            push_fmt(out, format_args!("{}{}{}i", re, sign, im))
+        }
+        #[cfg(feature = "complex_numbers")]
+        Value::Complex(c) => {
+            // The tokenizer reads exponents and the non-finite literals in
+            // either part; the imaginary part always needs its sign
+            write_float(out, c.re)?;
+            let imaginary = out.len();
+            write_float(out, c.im)?;
+            if !out[imaginary..].starts_with(['+', '-']) {
+                out.insert(imaginary, '+');
+            }
+            out.push('i');
+            Ok(())
+        }
+        Value::Atom(name, _) => {
+            out.push_str(name);
+            Ok(())
+        }
+        Value::QuotedAtom(name) => push_fmt(out, format_args!("'{}", name)),
+        Value::String(s) => {
+            write_string(out, s);
+            Ok(())
+        }
+        Value::Boolean(b) => {
+            out.push_str(if *b { "true" } else { "false" });
+            Ok(())
+        }
+        Value::Null => {
+            out.push_str("null");
+            Ok(())
+        }
+        Value::Nil => {
+            out.push_str("[]");
+            Ok(())
+        }
+        Value::Pair(car, cdr) => {
+            out.push('[');
+            write_into(out, car)?;
+            let mut current = cdr.as_ref();
+            loop {
+                match current {
+                    Value::Nil => break,
+                    Value::Pair(car, cdr) => {
+                        out.push(' ');
+                        write_into(out, car)?;
+                        current = cdr;
+                    }
+                    tail => {
+                        out.push_str(" | ");
+                        write_into(out, tail)?;
+                        break;
+                    }
+                }
+            }
+            out.push(']');
+            Ok(())
+        }
+        Value::Closure(closure) => write_into(out, &closure.body),
+        Value::Array(items) => {
+            out.push_str("#[");
+            write_separated(out, items.borrow().iter())?;
+            out.push(']');
+            Ok(())
+        }
+        Value::Map(map) => {
+            out.push('{');
+            for (i, (key, value)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push(' ');
+                }
+                write_into(out, &key.to_value())?;
+                out.push(' ');
+                write_into(out, value)?;
+            }
+            out.push('}');
+            Ok(())
+        }
+        Value::Record { type_name, fields } => {
+            out.push_str("#record[");
+            write_name(out, type_name);
+            for field in fields.borrow().iter() {
+                out.push(' ');
+                write_into(out, field)?;
+            }
+            out.push(']');
+            Ok(())
+        }
+        Value::RecordType {
+            type_name,
+            field_names,
+        } => {
+            out.push_str("#record-type[");
+            write_name(out, type_name);
+            for name in field_names.iter() {
+                out.push(' ');
+                write_name(out, name);
+            }
+            out.push(']');
+            Ok(())
+        }
+        Value::I32Buffer(buffer) => {
+            out.push_str("#i32[");
+            for (i, sample) in buffer.borrow().iter().enumerate() {
+                if i > 0 {
+                    out.push(' ');
+                }
+                push_fmt(out, format_args!("{}", sample))?;
+            }
+            out.push(']');
+            Ok(())
+        }
+        Value::F32Buffer(buffer) => {
+            out.push_str("#f32[");
+            for (i, sample) in buffer.borrow().iter().enumerate() {
+                if i > 0 {
+                    out.push(' ');
+                }
+                // Shortest text that reads back as the same f32
+                write_float_text(out, *sample as f64, &format!("{:?}", sample));
+            }
+            out.push(']');
+            Ok(())
+        }
+        Value::Variable(_) | Value::AsyncBuiltin(_) | Value::Task(_) | Value::Channel(_) => Err(RuntimeError::TypeError(format!(
+            "cannot write a {}: it is not data",
+            value.type_name()
+        ))),
+    }
+}
+
+fn write_separated<'a>(
+    out: &mut String,
+    items: impl Iterator<Item = &'a Value>,
+) -> Result<(), RuntimeError> {
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            out.push(' ');
+        }
+        write_into(out, item)?;
+    }
+    Ok(())
+}
+
+fn push_fmt(out: &mut String, args: core::fmt::Arguments<'_>) -> Result<(), RuntimeError> {
+    out.write_fmt(args)
+        .map_err(|_| RuntimeError::TypeError("failed to format value".to_string()))
+}
+
+// Debug formatting of f64 always keeps a '.' or exponent, so 1.0 stays a float
+fn write_float(out: &mut String, n: f64) -> Result<(), RuntimeError> {
+    write_float_text(out, n, &format!("{:?}", n));
+    Ok(())
+}
+
+fn write_float_text(out: &mut String, n: f64, text: &str) {
+    if n.is_nan() {
+        out.push_str("+nan.0");
+    } else if n.is_infinite() {
+        out.push_str(if n > 0.0 { "+inf.0" } else { "-inf.0" });
+    } else {
+        out.push_str(text);
+    }
+}
+
+// Record and field names are usually atoms, but make-record-type takes any
+// string; a name that wouldn't read back as that same atom is written as one
+fn write_name(out: &mut String, name: &str) {
+    let reads_as_atom = matches!(
+        tokenize(name).as_deref(),
+        Ok([token]) if matches!(&token.kind, TokenKind::Atom(atom) if atom == name)
+    );
+    if reads_as_atom {
+        out.push_str(name);
+    } else {
+        write_string(out, name);
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{{{:x}}}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compat::{BTreeMap, Rc, Vec, vec};
+    use crate::interpreter::AsyncInterpreter;
+    use crate::parser::read_value;
+    use crate::value::MapKey;
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+
+    #[cfg(not(target_os = "none"))]
+    use std::cell::RefCell;
+    #[cfg(target_os = "none")]
+    use core::cell::RefCell;
+
+    // Debug output names the variant and every component (but not atom spans),
+    // so equal Debug text means the values are structurally identical
+    fn assert_round_trip(value: Value) {
+        let mut interp = AsyncInterpreter::new();
+        let text = write_value(&value).unwrap();
+        let read = read_value(&text, &mut interp)
+            .unwrap_or_else(|e| panic!("{} did not parse: {}", text, e));
+        assert_eq!(format!("{:?}", read), format!("{:?}", value), "via {}", text);
+    }
+
+    fn list(items: Vec<Value>) -> Value {
+        items
+            .into_iter()
+            .rev()
+            .fold(Value::Nil, |acc, v| Value::Pair(Rc::new(v), Rc::new(acc)))
+    }
+
+    #[test]
+    fn test_round_trip_numbers() {
+        for n in [0.0, -0.0, 1.0, -2.5, 1e300, 1e-7, f64::MAX, f64::MIN_POSITIVE, 0.1 + 0.2] {
+            assert_round_trip(Value::Number(n));
+        }
+        assert_round_trip(Value::Number(f64::INFINITY));
+        assert_round_trip(Value::Number(f64::NEG_INFINITY));
+        assert_round_trip(Value::Int32(i32::MIN));
+        assert_round_trip(Value::Integer(BigInt::from(5)));
+        assert_round_trip(Value::Integer("-123456789012345678901234567890".parse().unwrap()));
+        assert_round_trip(Value::Rational(BigRational::new(BigInt::from(-3), BigInt::from(4))));
+        assert_round_trip(Value::Rational(BigRational::new(
+            "100000000000000000000001".parse().unwrap(),
+            "3".parse().unwrap(),
+        )));
+    }
+
+    #[test]
+    fn test_round_trip_nan() {
+        let mut interp = AsyncInterpreter::new();
+        let text = write_value(&Value::Number(f64::NAN)).unwrap();
+        assert!(matches!(read_value(&text, &mut interp), Ok(Value::Number(n)) if n.is_nan()));
+    }
+
+    #[cfg(feature = "complex_numbers")]
+    #[test]
+    fn test_round_trip_complex() {
+        use num_complex::Complex64;
+
+        assert_round_trip(Value::GaussianInt(BigInt::from(0), BigInt::from(1)));
+        assert_round_trip(Value::GaussianInt(BigInt::from(3), BigInt::from(-4)));
+        assert_round_trip(Value::GaussianInt(BigInt::from(7), BigInt::from(0)));
+        assert_round_trip(Value::Complex(Complex64::new(1.0, -2.5)));
+        assert_round_trip(Value::Complex(Complex64::new(0.0, 1.0)));
+        assert_round_trip(Value::Complex(Complex64::new(1e-7, 2.5)));
+        assert_round_trip(Value::Complex(Complex64::new(-1e300, -1e-300)));
+        assert_round_trip(Value::Complex(Complex64::new(f64::INFINITY, 1.0)));
+        assert_round_trip(Value::Complex(Complex64::new(f64::NEG_INFINITY, f64::NEG_INFINITY)));
+        assert_round_trip(Value::Complex(Complex64::new(2.0, f64::INFINITY)));
+
+        // NaN parts come back as NaN
+        let mut interp = AsyncInterpreter::new();
+        for c in [Complex64::new(f64::NAN, 1.0), Complex64::new(1.0, f64::NAN)] {
+            let text = write_value(&Value::Complex(c)).unwrap();
+            match read_value(&text, &mut interp) {
+                Ok(Value::Complex(read)) => {
+                    assert_eq!(read.re.is_nan(), c.re.is_nan(), "via {}", text);
+                    assert_eq!(read.im.is_nan(), c.im.is_nan(), "via {}", text);
+                }
+                other => panic!("{} read back as {:?}", text, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_strings_and_atoms() {
+        assert_round_trip(Value::String("plain".into()));
+        assert_round_trip(Value::String("quote \" backslash \\ tab\t cr\r nl\n bell\u{7} é→".into()));
+        assert_round_trip(Value::String("".into()));
+        assert_round_trip(Value::Atom("foo".into(), None));
+        assert_round_trip(Value::QuotedAtom("bar".into()));
+        assert_round_trip(Value::Boolean(false));
+        assert_round_trip(Value::Null);
+    }
+
+    #[test]
+    fn test_round_trip_collections() {
+        assert_round_trip(Value::Nil);
+        assert_round_trip(list(vec![
+            Value::Int32(1),
+            list(vec![Value::String("x".into()), Value::Nil]),
+            Value::QuotedAtom("q".into()),
+        ]));
+        assert_round_trip(Value::Pair(Rc::new(Value::Int32(1)), Rc::new(Value::Int32(2))));
+        assert_round_trip(Value::Array(Rc::new(RefCell::new(vec![
+            Value::Number(1.0),
+            Value::Array(Rc::new(RefCell::new(vec![]))),
+        ]))));
+
+        let mut entries = BTreeMap::new();
+        entries.insert(MapKey::Integer(BigInt::from(1)), Value::Null);
+        entries.insert(MapKey::String("s".into()), list(vec![Value::Int32(2)]));
+        entries.insert(MapKey::Atom("a".into()), Value::Boolean(true));
+        assert_round_trip(Value::Map(Rc::new(entries)));
+    }
+
+    #[test]
+    fn test_round_trip_records_and_buffers() {
+        assert_round_trip(Value::Record {
+            type_name: "point".into(),
+            fields: Rc::new(RefCell::new(vec![Value::Int32(3), Value::String("4".into())])),
+        });
+        assert_round_trip(Value::RecordType {
+            type_name: "point".into(),
+            field_names: Rc::new(vec!["x".into(), "y".into()]),
+        });
+        // Names that aren't plain atoms
+        assert_round_trip(Value::Record {
+            type_name: "my pt".into(),
+            fields: Rc::new(RefCell::new(vec![Value::Int32(1)])),
+        });
+        assert_round_trip(Value::RecordType {
+            type_name: "true".into(),
+            field_names: Rc::new(vec!["x y".into(), "'q".into(), "]".into()]),
+        });
+        assert_eq!(
+            write_value(&Value::RecordType {
+                type_name: "my pt".into(),
+                field_names: Rc::new(vec!["x".into()]),
+            })
+            .unwrap(),
+            "#record-type[\"my pt\" x]"
+        );
+
+        assert_round_trip(Value::I32Buffer(Rc::new(RefCell::new(vec![
+            i32::MIN,
+            0,
+            i32::MAX,
+        ]))));
+        assert_round_trip(Value::F32Buffer(Rc::new(RefCell::new(vec![
+            0.1,
+            -1.0,
+            f32::MAX,
+            f32::INFINITY,
+        ]))));
+
+        // Buffers longer than the Display preview are written in full
+        let long: Vec<i32> = (0..20).collect();
+        assert_round_trip(Value::I32Buffer(Rc::new(RefCell::new(long))));
+    }
+
+    #[test]
+    fn test_non_data_values_are_rejected() {
+        let variable = Value::Variable(Rc::new(RefCell::new(Value::Int32(1))));
+        assert!(matches!(write_value(&variable), Err(RuntimeError::TypeError(_))));
+        assert!(matches!(
+            write_value(&list(vec![variable])),
+            Err(RuntimeError::TypeError(_))
+        ));
+    }
+}
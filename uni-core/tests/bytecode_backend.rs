@@ -0,0 +1,8 @@
+// The integration suite again, on interpreters using the bytecode backend
+
+use uni_core::interpreter::Backend;
+
+const BACKEND: Backend = Backend::Bytecode;
+
+#[path = "integration_tests.rs"]
+mod integration_tests;
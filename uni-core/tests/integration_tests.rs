@@ -3,11 +3,23 @@
 // by parsing and evaluating hardcoded code strings rather than files
 
 use uni_core::evaluator::execute_string;
-use uni_core::interpreter::AsyncInterpreter;
+use uni_core::interpreter::{AsyncInterpreter, Backend};
 use uni_core::value::{RuntimeError, Value};
+use uni_core::virtual_time::VirtualClock;
 
-async fn setup_interpreter() -> AsyncInterpreter {
+// tests/bytecode_backend.rs runs this whole file again as a module with its
+// own BACKEND, so every test here checks both backends
+#[allow(dead_code)]
+const BACKEND: Backend = Backend::TreeWalker;
+
+fn new_interpreter() -> AsyncInterpreter {
     let mut interp = AsyncInterpreter::new();
+    interp.backend = crate::BACKEND;
+    interp
+}
+
+async fn setup_interpreter() -> AsyncInterpreter {
+    let mut interp = new_interpreter();
     interp.load_prelude().await.expect("Failed to load prelude");
     interp
 }
@@ -1019,3 +1031,1632 @@ async fn test_while_loop_with_accumulator() {
         result
     );
 }
+
+// RUST CONCEPT: Exception handling tests
+// Tests for try/throw and recovery from runtime errors
+
+#[tokio::test]
+async fn test_try_without_error_runs_body_only() {
+    let code = r#"
+        [ 2 3 + ] [ drop 0 ] try
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(5)), "Expected 5, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_try_catches_division_by_zero() {
+    let code = r#"
+        [ 1 0 / ] [ error-kind ] try
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(
+        matches!(&result, Value::String(s) if s.as_ref() == "division-by-zero"),
+        "Expected division-by-zero kind, got {:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_try_error_record_fields() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        "[ undefined-thing ] [ dup error? swap dup error-message swap error-position ] try",
+        &mut interp,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(interp.stack.len(), 3);
+    assert!(matches!(interp.stack[0], Value::Boolean(true)));
+    assert!(matches!(&interp.stack[1], Value::String(s) if s.contains("undefined-thing")));
+    // position is [line column] of the failing word
+    assert_eq!(interp.stack[2].to_string(), "[1 3]");
+}
+
+#[tokio::test]
+async fn test_try_restores_stack_depth() {
+    // Values pushed by the body before the error are discarded,
+    // values below the try are preserved
+    let mut interp = setup_interpreter().await;
+    execute_string("10 20 [ 1 2 3 1 0 / ] [ drop 99 ] try", &mut interp)
+        .await
+        .unwrap();
+
+    assert_eq!(interp.stack.len(), 3);
+    assert!(matches!(interp.stack[0], Value::Int32(10)));
+    assert!(matches!(interp.stack[1], Value::Int32(20)));
+    assert!(matches!(interp.stack[2], Value::Int32(99)));
+}
+
+#[tokio::test]
+async fn test_try_restores_return_stack_and_locals() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        "[ 5 >r 7 'x lval 1 0 / ] [ drop 'done ] try",
+        &mut interp,
+    )
+    .await
+    .unwrap();
+
+    assert!(interp.return_stack.is_empty());
+    assert!(interp.local_frames.is_empty());
+    assert!(matches!(interp.stack.last(), Some(Value::Atom(a, _)) if a.as_ref() == "done"));
+}
+
+#[tokio::test]
+async fn test_throw_arbitrary_value() {
+    let code = r#"
+        [ "custom failure" throw 42 ] [ ] try
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(
+        matches!(&result, Value::String(s) if s.as_ref() == "custom failure"),
+        "Expected thrown string, got {:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_throw_from_nested_definitions() {
+    let code = r#"
+        'inner [ 'bad-input throw ] def
+        'middle [ 1 inner 2 ] def
+        'outer [ middle 3 ] def
+        [ outer ] [ ] try
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(
+        matches!(&result, Value::Atom(a, _) if a.as_ref() == "bad-input"),
+        "Expected bad-input, got {:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_nested_try_rethrow() {
+    let code = r#"
+        [
+            [ 1 0 / ] [ throw ] try
+        ] [ error-kind ] try
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(
+        matches!(&result, Value::String(s) if s.as_ref() == "division-by-zero"),
+        "Expected rethrown error, got {:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_uncaught_throw_propagates() {
+    let result = execute_and_get_top("42 throw").await;
+    let error = result.unwrap_err();
+    assert!(matches!(error.root(), RuntimeError::Thrown(Value::Int32(42))));
+}
+
+#[tokio::test]
+async fn test_try_does_not_catch_quit() {
+    let result = execute_and_get_top("[ quit ] [ drop 1 ] try").await;
+    assert!(matches!(result, Err(RuntimeError::QuitRequested)));
+}
+
+#[tokio::test]
+async fn test_try_inside_loop() {
+    // Errors caught inside a loop body don't disturb the loop
+    let code = r#"
+        0 'caught var
+        0 'n var
+        [ n @ 5 < ] [
+            [ n @ 2 mod 0 = [ 'even throw ] [ ] if ]
+            [ drop caught @ 1 + caught ! ]
+            try
+            n @ 1 + n !
+        ] while
+        caught @
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(3)), "Expected 3 catches, got {:?}", result);
+}
+
+// RUST CONCEPT: Source location tests
+// Runtime errors should say where the failing word was written
+
+#[tokio::test]
+async fn test_error_reports_line_column_and_word() {
+    let result = execute_and_get_top("1 2 +\n  5 0 /").await;
+    match result {
+        Err(RuntimeError::Located { error, word, pos, source, .. }) => {
+            assert!(matches!(*error, RuntimeError::DivisionByZero));
+            assert_eq!(word.as_ref(), "/");
+            assert_eq!((pos.line, pos.column), (2, 7));
+            assert!(source.is_none());
+        }
+        other => panic!("Expected located error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_error_inside_definition_points_at_definition() {
+    let code = r#"
+        'explode [
+            1 nosuchword
+        ] def
+        explode
+    "#;
+    let error = execute_and_get_top(code).await.unwrap_err();
+    assert!(matches!(error.root(), RuntimeError::UndefinedWord(w) if w == "nosuchword"));
+    let pos = error.position().expect("error should carry a position");
+    assert_eq!((pos.line, pos.column), (3, 15));
+}
+
+#[tokio::test]
+async fn test_error_message_includes_source_name() {
+    let mut interp = setup_interpreter().await;
+    interp.source_name = Some("script.uni".into());
+    let error = execute_string("1 0 mod", &mut interp).await.unwrap_err();
+    let message = error.to_string();
+    assert!(
+        message == "Modulo by zero (in 'mod' at script.uni:1:5)",
+        "Unexpected message: {}",
+        message
+    );
+}
+
+#[tokio::test]
+async fn test_generated_code_errors_reported_at_call_site() {
+    // Record accessors are generated code; the error should point at the user's call
+    let code = r#"
+        ["x"] "point" make-record-type drop
+        42 point-x
+    "#;
+    let error = execute_and_get_top(code).await.unwrap_err();
+    match error {
+        RuntimeError::Located { word, pos, .. } => {
+            assert_eq!(word.as_ref(), "point-x");
+            assert_eq!(pos.line, 3);
+        }
+        other => panic!("Expected located error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_stack_underflow_reports_position_once() {
+    let error = execute_and_get_top("3 *").await.unwrap_err();
+    assert!(matches!(error.root(), RuntimeError::StackUnderflowAt { .. }));
+    assert_eq!(
+        error.to_string(),
+        "Stack underflow: '*' requires exactly 2 values on the stack (e.g., '4 3 *') (in '*' at line 1, column 3)"
+    );
+}
+
+// RUST CONCEPT: Backtrace tests
+// Errors carry the chain of defined words that led to them
+
+#[tokio::test]
+async fn test_backtrace_lists_defined_words_innermost_last() {
+    let mut interp = setup_interpreter().await;
+    interp.source_name = Some("bt.uni".into());
+    let code = "'inner [ 1 0 / ] def\n'middle [ inner 1 + ] def\n'outer [ middle 2 * ] def\nouter";
+    let error = execute_string(code, &mut interp).await.unwrap_err();
+
+    let frames: Vec<String> = error.backtrace().iter().map(|f| f.to_string()).collect();
+    assert_eq!(
+        frames,
+        [
+            "outer (called at bt.uni:4:1)",
+            "middle (called at bt.uni:3:10)",
+            "inner (called at bt.uni:2:11)",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_backtrace_shows_collapsed_tail_calls() {
+    let code = r#"
+        'fail [ 1 0 / ] def
+        'countdown [ dup 0 = [ drop fail ] [ 1 - countdown ] if ] def
+        'start [ 3 countdown 1 + ] def
+        start
+    "#;
+    let error = execute_and_get_top(code).await.unwrap_err();
+    let backtrace = error.backtrace();
+    assert_eq!(backtrace.len(), 2);
+    assert_eq!(backtrace[0].word.as_ref(), "start");
+    assert_eq!(backtrace[1].word.as_ref(), "fail");
+    assert_eq!(backtrace[1].tail_calls, 4);
+    assert!(backtrace[1].to_string().ends_with("[4 tail calls collapsed]"));
+}
+
+#[tokio::test]
+async fn test_top_level_error_has_empty_backtrace() {
+    let error = execute_and_get_top("1 0 /").await.unwrap_err();
+    assert!(error.backtrace().is_empty());
+}
+
+// RUST CONCEPT: Counted loop tests
+// times, do, loop, range-each, i/j and leave are evaluator special forms
+
+#[tokio::test]
+async fn test_times_runs_body_n_times() {
+    let code = r#"
+        0 5 [ 2 + ] times
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(10)), "Expected 10, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_times_zero_and_negative_counts() {
+    let mut interp = setup_interpreter().await;
+    execute_string("0 [ 99 ] times -3 [ 99 ] times", &mut interp).await.unwrap();
+    assert!(interp.stack.is_empty());
+}
+
+#[tokio::test]
+async fn test_times_exposes_index_as_i() {
+    // 0 + 1 + 2 + 3 = 6
+    let code = "0 4 [ i + ] times";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(6)), "Expected 6, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_do_loop_with_start_and_limit() {
+    // i runs 3, 4, 5, 6
+    let mut interp = setup_interpreter().await;
+    execute_string("7 3 [ i ] do", &mut interp).await.unwrap();
+    let indices: Vec<String> = interp.stack.iter().map(|v| v.to_string()).collect();
+    assert_eq!(indices, ["3", "4", "5", "6"]);
+}
+
+#[tokio::test]
+async fn test_nested_do_with_i_and_j() {
+    // Sum of j * 10 + i for j in 0..2, i in 0..3 = 0+1+2+10+11+12 = 36
+    let code = r#"
+        0
+        2 0 [
+            3 0 [ j 10 * i + + ] do
+        ] do
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(36)), "Expected 36, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_range_each_pushes_index() {
+    let mut interp = setup_interpreter().await;
+    execute_string("-2 2 [ 10 * ] range-each", &mut interp).await.unwrap();
+    let values: Vec<String> = interp.stack.iter().map(|v| v.to_string()).collect();
+    assert_eq!(values, ["-20", "-10", "0", "10"]);
+}
+
+#[tokio::test]
+async fn test_loop_until_leave() {
+    let code = r#"
+        [ i 5 = [ i leave ] [ ] if ] loop
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(5)), "Expected 5, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_leave_exits_only_innermost_loop() {
+    // The inner loop stops at i = 2 each time; the outer loop runs all 3 times
+    let code = r#"
+        0
+        3 [
+            10 [ i 2 = [ leave ] [ 1 + ] if ] times
+        ] times
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(6)), "Expected 6, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_leave_from_called_word_restores_frames() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        r#"
+        'stop-at-3 [ 42 'unused lval i 3 = [ leave ] [ ] if ] def
+        0 'count var
+        100 [ stop-at-3 count @ 1 + count ! ] times
+        count @
+        "#,
+        &mut interp,
+    )
+    .await
+    .unwrap();
+
+    assert!(matches!(interp.stack.last(), Some(Value::Int32(3))));
+    assert!(interp.local_frames.is_empty());
+    assert!(interp.call_frames.is_empty());
+    assert!(interp.loop_indices.is_empty());
+}
+
+#[tokio::test]
+async fn test_loop_body_sees_enclosing_locals() {
+    let code = r#"
+        'scaled-sum [
+            10 'factor lval
+            0 4 [ i factor * + ] times
+        ] def
+        scaled-sum
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(60)), "Expected 60, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_loop_locals_are_per_iteration() {
+    // lval inside the body binds in the iteration's own frame
+    let code = r#"
+        0 3 [ i 'k lval k + ] times
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(3)), "Expected 3, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_i_outside_loop_is_ordinary_word() {
+    // The index binding only exists while a loop body runs
+    let code = r#"
+        7 'i var
+        2 [ ] times
+        i @
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(7)), "Expected 7, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_leave_outside_loop_is_error() {
+    for code in ["leave", "[1 2] [leave] map"] {
+        let error = execute_and_get_top(code).await.unwrap_err();
+        assert!(
+            matches!(error.root(), RuntimeError::DomainError(msg) if msg.contains("outside of a loop")),
+            "{}: got {:?}",
+            code,
+            error
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_leave_through_quotation_run_by_builtin() {
+    // The loop is outside the evaluation map runs its quotation in
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        "[ [1 2 3] [ dup 2 = [leave] [] if ] map drop ] loop  'after",
+        &mut interp,
+    )
+    .await
+    .unwrap();
+    assert_eq!(interp.stack.len(), 2);
+    assert!(matches!(interp.stack[0], Value::Int32(2)));
+    assert!(interp.loop_indices.is_empty());
+    assert!(interp.local_frames.is_empty());
+
+    // try inside the quotation doesn't catch it
+    let code = "[ [1 2] [ [leave] [drop 99] try ] map ] loop";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(1)), "Expected 1, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_loop_words_cannot_be_redefined() {
+    for code in ["'times [drop] def", "'leave 1 val", "0 'do var", "[ 1 'loop lval ] exec"] {
+        let error = execute_and_get_top(code).await.unwrap_err();
+        assert!(
+            matches!(error.root(), RuntimeError::DomainError(msg) if msg.contains("can't be redefined")),
+            "{}: got {:?}",
+            code,
+            error
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_error_in_loop_caught_by_try() {
+    let mut interp = setup_interpreter().await;
+    execute_string("[ 5 [ i 2 = [ 1 0 / ] [ ] if ] times ] [ error-kind ] try", &mut interp)
+        .await
+        .unwrap();
+    assert!(matches!(interp.stack.last(), Some(Value::String(s)) if s.as_ref() == "division-by-zero"));
+    assert!(interp.loop_indices.is_empty());
+    assert!(interp.local_frames.is_empty());
+}
+
+// RUST CONCEPT: Sequence library tests
+// Tests for the native higher-order words over lists and vectors
+
+#[tokio::test]
+async fn test_map_filter_fold_pipeline() {
+    let code = r#"
+        1 11 range
+        [ dup * ] map
+        [ 2 mod 0 = ] filter
+        0 [ + ] fold
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(220)), "Expected 220, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_map_preserves_vector_shape() {
+    let mut interp = setup_interpreter().await;
+    execute_string("#[1 2 3] [ 10 * ] map", &mut interp).await.unwrap();
+    match interp.stack.last() {
+        Some(Value::Array(items)) => {
+            let items = items.borrow();
+            assert_eq!(items.len(), 3);
+            assert!(matches!(items[2], Value::Int32(30)));
+        }
+        other => panic!("Expected vector, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_map_runs_async_words() {
+    // Quotations run through the evaluator, so async builtins work inside them
+    let code = "[ 1 2 ] [ 1 delay 1 + ] map 0 [ + ] fold";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(5)), "Expected 5, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_error_in_map_caught_by_try() {
+    let mut interp = setup_interpreter().await;
+    execute_string("[ [ 1 0 ] [ 1 swap / ] map ] [ error-kind ] try", &mut interp)
+        .await
+        .unwrap();
+    assert!(matches!(interp.stack.last(), Some(Value::String(s)) if s.as_ref() == "division-by-zero"));
+    assert_eq!(interp.stack.len(), 1);
+}
+
+#[tokio::test]
+async fn test_find_any_all() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        "[ 1 5 9 ] [ 4 > ] find  [ 1 5 9 ] [ 10 > ] find  [ 1 2 ] [ 2 = ] any?  [ 1 2 ] [ 2 = ] all?",
+        &mut interp,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(interp.stack[0], Value::Int32(5)));
+    assert!(matches!(interp.stack[1], Value::Null));
+    assert!(matches!(interp.stack[2], Value::Boolean(true)));
+    assert!(matches!(interp.stack[3], Value::Boolean(false)));
+}
+
+#[tokio::test]
+async fn test_sort_and_sort_by() {
+    let mut interp = setup_interpreter().await;
+    execute_string("[ 3 1 2 ] sort  [ 3 -1 -2 ] [ abs ] sort-by", &mut interp)
+        .await
+        .unwrap();
+    assert_eq!(interp.stack[0].to_string(), "[1 2 3]");
+    assert_eq!(interp.stack[1].to_string(), "[-1 -2 3]");
+}
+
+#[tokio::test]
+async fn test_sequence_quotations_must_leave_one_value() {
+    // Eating into the caller's stack, or leaving strays, is caught instead of corrupting it
+    for code in [
+        "5 [1 2] [drop] map",
+        "[1 2 3] [dup] map",
+        "[1 2] 0 [+ 1] fold",
+        "[1 2] [drop drop] reduce",
+        "[1 2] [drop true false] filter",
+        "[1 2] [dup 1 =] find",
+        "[1 2] [drop] any?",
+        "[1 2] [dup] all?",
+        "[2 1] [drop] sort-by",
+    ] {
+        let error = execute_and_get_top(code).await.unwrap_err();
+        assert!(
+            matches!(error.root(), RuntimeError::TypeError(msg) if msg.contains("quotation")),
+            "{}: expected a stack effect error, got {:?}",
+            code,
+            error
+        );
+    }
+
+    // Quotations that replace their arguments with one value leave the rest alone
+    let mut interp = setup_interpreter().await;
+    execute_string("5 [1 2] [1 +] map [3 4] 0 [+] fold", &mut interp)
+        .await
+        .unwrap();
+    assert_eq!(interp.stack.len(), 3);
+    assert!(matches!(interp.stack[0], Value::Int32(5)));
+    assert_eq!(interp.stack[1].to_string(), "[2 3]");
+    assert!(matches!(interp.stack[2], Value::Int32(7)));
+}
+
+#[tokio::test]
+async fn test_reduce_empty_is_error() {
+    let error = execute_and_get_top("[ ] [ + ] reduce").await.unwrap_err();
+    assert!(matches!(error.root(), RuntimeError::TypeError(msg) if msg.contains("empty")));
+}
+
+// RUST CONCEPT: Map tests
+// Tests for the { key value } literal and the map-* words
+
+#[tokio::test]
+async fn test_map_literal_lookup() {
+    let code = r#"
+        { name "Ada" born 1815 } 'born map-get
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(1815)), "Expected 1815, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_map_put_is_non_destructive() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        "'m { a 1 } def  m 'b 2 map-put map-size  m map-size",
+        &mut interp,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(interp.stack[0], Value::Int32(2)));
+    assert!(matches!(interp.stack[1], Value::Int32(1)));
+}
+
+#[tokio::test]
+async fn test_map_equality_and_type() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        "{ a 1 b [2] } { b [2] a 1 } =  { a 1 } { a 2 } !=  { } type-of",
+        &mut interp,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(interp.stack[0], Value::Boolean(true)));
+    assert!(matches!(interp.stack[1], Value::Boolean(true)));
+    assert!(matches!(&interp.stack[2], Value::String(s) if s.as_ref() == "map"));
+}
+
+#[tokio::test]
+async fn test_map_each_sums_values() {
+    let code = "0 { a 1 b 2 c 3 } [ swap drop + ] map-each";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(6)), "Expected 6, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_map_display_matches_literal_syntax() {
+    let mut interp = setup_interpreter().await;
+    execute_string("{ \"k\" [1 2] 2 true x null }", &mut interp).await.unwrap();
+    let shown = interp.stack.last().unwrap().to_string();
+    assert_eq!(shown, "{2 true \"k\" [1 2] x null}");
+}
+
+// RUST CONCEPT: String library tests
+// Tests for the character-based string words
+
+#[tokio::test]
+async fn test_parse_serial_line() {
+    // A typical protocol line: split, trim and re-join fields
+    let code = r#"
+        "  temp=21.5 ; hum=40 \n" trim ";" split [ trim "=" split car ] map "," join
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(&result, Value::String(s) if s.as_ref() == "temp,hum"), "got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_string_words_are_unicode_aware() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        r#""naïve café" 6 10 substring  "naïve café" "é" index-of  "ÉCOLE" lower"#,
+        &mut interp,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(&interp.stack[0], Value::String(s) if s.as_ref() == "café"));
+    assert!(matches!(interp.stack[1], Value::Int32(9)));
+    assert!(matches!(&interp.stack[2], Value::String(s) if s.as_ref() == "école"));
+}
+
+#[tokio::test]
+async fn test_string_predicates_and_replace() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        r#""AT+OK" "AT" starts-with?  "AT+OK" "OK" ends-with?  "a b c" " " "_" replace"#,
+        &mut interp,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(interp.stack[0], Value::Boolean(true)));
+    assert!(matches!(interp.stack[1], Value::Boolean(true)));
+    assert!(matches!(&interp.stack[2], Value::String(s) if s.as_ref() == "a_b_c"));
+}
+
+// RUST CONCEPT: Reader tests
+// Tests for string->number and read
+
+#[tokio::test]
+async fn test_string_to_number_from_input() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        r#""17" string->number 1 +  "3/4" string->number  "oops" string->number"#,
+        &mut interp,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(interp.stack[0], Value::Int32(18)));
+    assert!(matches!(interp.stack[1], Value::Rational(_)));
+    assert!(matches!(interp.stack[2], Value::Null));
+}
+
+#[tokio::test]
+async fn test_read_does_not_execute() {
+    let mut interp = setup_interpreter().await;
+    execute_string(r#""[ 1 2 + ]" read dup length swap exec"#, &mut interp)
+        .await
+        .unwrap();
+    assert!(matches!(interp.stack[0], Value::Int32(3)));
+    assert!(matches!(interp.stack[1], Value::Int32(3)));
+}
+
+#[tokio::test]
+async fn test_read_error_is_catchable() {
+    let code = r#"[ "[1 2" read ] [ error-kind ] try"#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(&result, Value::String(s) if s.as_ref() == "type-error"));
+}
+
+#[test]
+fn test_read_value_public_api() {
+    let mut interp = new_interpreter();
+    let value = uni_core::read_value("{ a [1 2] }", &mut interp).unwrap();
+    assert_eq!(value.to_string(), "{a [1 2]}");
+}
+
+// RUST CONCEPT: Writer round-trip tests
+// ->source and read should be inverses for data values
+
+#[tokio::test]
+async fn test_source_read_round_trip_in_uni() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        r##"
+        ["x" "y"] "pt" make-record-type drop
+        [ 1.0 2n 3/4 "a\"b" #[1 [2 | 3]] { k 'v } ] 1 2 make-pt cons
+        dup ->source read ->source swap ->source =
+        "#i32[1 2 3]" read i32-length nip
+        "#f32[1 2.5]" read ->source
+        "#record[pt 1 2]" read pt-y
+        "#record-type[pt x y]" read type-of
+        "#record[pt]" read
+    "##,
+        &mut interp,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(interp.stack[0], Value::Boolean(true)));
+    assert_eq!(interp.stack[1].to_string(), "3");
+    assert!(matches!(&interp.stack[2], Value::String(s) if s.as_ref() == "#f32[1.0 2.5]"));
+    assert!(matches!(interp.stack[3], Value::Int32(2)));
+    assert!(matches!(&interp.stack[4], Value::String(s) if s.as_ref() == "record-type"));
+    assert!(matches!(&interp.stack[5], Value::Record { .. }));
+}
+
+#[tokio::test]
+async fn test_source_of_variable_is_error() {
+    let code = "[ 'x 1 var x ->source ] [ error-kind ] try";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(&result, Value::String(s) if s.as_ref() == "type-error"));
+}
+
+// RUST CONCEPT: Structural comparison tests
+// =, != and the ordering words compare any two values by contents
+
+#[tokio::test]
+async fn test_structural_equality_in_uni() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        r#"
+        [1 [2 3]] [1 [2 3]] =
+        #[1 2] #[1 2.0] =
+        1 1.0 =
+        1/2 0.5 =
+        ["x"] "pt" make-record-type drop
+        5 make-pt 5 make-pt =
+        [1 2] [1 3] !=
+        "#,
+        &mut interp,
+    )
+    .await
+    .unwrap();
+    for (i, value) in interp.stack.iter().enumerate() {
+        assert!(matches!(value, Value::Boolean(true)), "comparison {} was {:?}", i, value);
+    }
+    assert_eq!(interp.stack.len(), 6);
+}
+
+#[tokio::test]
+async fn test_ordering_words_on_any_values() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        r#""apple" "banana" <  [1 2] [1 2 0] <  3/4 1 <=  1n 1.0 >=  "a" 1 >"#,
+        &mut interp,
+    )
+    .await
+    .unwrap();
+    for (i, value) in interp.stack.iter().enumerate() {
+        assert!(matches!(value, Value::Boolean(true)), "comparison {} was {:?}", i, value);
+    }
+}
+
+#[test]
+fn test_values_compare_from_rust() {
+    let mut interp = new_interpreter();
+    let a = uni_core::read_value("[1 #[2 3] { k 1/2 }]", &mut interp).unwrap();
+    let b = uni_core::read_value("[1.0 #[2 3] { k 0.5 }]", &mut interp).unwrap();
+    let c = uni_core::read_value("[1 #[2 4]]", &mut interp).unwrap();
+    assert_eq!(a, b);
+    assert!(a < c);
+    assert_eq!(a.cmp(&c), std::cmp::Ordering::Less);
+}
+
+// RUST CONCEPT: Compiled definition tests
+// Definitions run as compiled code with cached lookups; these check that the
+// caching never changes what a program means
+
+#[tokio::test]
+async fn test_callers_see_redefined_words() {
+    let mut interp = setup_interpreter().await;
+    execute_string(
+        r#"
+        'helper [1] def
+        'caller [helper 10 +] def
+        caller
+        'helper [2] def
+        caller
+        'k 5 val
+        'read-k [k] def
+        read-k
+        'k 6 val
+        read-k
+        "#,
+        &mut interp,
+    )
+    .await
+    .unwrap();
+    let results: Vec<String> = interp.stack.iter().map(|v| v.to_string()).collect();
+    assert_eq!(results, ["11", "12", "5", "6"]);
+}
+
+#[tokio::test]
+async fn test_locals_shadow_words_in_compiled_code() {
+    let code = r#"
+        'x [100] def
+        'shadow [7 'x lval x x +] def
+        shadow x +
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(114)), "Expected 114, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_if_inside_compiled_loop() {
+    let code = r#"
+        'evens [0 swap [i 2 mod 0 = [1 +] [] if] times] def
+        1000 evens
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(500)), "Expected 500, got {:?}", result);
+}
+
+#[tokio::test]
+async fn test_compiled_code_runs_quotations_built_at_runtime() {
+    // The same exec site is handed a different list each time round
+    let code = r#"
+        'run-with [1 swap cons exec] def
+        [2 +] run-with
+        [10 *] run-with
+        +
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(13)), "Expected 13, got {:?}", result);
+}
+
+// ASYNC CONCEPT: Task handle tests
+// spawn uses spawn_local, so these run inside a LocalSet like the CLI does
+async fn execute_with_tasks(code: &str) -> Result<Value, RuntimeError> {
+    tokio::task::LocalSet::new().run_until(execute_and_get_top(code)).await
+}
+
+// Timed code runs on a VirtualClock instead, so it finishes at once and
+// always sees the same interleaving; also gives the time it finished at
+async fn execute_on_virtual_time(code: &str) -> (Result<Value, RuntimeError>, u64) {
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let clock = VirtualClock::install();
+            let mut interp = setup_interpreter().await;
+            let result = clock.run(execute_string(code, &mut interp)).await;
+            (result.and_then(|()| interp.pop()), clock.now_ms())
+        })
+        .await
+}
+
+#[tokio::test]
+async fn test_task_join_returns_final_stack() {
+    let code = "[2 3 + 10 delay 7] spawn task-join";
+    let (result, now) = execute_on_virtual_time(code).await;
+    assert_eq!(result.unwrap().to_string(), "[5 7]");
+    assert_eq!(now, 10);
+}
+
+#[tokio::test]
+async fn test_task_status_follows_the_task() {
+    let code = r#"
+        [20 delay] spawn
+        dup task-status swap
+        dup task-join drop task-status
+        2 list
+    "#;
+    let (result, now) = execute_on_virtual_time(code).await;
+    assert_eq!(result.unwrap().to_string(), r#"["running" "done"]"#);
+    assert_eq!(now, 20);
+}
+
+#[tokio::test]
+async fn test_task_join_raises_task_error() {
+    let code = "[[1 0 /] spawn task-join] [error-kind] try";
+    let result = execute_with_tasks(code).await.unwrap();
+    assert!(matches!(result, Value::String(ref s) if s.as_ref() == "division-by-zero"));
+
+    let code = "[[\"boom\" throw] spawn task-join] [] try";
+    let result = execute_with_tasks(code).await.unwrap();
+    assert!(matches!(result, Value::String(ref s) if s.as_ref() == "boom"));
+}
+
+#[tokio::test]
+async fn test_cancel_stops_a_running_task() {
+    let code = r#"
+        [[true] [] while] spawn
+        dup cancel
+        dup [task-join] [error-kind] try
+        swap task-status
+        2 list
+    "#;
+    let result = execute_with_tasks(code).await.unwrap();
+    assert_eq!(result.to_string(), r#"["interrupted" "cancelled"]"#);
+}
+
+#[tokio::test]
+async fn test_tasks_registry_lists_running_tasks() {
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let clock = VirtualClock::install();
+            let mut interp = setup_interpreter().await;
+            execute_string("[60000 delay] spawn [1] spawn task-join drop", &mut interp)
+                .await
+                .unwrap();
+
+            // The finished task has left the registry; the sleeping one remains
+            let running = interp.tasks.running();
+            assert_eq!(running.len(), 1);
+            assert_eq!(running[0].id, 1);
+            assert_eq!(running[0].origin.to_string(), "[60000 delay]");
+
+            execute_string("cancel", &mut interp).await.unwrap();
+            clock.run_until_idle().await;
+            assert!(interp.tasks.running().is_empty());
+            assert_eq!(clock.now_ms(), 0);
+        })
+        .await;
+}
+
+// ASYNC CONCEPT: Channel tests
+// Channels carry values between spawned tasks, so these also run in a LocalSet
+#[tokio::test]
+async fn test_channel_passes_values_between_tasks() {
+    let code = r#"
+        'ch 3 make-channel val
+        [1 ch send 2 ch send 3 ch send] spawn drop
+        ch receive ch receive ch receive
+        3 list
+    "#;
+    let result = execute_with_tasks(code).await.unwrap();
+    assert_eq!(result.to_string(), "[1 2 3]");
+}
+
+#[tokio::test]
+async fn test_full_channel_suspends_sender() {
+    let code = r#"
+        'ch 1 make-channel val
+        [1 ch send 2 ch send "sent"] spawn
+        30 delay
+        dup task-status swap
+        ch receive drop ch receive drop
+        task-join
+        2 list
+    "#;
+    // Nothing else can run, so the sender is still waiting when the delay ends
+    let (result, now) = execute_on_virtual_time(code).await;
+    assert_eq!(result.unwrap().to_string(), r#"["running" ["sent"]]"#);
+    assert_eq!(now, 30);
+}
+
+#[tokio::test]
+async fn test_try_receive_does_not_wait() {
+    let code = r#"
+        'ch 2 make-channel val
+        ch try-receive
+        5 ch send ch try-receive
+        4 list
+    "#;
+    let result = execute_with_tasks(code).await.unwrap();
+    assert_eq!(result.to_string(), "[null false 5 true]");
+
+    let result = execute_with_tasks("2 make-channel 7 over send").await.unwrap();
+    assert_eq!(result.to_string(), "#<channel:1/2>");
+
+    for code in ["0 make-channel", "4000000000000000000 make-channel"] {
+        let error = execute_with_tasks(code).await.unwrap_err();
+        assert!(matches!(error.root(), RuntimeError::DomainError(_)), "{}: got {:?}", code, error);
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_interrupts_blocked_receive() {
+    let code = r#"
+        'ch 1 make-channel val
+        [ch receive] spawn
+        dup cancel
+        [task-join] [error-kind] try
+    "#;
+    let result = execute_with_tasks(code).await.unwrap();
+    assert!(matches!(result, Value::String(ref s) if s.as_ref() == "interrupted"));
+}
+
+// ASYNC CONCEPT: Timer tests
+// after and every start tasks, so their handles work with task-join and cancel
+#[tokio::test]
+async fn test_after_runs_quotation_once_later() {
+    let code = r#"
+        30 [2 3 +] after
+        dup task-status swap
+        task-join
+        2 list
+    "#;
+    let (result, now) = execute_on_virtual_time(code).await;
+    assert_eq!(result.unwrap().to_string(), r#"["running" [5]]"#);
+    assert_eq!(now, 30);
+}
+
+#[tokio::test]
+async fn test_every_repeats_until_cancelled() {
+    let code = r#"
+        0 'ticks var
+        20 [ticks @ 1 + ticks !] every
+        110 delay
+        cancel
+        ticks @
+    "#;
+    // Ticks at 20, 40, 60, 80 and 100
+    let (result, now) = execute_on_virtual_time(code).await;
+    assert!(matches!(result, Ok(Value::Int32(5))), "expected 5 ticks, got {:?}", result);
+    assert_eq!(now, 110);
+}
+
+#[tokio::test]
+async fn test_cancelled_timer_never_runs() {
+    let code = r#"
+        0 'fired var
+        20 [1 fired !] after
+        dup cancel
+        [task-join] [error-kind] try
+        50 delay
+        fired @
+        2 list
+    "#;
+    let (result, now) = execute_on_virtual_time(code).await;
+    assert_eq!(result.unwrap().to_string(), r#"["interrupted" 0]"#);
+    assert_eq!(now, 50);
+
+    let error = execute_with_tasks("0 [] every").await.unwrap_err();
+    assert!(matches!(error.root(), RuntimeError::DomainError(_)));
+}
+
+// ASYNC CONCEPT: Virtual time tests
+// A VirtualClock stands in for the real one, so timed code runs instantly
+// and in the same order every time
+async fn top_of(code: &str, interp: &mut AsyncInterpreter) -> String {
+    execute_string(code, interp).await.unwrap();
+    interp.pop().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_virtual_delay_finishes_instantly() {
+    let clock = VirtualClock::install();
+    let started = std::time::Instant::now();
+    let mut interp = setup_interpreter().await;
+
+    clock.run(execute_string("60000 delay", &mut interp)).await.unwrap();
+    assert_eq!(clock.now_ms(), 60000);
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_virtual_time_orders_tasks_by_deadline() {
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let clock = VirtualClock::install();
+            let mut interp = setup_interpreter().await;
+            let code = r#"
+                [] 'log var
+                [30 delay 'slow log @ cons log !] spawn drop
+                [10 delay 'fast log @ cons log !] spawn drop
+            "#;
+            execute_string(code, &mut interp).await.unwrap();
+
+            clock.advance_by(29).await;
+            assert_eq!(top_of("log @", &mut interp).await, "[fast]");
+            clock.advance_by(1).await;
+            assert_eq!(top_of("log @", &mut interp).await, "[slow fast]");
+
+            // run jumps the clock to whatever the joined task is waiting for
+            let joined = clock.run(execute_string("[50 delay 7] spawn task-join", &mut interp)).await;
+            joined.unwrap();
+            assert_eq!(interp.pop().unwrap().to_string(), "[7]");
+            assert_eq!(clock.now_ms(), 80);
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_virtual_time_counts_timer_ticks_exactly() {
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let clock = VirtualClock::install();
+            let mut interp = setup_interpreter().await;
+            let code = "0 'ticks var 'ticker 100 [ticks @ 1 + ticks !] every val";
+            execute_string(code, &mut interp).await.unwrap();
+
+            clock.advance_by(1000).await;
+            assert_eq!(top_of("ticks @", &mut interp).await, "10");
+            clock.advance_by(99).await;
+            assert_eq!(top_of("ticks @", &mut interp).await, "10");
+            clock.advance_by(1).await;
+            assert_eq!(top_of("ticks @", &mut interp).await, "11");
+
+            execute_string("ticker cancel", &mut interp).await.unwrap();
+            clock.advance_by(1000).await;
+            assert_eq!(top_of("ticks @", &mut interp).await, "11");
+            assert_eq!(top_of("ticker task-status", &mut interp).await, r#""cancelled""#);
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_virtual_time_shows_every_skipping_missed_ticks() {
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let clock = VirtualClock::install();
+            let mut interp = setup_interpreter().await;
+            // Each run takes 250 ms, so after the tick at 100 the ones at 200
+            // and 300 are missed and the next starts at 400, on the grid
+            let code = "0 'ticks var 100 [ticks @ 1 + ticks ! 250 delay] every drop";
+            execute_string(code, &mut interp).await.unwrap();
+
+            clock.advance_by(1000).await;
+            assert_eq!(top_of("ticks @", &mut interp).await, "4");
+        })
+        .await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "nothing is waiting on the clock")]
+async fn test_virtual_time_run_reports_deadlock() {
+    // The task and the script each wait for the other; no deadline can change that
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let clock = VirtualClock::install();
+            let mut interp = setup_interpreter().await;
+            let code = r#"
+                'inbox 1 make-channel val
+                'worker [inbox receive] spawn val
+                worker task-join
+            "#;
+            let _ = clock.run(execute_string(code, &mut interp)).await;
+        })
+        .await;
+}
+
+// ASYNC CONCEPT: Select tests
+// Run on virtual time, so which event comes first never depends on timing
+
+#[tokio::test]
+async fn test_select_runs_handler_of_first_event() {
+    let code = r#"
+        'inbox 2 make-channel val
+        [20 delay 42 inbox send] spawn drop
+        [[inbox [1 +]] [100 ["timeout"]]] select
+    "#;
+    let (result, now) = execute_on_virtual_time(code).await;
+    assert!(matches!(result, Ok(Value::Int32(43))));
+    assert_eq!(now, 20);
+}
+
+#[tokio::test]
+async fn test_select_timeout_leaves_channel_untouched() {
+    let code = r#"
+        'inbox 2 make-channel val
+        [200 delay 42 inbox send] spawn drop
+        [[inbox [drop "message"]] [100 ["timeout"]]] select
+        300 delay
+        inbox try-receive drop
+        2 list
+    "#;
+    let (result, now) = execute_on_virtual_time(code).await;
+    assert_eq!(result.unwrap().to_string(), r#"["timeout" 42]"#);
+    assert_eq!(now, 400);
+}
+
+#[tokio::test]
+async fn test_select_on_tasks_and_clause_order() {
+    let code = r#"
+        'job [30 delay 5] spawn val
+        [[job []] [100 ["late"]]] select
+    "#;
+    let (result, now) = execute_on_virtual_time(code).await;
+    assert_eq!(result.unwrap().to_string(), "[5]");
+    assert_eq!(now, 30);
+
+    // When several sources are ready at once, the first clause wins
+    let code = r#"
+        'a 1 make-channel val 'b 1 make-channel val
+        "from b" b send "from a" a send
+        [[b []] [a []]] select
+    "#;
+    let (result, _) = execute_on_virtual_time(code).await;
+    assert!(matches!(result, Ok(Value::String(ref s)) if s.as_ref() == "from b"));
+
+    let code = r#"
+        'a 1 make-channel val 'b 1 make-channel val
+        "from b" b send "from a" a send
+        [[a []] [b []]] select
+    "#;
+    let (result, _) = execute_on_virtual_time(code).await;
+    assert!(matches!(result, Ok(Value::String(ref s)) if s.as_ref() == "from a"));
+
+    // A waiting clause is skipped, and the next ready one wins over later ones
+    let code = r#"
+        'a 1 make-channel val 'empty 1 make-channel val
+        "from a" a send
+        [[empty []] [0 ["timeout"]] [a []]] select
+        a try-receive drop
+        2 list
+    "#;
+    let (result, _) = execute_on_virtual_time(code).await;
+    assert_eq!(result.unwrap().to_string(), r#"["timeout" "from a"]"#);
+}
+
+#[tokio::test]
+async fn test_select_rejects_malformed_clauses() {
+    let (result, _) = execute_on_virtual_time("[] select").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::DomainError(_)));
+
+    let (result, _) = execute_on_virtual_time("[[100 \"not code\"]] select").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::TypeError(_)));
+
+    let (result, _) = execute_on_virtual_time("[[\"nope\" []]] select").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::TypeError(_)));
+}
+
+// RUST CONCEPT: Closure tests
+// Quotations that mention bound locals keep them after the frame is gone
+#[tokio::test]
+async fn test_closure_returned_from_definition() {
+    let code = r#"
+        'adder ['n lval [n +]] def
+        'add5 5 adder def
+        10 add5
+        1 2 adder exec
+        2 list
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), "[15 3]");
+
+    let result = execute_and_get_top("'adder ['n lval [n +]] def 5 adder").await.unwrap();
+    assert_eq!(result.to_string(), "#<closure:[n +]>");
+}
+
+#[tokio::test]
+async fn test_closure_shares_captured_lvar() {
+    let code = r#"
+        'make-counter [0 'count lvar [count @ 1 + dup count !]] def
+        'next make-counter def
+        next drop next drop next
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(3)));
+}
+
+#[tokio::test]
+async fn test_closure_as_loop_body_and_task() {
+    let code = "'times-n ['n lval 0 3 [n +] times] def 4 times-n";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(12)));
+
+    let code = "'compute [6 'x lval [x 7 *] spawn task-join] def compute";
+    let result = execute_with_tasks(code).await.unwrap();
+    assert_eq!(result.to_string(), "[42]");
+}
+
+#[tokio::test]
+async fn test_quotations_without_locals_stay_lists() {
+    let code = "'f [1 'x lval [1 2 3] length] def f";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(3)));
+
+    // Nothing is captured outside a definition either
+    let result = execute_and_get_top("[x 1 +]").await.unwrap();
+    assert!(matches!(result, Value::Pair(_, _)));
+}
+
+#[tokio::test]
+async fn test_lists_naming_locals_are_still_data() {
+    // Inside a word that binds x, [x 1 2] is a closure, but reads as the list it holds
+    let result = execute_and_get_top("'f [5 'x lval [x 1 2] length] def f").await.unwrap();
+    assert!(matches!(result, Value::Int32(3)));
+
+    let result = execute_and_get_top("'f [5 'x lval [x 1 2] cdr car] def f").await.unwrap();
+    assert!(matches!(result, Value::Int32(1)));
+
+    let result = execute_and_get_top("'f [5 'x lval [x 1 2] [type-of] map] def f").await.unwrap();
+    assert_eq!(result.to_string(), "[\"atom\" \"int32\" \"int32\"]");
+
+    let result = execute_and_get_top("'f [5 'x lval [x 1 2]] def f [x 1 2] =").await.unwrap();
+    assert!(matches!(result, Value::Boolean(true)));
+
+    let result = execute_and_get_top("'f [5 'x lval [x 1 2] ->source] def f").await.unwrap();
+    assert!(matches!(result, Value::String(s) if &*s == "[x 1 2]"));
+
+    // Every loop iteration binds i
+    let result = execute_and_get_top("0 3 [[i j] length +] times").await.unwrap();
+    assert!(matches!(result, Value::Int32(6)));
+}
+
+#[tokio::test]
+async fn test_deep_tail_recursion_with_named_parameters() {
+    let code = "'sum-to [| n acc | n 0 = [acc] [n 1 - acc n + sum-to] if] def 10000 0 sum-to";
+    let mut interp = setup_interpreter().await;
+    execute_string(code, &mut interp).await.unwrap();
+    assert!(matches!(interp.pop().unwrap(), Value::Int32(50005000)));
+    assert!(interp.local_frames.is_empty());
+
+    // The branch refers to n, and still leaves one frame per word
+    let code = "'cnt [| n | n 0 = [undefined-word] [n 1 - cnt] if] def 10000 cnt";
+    let error = execute_and_get_top(code).await.unwrap_err();
+    let backtrace = error.backtrace();
+    assert_eq!(backtrace.len(), 1);
+    assert_eq!(backtrace[0].tail_calls, 10000);
+}
+
+// RUST CONCEPT: Named parameter tests
+// [| a b | body] pops its arguments into locals before the body runs
+#[tokio::test]
+async fn test_named_parameters_in_definition() {
+    let code = "'hyp [| a b | a a * b b * + sqrt] def 3 4 hyp";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Number(n) if n == 5.0));
+
+    // The first parameter is the deepest argument
+    let code = "'minus [| a b | a b -] def 10 3 minus";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(7)));
+}
+
+#[tokio::test]
+async fn test_named_parameters_in_quotations_and_closures() {
+    let result = execute_and_get_top("2 5 [| x y | y x -] exec").await.unwrap();
+    assert!(matches!(result, Value::Int32(3)));
+
+    let code = "'scaler [| k | [k *]] def 'triple 3 scaler def 7 triple";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(21)));
+
+    // Parameters are locals only while their body runs
+    let result = execute_and_get_top("'f [| n | n] def 1 f n").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::UndefinedWord(_)));
+}
+
+#[tokio::test]
+async fn test_named_parameters_in_branches() {
+    // A branch with a header gets a frame of its own, even at top level
+    let mut interp = setup_interpreter().await;
+    execute_string("3 4 true [| a b | a b +] [] if", &mut interp).await.unwrap();
+    assert_eq!(interp.stack.len(), 1);
+    assert!(matches!(interp.pop().unwrap(), Value::Int32(7)));
+    assert!(interp.local_frames.is_empty());
+
+    // Its parameters shadow the caller's locals rather than overwrite them
+    let code = "'f [| a | 3 4 a 0 > [| a b | a b -] [] if a 2 list] def 10 f";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), "[-1 10]");
+
+    let code = "'g [| n | 2 n [1 [| n | n n *] [n]] case n 2 list] def 1 g";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), "[4 1]");
+}
+
+#[tokio::test]
+async fn test_named_parameters_need_their_arguments() {
+    let result = execute_and_get_top("'hyp [| a b | a b +] def 1 hyp").await;
+    assert!(matches!(
+        result.unwrap_err().root(),
+        RuntimeError::StackUnderflow | RuntimeError::StackUnderflowAt { .. }
+    ));
+}
+
+// RUST CONCEPT: Macro tests
+// Immediate words rewrite the code after them before it runs
+const WHEN_MACRO: &str = r#"
+    'when [dup cdr swap car [] 'if 3 list swap append] def 'when immediate
+"#;
+
+#[tokio::test]
+async fn test_macro_rewrites_following_code() {
+    let code = format!("{} 'sign [0 > when [\"positive\"]] def 5 sign", WHEN_MACRO);
+    let result = execute_and_get_top(&code).await.unwrap();
+    assert!(matches!(result, Value::String(s) if &*s == "positive"));
+
+    // The body was expanded once, when it was defined
+    let code = format!("{} 'f [true when [1]] def", WHEN_MACRO);
+    let mut interp = setup_interpreter().await;
+    execute_string(&code, &mut interp).await.unwrap();
+    let name = interp.intern_atom("f");
+    let body = interp.dict_get(&name).unwrap().value;
+    assert_eq!(body.to_string(), "[true [1] [] if]");
+
+    // At top level the rest of the source is the code after it
+    let code = format!("{} 1 false when [drop 2] 10", WHEN_MACRO);
+    let result = execute_and_get_top(&code).await.unwrap();
+    assert!(matches!(result, Value::Int32(10)));
+}
+
+#[tokio::test]
+async fn test_macro_can_expand_into_macro() {
+    let code = format!(
+        "{} 'unless ['when swap cons 'not swap cons] def 'unless immediate false unless [42]",
+        WHEN_MACRO
+    );
+    let result = execute_and_get_top(&code).await.unwrap();
+    assert!(matches!(result, Value::Int32(42)));
+}
+
+#[tokio::test]
+async fn test_macro_must_leave_code() {
+    let result = execute_and_get_top("'bad [drop 7] def 'bad immediate 1 bad 2").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::TypeError(_)));
+
+    let result = execute_and_get_top("'x 1 val 'x immediate").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::TypeError(_)));
+}
+
+// RUST CONCEPT: Quasiquote tests
+// `[...] templates are filled in with the values their unquotes leave
+#[tokio::test]
+async fn test_quasiquote_fills_template() {
+    let result = execute_and_get_top("2 3 `[,[+] *]").await.unwrap();
+    assert_eq!(result.to_string(), "[5 *]");
+
+    let code = "'adder [| n | `[,n +]] def 5 adder";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), "[5 +]");
+
+    let code = "'wrap [| items | `[begin ,@items [,items] end]] def [1 2] wrap";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), "[begin 1 2 [[1 2]] end]");
+}
+
+#[tokio::test]
+async fn test_quasiquote_builds_runnable_code() {
+    let code = r#"
+        'make-scaler [| k | `[,k *]] def
+        'triple 3 make-scaler def
+        7 triple
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(21)));
+
+    let result = execute_and_get_top("'f [| x | `[,@x]] def 5 f").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::TypeError(_)));
+}
+
+#[tokio::test]
+async fn test_record_words_are_filled_templates() {
+    let mut interp = setup_interpreter().await;
+    execute_string("[\"x\" \"y\"] \"point\" make-record-type drop", &mut interp)
+        .await
+        .unwrap();
+    let body = |interp: &mut AsyncInterpreter, name: &str| {
+        let name = interp.intern_atom(name);
+        interp.dict_get(&name).unwrap().value.to_string()
+    };
+    assert_eq!(body(&mut interp, "make-point"), "[2 \"point\" construct-record]");
+    assert_eq!(body(&mut interp, "point-y"), "[\"point\" 1 get-record-field]");
+    assert_eq!(body(&mut interp, "point-x!"), "[\"point\" 0 set-record-field!]");
+}
+
+// RUST CONCEPT: Multi-way conditional tests
+// cond runs the body of the first truthy test, case the body of the first matching key
+#[tokio::test]
+async fn test_cond_picks_first_truthy_test() {
+    let code = r#"
+        'sign [[[dup 0 <] [drop "neg"] [dup 0 =] [drop "zero"] [drop "pos"]] cond] def
+        -3 sign 0 sign 8 sign 3 list
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), "[\"neg\" \"zero\" \"pos\"]");
+
+    // Without a default, nothing runs when no test passes
+    let result = execute_and_get_top("1 [[false] [2]] cond").await.unwrap();
+    assert!(matches!(result, Value::Int32(1)));
+}
+
+#[tokio::test]
+async fn test_cond_and_case_see_locals() {
+    let code = r#"
+        'classify [| n | [[n 10 <] ["small"] [n 100 <] ["medium"] ["large"]] cond] def
+        50 classify
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::String(s) if &*s == "medium"));
+
+    let code = "'twice-if-one [| n | n [1 [n n +]] case] def 1 twice-if-one";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(2)));
+}
+
+#[tokio::test]
+async fn test_cond_branch_is_a_tail_call() {
+    let code = r#"
+        'countdown [[[dup 0 =] [drop 99] [1 - countdown]] cond] def
+        2000 countdown
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(99)));
+}
+
+#[tokio::test]
+async fn test_case_dispatches_on_keys() {
+    let code = r#"
+        'next-state [[idle ['running] running ['stopped] ['idle]] case] def
+        'idle next-state next-state next-state
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), "idle");
+
+    let code = r#"
+        'size [[0 ["none"] 1 ["one"] "many" ["named"] ["lots"]] case] def
+        0 size 1 size "many" size 7 size 4 list
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), "[\"none\" \"one\" \"named\" \"lots\"]");
+}
+
+#[tokio::test]
+async fn test_case_compares_list_keys_whole() {
+    // A list key is a value to compare with =, not a set of alternatives
+    let code = r#"
+        'shape [[[1 2] ["list"] 1 ["one"] [] ["empty"] ["other"]] case] def
+        [1 2] shape 1 shape 2 shape [] shape 4 list
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), r#"["list" "one" "other" "empty"]"#);
+}
+
+#[tokio::test]
+async fn test_malformed_clause_lists() {
+    let result = execute_and_get_top("[[false] [1] | 2] cond").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::TypeError(_)));
+
+    let result = execute_and_get_top("1 5 case").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::TypeError(_)));
+}
+
+// RUST CONCEPT: Pattern matching tests
+// match runs the body of the first pattern that fits, with its bindings as locals
+#[tokio::test]
+async fn test_match_destructures_lists() {
+    let code = r#"
+        'describe [[
+            [] ["empty"]
+            [x] [x]
+            [x y | rest] [rest length x y + +]
+        ] match] def
+        [] describe [7] describe [1 2 3 4] describe 3 list
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), "[\"empty\" 7 5]");
+
+    let code = r#"
+        'sum [[[] [] [x | rest] [x + rest sum]] match] def
+        'sum-list [0 swap sum] def
+        [1 2 3 4] sum-list
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(10)));
+}
+
+#[tokio::test]
+async fn test_match_literals_vectors_and_records() {
+    let code = r#"
+        'shape [[
+            ['circle r] [r r * 3 *]
+            ['rect w h] [w h *]
+            _ [0]
+        ] match] def
+        [circle 2] shape [rect 3 4] shape "other" shape 3 list
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), "[12 12 0]");
+
+    let result = execute_and_get_top("#[1 2] [#[a b] [b a -]] match").await.unwrap();
+    assert!(matches!(result, Value::Int32(1)));
+
+    let code = r#"
+        ["x" "y"] "point" make-record-type drop
+        3 4 make-point
+        [#record[size w] [w] #record[point x y] [x y *]] match
+    "#;
+    let result = execute_and_get_top(code).await.unwrap();
+    assert!(matches!(result, Value::Int32(12)));
+}
+
+#[tokio::test]
+async fn test_match_sees_locals_and_reports_no_match() {
+    let code = "'add-to [| k | [[a b] [a k + b k +]] match] def [1 2] 10 add-to 2 list";
+    let result = execute_and_get_top(code).await.unwrap();
+    assert_eq!(result.to_string(), "[11 12]");
+
+    let result = execute_and_get_top("5 [\"five\" [1]] match").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::DomainError(_)));
+
+    let result = execute_and_get_top("5 [_] match").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::TypeError(_)));
+
+    // Special forms can't be bound, as with lval
+    let result = execute_and_get_top("[5] [[if] [if]] match").await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::DomainError(_)));
+}
+
+#[tokio::test]
+async fn test_failed_match_body_drops_its_bindings() {
+    let mut interp = setup_interpreter().await;
+    let result = execute_string("[1 2] [[a b] [a 0 /]] match", &mut interp).await;
+    assert!(matches!(result.unwrap_err().root(), RuntimeError::DivisionByZero));
+    assert!(interp.local_frames.is_empty());
+
+    // a was only bound inside the body
+    interp.stack.clear();
+    assert!(execute_string("a", &mut interp).await.is_err());
+}